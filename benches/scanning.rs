@@ -1,13 +1,67 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use memory_mcp::core::types::Address;
+use memory_mcp::memory::{
+    MemoryScanner, ScanOptions, ScanPattern, SnapshotProcess, SnapshotRegion,
+};
 
-fn benchmark_placeholder(c: &mut Criterion) {
-    c.bench_function("placeholder_scan", |b| {
+const REGION_SIZE: usize = 16 * 1024 * 1024;
+const PAGE_READWRITE: u32 = 0x04;
+
+/// A single 16 MB region of pseudo-random bytes with a few planted matches
+fn snapshot() -> SnapshotProcess {
+    let mut state = 0x2545F491u32;
+    let mut data: Vec<u8> = (0..REGION_SIZE)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            state as u8
+        })
+        .collect();
+
+    for offset in [0x1000, REGION_SIZE / 2, REGION_SIZE - 0x1000] {
+        data[offset..offset + 7].copy_from_slice(&[0x48, 0x8B, 0x05, 0x10, 0x20, 0x30, 0x89]);
+    }
+
+    let mut process = SnapshotProcess::new(1);
+    process
+        .add_region(SnapshotRegion::new(
+            Address::new(0x10000),
+            data,
+            PAGE_READWRITE,
+        ))
+        .unwrap();
+    process
+}
+
+fn benchmark_scanning(c: &mut Criterion) {
+    let process = snapshot();
+    let scanner = MemoryScanner::new(&process);
+    let exact = ScanPattern::Exact(vec![0x48, 0x8B, 0x05, 0x10, 0x20, 0x30, 0x89]);
+    let masked = ScanPattern::from_hex_string("48 8B 05 ?? ?? ?? 89").unwrap();
+    let sequential = ScanOptions {
+        parallel: false,
+        ..ScanOptions::default()
+    };
+
+    c.bench_function("scan_exact_sequential", |b| {
+        b.iter(|| scanner.scan(black_box(&exact), sequential.clone()).unwrap())
+    });
+    c.bench_function("scan_masked_sequential", |b| {
+        b.iter(|| {
+            scanner
+                .scan(black_box(&masked), sequential.clone())
+                .unwrap()
+        })
+    });
+    c.bench_function("scan_masked_parallel", |b| {
         b.iter(|| {
-            // TODO: Implement actual scanning benchmark
-            black_box(42);
-        });
+            scanner
+                .scan(black_box(&masked), ScanOptions::default())
+                .unwrap()
+        })
     });
 }
 
-criterion_group!(benches, benchmark_placeholder);
+criterion_group!(benches, benchmark_scanning);
 criterion_main!(benches);
@@ -8,6 +8,7 @@ pub struct ConfigDefaults {
     pub server: ServerDefaults,
     pub scanner: ScannerDefaults,
     pub memory: MemoryDefaults,
+    pub policy: PolicyDefaults,
     pub logging: LoggingDefaults,
 }
 
@@ -25,6 +26,9 @@ pub struct ScannerDefaults {
     pub max_threads: usize,
     pub chunk_size: usize,
     pub cache_size: usize,
+    pub snapshot_memory_limit: usize,
+    pub materialize_threshold: usize,
+    pub session_ttl_secs: u64,
 }
 
 /// Default memory configuration
@@ -33,6 +37,16 @@ pub struct MemoryDefaults {
     pub max_read_size: usize,
     pub enable_write_protection: bool,
     pub backup_before_write: bool,
+    pub watch_interval_ms: u64,
+}
+
+/// Default process access policy
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolicyDefaults {
+    pub allow: Vec<String>,
+    pub deny: Vec<String>,
+    pub read_only: Vec<String>,
+    pub allow_system_processes: bool,
 }
 
 /// Default logging configuration
@@ -52,13 +66,23 @@ pub fn default_config() -> ConfigDefaults {
         },
         scanner: ScannerDefaults {
             max_threads: num_cpus::get().min(8),
-            chunk_size: 65536,   // 64KB
-            cache_size: 1048576, // 1MB
+            chunk_size: 65536,                // 64KB
+            cache_size: 1048576,              // 1MB
+            snapshot_memory_limit: 536870912, // 512MB
+            materialize_threshold: 100_000,
+            session_ttl_secs: 1800, // 30 minutes
         },
         memory: MemoryDefaults {
             max_read_size: 10485760, // 10MB
             enable_write_protection: true,
             backup_before_write: true,
+            watch_interval_ms: 250,
+        },
+        policy: PolicyDefaults {
+            allow: Vec::new(),
+            deny: Vec::new(),
+            read_only: Vec::new(),
+            allow_system_processes: false,
         },
         logging: LoggingDefaults {
             level: "info".to_string(),
@@ -86,6 +110,9 @@ mod tests {
         assert!(config.scanner.max_threads <= 8);
         assert_eq!(config.scanner.chunk_size, 65536);
         assert_eq!(config.scanner.cache_size, 1048576);
+        assert_eq!(config.scanner.snapshot_memory_limit, 536870912);
+        assert_eq!(config.scanner.materialize_threshold, 100_000);
+        assert_eq!(config.scanner.session_ttl_secs, 1800);
     }
 
     #[test]
@@ -94,6 +121,16 @@ mod tests {
         assert_eq!(config.memory.max_read_size, 10485760);
         assert!(config.memory.enable_write_protection);
         assert!(config.memory.backup_before_write);
+        assert_eq!(config.memory.watch_interval_ms, 250);
+    }
+
+    #[test]
+    fn test_policy_defaults() {
+        let config = default_config();
+        assert!(config.policy.allow.is_empty());
+        assert!(config.policy.deny.is_empty());
+        assert!(config.policy.read_only.is_empty());
+        assert!(!config.policy.allow_system_processes);
     }
 
     #[test]
@@ -39,6 +39,9 @@ pub struct Config {
     #[serde(default = "default_memory")]
     pub memory: MemoryConfig,
 
+    #[serde(default = "default_policy")]
+    pub policy: PolicyConfig,
+
     #[serde(default = "default_logging")]
     pub logging: LoggingConfig,
 }
@@ -63,6 +66,15 @@ pub struct ScannerConfig {
     pub chunk_size: usize,
     #[serde(default = "default_cache_size")]
     pub cache_size: usize,
+    /// Bytes of region snapshots kept in memory before spilling to temp files
+    #[serde(default = "default_snapshot_memory_limit")]
+    pub snapshot_memory_limit: usize,
+    /// Candidate count below which compact scans produce individual results
+    #[serde(default = "default_materialize_threshold")]
+    pub materialize_threshold: usize,
+    /// Seconds a scan session may stay idle before it is evicted
+    #[serde(default = "default_session_ttl_secs")]
+    pub session_ttl_secs: u64,
 }
 
 /// Memory configuration
@@ -74,6 +86,28 @@ pub struct MemoryConfig {
     pub enable_write_protection: bool,
     #[serde(default = "default_backup_before_write")]
     pub backup_before_write: bool,
+    /// Milliseconds between polls of watched addresses
+    #[serde(default = "default_watch_interval_ms")]
+    pub watch_interval_ms: u64,
+}
+
+/// Process access policy
+///
+/// Rules are process names, or path globs when they contain a path separator.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolicyConfig {
+    /// When not empty, only matching processes may be attached
+    #[serde(default = "default_policy_allow")]
+    pub allow: Vec<String>,
+    /// Processes that may never be attached
+    #[serde(default = "default_policy_deny")]
+    pub deny: Vec<String>,
+    /// Processes that may be read but not written
+    #[serde(default = "default_policy_read_only")]
+    pub read_only: Vec<String>,
+    /// Whether system processes may be attached
+    #[serde(default = "default_allow_system_processes")]
+    pub allow_system_processes: bool,
 }
 
 /// Logging configuration
@@ -146,6 +180,9 @@ fn default_scanner() -> ScannerConfig {
         max_threads: defaults.scanner.max_threads,
         chunk_size: defaults.scanner.chunk_size,
         cache_size: defaults.scanner.cache_size,
+        snapshot_memory_limit: defaults.scanner.snapshot_memory_limit,
+        materialize_threshold: defaults.scanner.materialize_threshold,
+        session_ttl_secs: defaults.scanner.session_ttl_secs,
     }
 }
 
@@ -155,6 +192,17 @@ fn default_memory() -> MemoryConfig {
         max_read_size: defaults.memory.max_read_size,
         enable_write_protection: defaults.memory.enable_write_protection,
         backup_before_write: defaults.memory.backup_before_write,
+        watch_interval_ms: defaults.memory.watch_interval_ms,
+    }
+}
+
+fn default_policy() -> PolicyConfig {
+    let defaults = default_config();
+    PolicyConfig {
+        allow: defaults.policy.allow,
+        deny: defaults.policy.deny,
+        read_only: defaults.policy.read_only,
+        allow_system_processes: defaults.policy.allow_system_processes,
     }
 }
 
@@ -191,6 +239,18 @@ fn default_cache_size() -> usize {
     default_config().scanner.cache_size
 }
 
+fn default_snapshot_memory_limit() -> usize {
+    default_config().scanner.snapshot_memory_limit
+}
+
+fn default_materialize_threshold() -> usize {
+    default_config().scanner.materialize_threshold
+}
+
+fn default_session_ttl_secs() -> u64 {
+    default_config().scanner.session_ttl_secs
+}
+
 fn default_max_read_size() -> usize {
     default_config().memory.max_read_size
 }
@@ -203,6 +263,26 @@ fn default_backup_before_write() -> bool {
     default_config().memory.backup_before_write
 }
 
+fn default_watch_interval_ms() -> u64 {
+    default_config().memory.watch_interval_ms
+}
+
+fn default_policy_allow() -> Vec<String> {
+    default_config().policy.allow
+}
+
+fn default_policy_deny() -> Vec<String> {
+    default_config().policy.deny
+}
+
+fn default_policy_read_only() -> Vec<String> {
+    default_config().policy.read_only
+}
+
+fn default_allow_system_processes() -> bool {
+    default_config().policy.allow_system_processes
+}
+
 fn default_log_level() -> String {
     default_config().logging.level
 }
@@ -217,6 +297,7 @@ impl Default for Config {
             server: default_server(),
             scanner: default_scanner(),
             memory: default_memory(),
+            policy: default_policy(),
             logging: default_logging(),
         }
     }
@@ -290,6 +371,22 @@ mod tests {
         // Check defaults are applied
         assert!(config.scanner.max_threads > 0);
         assert_eq!(config.memory.max_read_size, 10485760);
+        assert!(config.policy.deny.is_empty());
+    }
+
+    #[test]
+    fn test_policy_section() {
+        let toml_str = r#"
+            [policy]
+            deny = ["lsass.exe", "C:\\Windows\\*"]
+            read_only = ["game.exe"]
+        "#;
+
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.policy.deny, vec!["lsass.exe", "C:\\Windows\\*"]);
+        assert_eq!(config.policy.read_only, vec!["game.exe"]);
+        assert!(config.policy.allow.is_empty());
+        assert!(!config.policy.allow_system_processes);
     }
 
     #[test]
@@ -11,8 +11,8 @@ pub use defaults::{default_config, ConfigDefaults};
 pub use loader::{load_config, ConfigLoader};
 pub use validator::{validate_config, ConfigValidator};
 
-// Re-export the main configuration structure
-pub use loader::Config;
+// Re-export the main configuration structure and its sections
+pub use loader::{Config, LoggingConfig, MemoryConfig, PolicyConfig, ScannerConfig, ServerConfig};
 
 // Configuration-related error type
 pub use loader::ConfigError;
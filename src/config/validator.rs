@@ -14,6 +14,7 @@ impl ConfigValidator {
         Self::validate_server(&config.server)?;
         Self::validate_scanner(&config.scanner)?;
         Self::validate_memory(&config.memory)?;
+        Self::validate_policy(&config.policy)?;
         Self::validate_logging(&config.logging)?;
         Ok(())
     }
@@ -77,6 +78,18 @@ impl ConfigValidator {
             ));
         }
 
+        if scanner.materialize_threshold == 0 {
+            return Err(ConfigError::Invalid(
+                "Materialize threshold must be greater than 0".to_string(),
+            ));
+        }
+
+        if scanner.session_ttl_secs == 0 {
+            return Err(ConfigError::Invalid(
+                "Session TTL must be greater than 0".to_string(),
+            ));
+        }
+
         Ok(())
     }
 
@@ -95,6 +108,30 @@ impl ConfigValidator {
             eprintln!("Warning: Maximum read size exceeds 100MB");
         }
 
+        if memory.watch_interval_ms == 0 {
+            return Err(ConfigError::Invalid(
+                "Watch interval must be greater than 0".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Validates process policy configuration
+    fn validate_policy(policy: &super::loader::PolicyConfig) -> Result<(), ConfigError> {
+        let rules = policy
+            .allow
+            .iter()
+            .chain(&policy.deny)
+            .chain(&policy.read_only);
+        for rule in rules {
+            if rule.trim().is_empty() {
+                return Err(ConfigError::Invalid(
+                    "Policy rules cannot be empty".to_string(),
+                ));
+            }
+        }
+
         Ok(())
     }
 
@@ -223,6 +260,27 @@ mod tests {
             .contains("Cache size must be at least"));
     }
 
+    #[test]
+    fn test_invalid_materialize_threshold() {
+        let mut config = Config::default();
+        config.scanner.materialize_threshold = 0;
+        let result = validate_config(&config);
+        assert!(result.is_err());
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("Materialize threshold"));
+    }
+
+    #[test]
+    fn test_invalid_session_ttl() {
+        let mut config = Config::default();
+        config.scanner.session_ttl_secs = 0;
+        let result = validate_config(&config);
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("Session TTL"));
+    }
+
     #[test]
     fn test_invalid_max_read_size() {
         let mut config = Config::default();
@@ -240,6 +298,24 @@ mod tests {
         assert!(result.is_ok()); // Should succeed with warning
     }
 
+    #[test]
+    fn test_invalid_watch_interval() {
+        let mut config = Config::default();
+        config.memory.watch_interval_ms = 0;
+        let result = validate_config(&config);
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("Watch interval"));
+    }
+
+    #[test]
+    fn test_invalid_policy_rule() {
+        let mut config = Config::default();
+        config.policy.read_only = vec!["game.exe".to_string(), " ".to_string()];
+        let result = validate_config(&config);
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("Policy rules"));
+    }
+
     #[test]
     fn test_more_log_levels() {
         let mut config = Config::default();
@@ -14,8 +14,8 @@ pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 pub const AUTHORS: &str = env!("CARGO_PKG_AUTHORS");
 
 // Platform verification at compile time
-#[cfg(not(target_os = "windows"))]
-compile_error!("Memory-MCP only supports Windows platform");
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+compile_error!("Memory-MCP only supports Windows and Linux platforms");
 
 #[cfg(not(target_pointer_width = "64"))]
 compile_error!("Memory-MCP requires 64-bit architecture");
@@ -145,13 +145,13 @@ mod tests {
 
     #[test]
     fn test_platform_target() {
-        // This test will only compile on Windows 64-bit
+        // This test will only compile on 64-bit Windows or Linux
         // The compile_error! macros ensure this at compile time
 
-        // Verify we're on Windows at runtime
-        #[cfg(target_os = "windows")]
+        // Verify we're on a supported platform at runtime
+        #[cfg(any(target_os = "windows", target_os = "linux"))]
         {
-            // Running on Windows platform - compile-time check
+            // Running on a supported platform - compile-time check
         }
 
         // Verify we're on 64-bit at runtime
@@ -0,0 +1,249 @@
+//! Encodings for numeric values that are not stored as plain little-endian
+
+use super::error::{MemoryError, MemoryResult};
+use super::process_info::ProcessArchitecture;
+use super::value::{MemoryValue, ValueType};
+use serde::{Deserialize, Serialize};
+
+/// Byte order of a value in memory
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ByteOrder {
+    #[default]
+    Little,
+    Big,
+}
+
+/// Reversible transform between the stored value and the logical value
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ValueTransform {
+    /// The stored bits are the value's bits XORed with `key`
+    Xor { key: u64 },
+    /// The logical value is `stored * scale + offset`; integers are rounded
+    Linear {
+        scale: f64,
+        #[serde(default)]
+        offset: f64,
+    },
+}
+
+/// How a numeric value is laid out in memory
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct ValueEncoding {
+    #[serde(default)]
+    pub byte_order: ByteOrder,
+    /// Applied in order when decoding and in reverse when encoding
+    #[serde(default)]
+    pub transforms: Vec<ValueTransform>,
+}
+
+impl ValueEncoding {
+    /// Plain big-endian values
+    pub fn big_endian() -> Self {
+        ValueEncoding {
+            byte_order: ByteOrder::Big,
+            transforms: Vec::new(),
+        }
+    }
+
+    /// Add an XOR transform
+    pub fn with_xor(mut self, key: u64) -> Self {
+        self.transforms.push(ValueTransform::Xor { key });
+        self
+    }
+
+    /// Add a linear transform
+    pub fn with_linear(mut self, scale: f64, offset: f64) -> Self {
+        self.transforms
+            .push(ValueTransform::Linear { scale, offset });
+        self
+    }
+
+    /// Whether values are stored as plain little-endian
+    pub fn is_identity(&self) -> bool {
+        self.byte_order == ByteOrder::Little && self.transforms.is_empty()
+    }
+
+    /// Decode a value of `value_type` from the start of `bytes`
+    ///
+    /// Returns `None` if `bytes` is too short, or if the encoding is not the
+    /// identity and `value_type` is not numeric.
+    pub fn decode(&self, bytes: &[u8], value_type: ValueType) -> Option<MemoryValue> {
+        self.decode_for(bytes, value_type, ProcessArchitecture::Unknown)
+    }
+
+    /// Decode a value read from a process of `architecture`
+    pub fn decode_for(
+        &self,
+        bytes: &[u8],
+        value_type: ValueType,
+        architecture: ProcessArchitecture,
+    ) -> Option<MemoryValue> {
+        if self.is_identity() {
+            return MemoryValue::from_bytes_for(bytes, value_type, architecture);
+        }
+        if !value_type.is_numeric() {
+            return None;
+        }
+
+        let mut raw = bytes.get(..value_type.size()?)?.to_vec();
+        if self.byte_order == ByteOrder::Big {
+            raw.reverse();
+        }
+        let mut value = MemoryValue::from_bytes(&raw, value_type)?;
+        for transform in &self.transforms {
+            value = match *transform {
+                ValueTransform::Xor { key } => xor(&value, key)?,
+                ValueTransform::Linear { scale, offset } => {
+                    from_f64(to_f64(&value)? * scale + offset, value_type)?
+                }
+            };
+        }
+        Some(value)
+    }
+
+    /// Encode `value` into the bytes stored in memory
+    pub fn encode(&self, value: &MemoryValue) -> MemoryResult<Vec<u8>> {
+        if self.is_identity() {
+            return Ok(value.to_bytes());
+        }
+        let value_type = value.value_type();
+        if !value_type.is_numeric() {
+            return Err(MemoryError::InvalidValueType(format!(
+                "{:?} values cannot be encoded",
+                value_type
+            )));
+        }
+
+        let mut value = value.clone();
+        for transform in self.transforms.iter().rev() {
+            value = match *transform {
+                ValueTransform::Xor { key } => xor(&value, key),
+                ValueTransform::Linear { scale, offset } if scale != 0.0 => {
+                    to_f64(&value).and_then(|v| from_f64((v - offset) / scale, value_type))
+                }
+                ValueTransform::Linear { .. } => None,
+            }
+            .ok_or_else(|| {
+                MemoryError::InvalidValueType(format!(
+                    "Cannot encode {} with {:?}",
+                    value, transform
+                ))
+            })?;
+        }
+
+        let mut bytes = value.to_bytes();
+        if self.byte_order == ByteOrder::Big {
+            bytes.reverse();
+        }
+        Ok(bytes)
+    }
+}
+
+fn xor(value: &MemoryValue, key: u64) -> Option<MemoryValue> {
+    let bytes: Vec<u8> = value
+        .to_bytes()
+        .iter()
+        .zip(key.to_le_bytes())
+        .map(|(b, k)| b ^ k)
+        .collect();
+    MemoryValue::from_bytes(&bytes, value.value_type())
+}
+
+fn to_f64(value: &MemoryValue) -> Option<f64> {
+    Some(match *value {
+        MemoryValue::I8(v) => v as f64,
+        MemoryValue::I16(v) => v as f64,
+        MemoryValue::I32(v) => v as f64,
+        MemoryValue::I64(v) => v as f64,
+        MemoryValue::U8(v) => v as f64,
+        MemoryValue::U16(v) => v as f64,
+        MemoryValue::U32(v) => v as f64,
+        MemoryValue::U64(v) => v as f64,
+        MemoryValue::I128(v) => v as f64,
+        MemoryValue::U128(v) => v as f64,
+        MemoryValue::F32(v) => v as f64,
+        MemoryValue::F64(v) => v,
+        _ => return None,
+    })
+}
+
+/// Convert to `value_type`, rounding and saturating integers
+fn from_f64(value: f64, value_type: ValueType) -> Option<MemoryValue> {
+    let int = value.round();
+    Some(match value_type {
+        ValueType::I8 => MemoryValue::I8(int as i8),
+        ValueType::I16 => MemoryValue::I16(int as i16),
+        ValueType::I32 => MemoryValue::I32(int as i32),
+        ValueType::I64 => MemoryValue::I64(int as i64),
+        ValueType::U8 => MemoryValue::U8(int as u8),
+        ValueType::U16 => MemoryValue::U16(int as u16),
+        ValueType::U32 => MemoryValue::U32(int as u32),
+        ValueType::U64 => MemoryValue::U64(int as u64),
+        ValueType::I128 => MemoryValue::I128(int as i128),
+        ValueType::U128 => MemoryValue::U128(int as u128),
+        ValueType::F32 => MemoryValue::F32(value as f32),
+        ValueType::F64 => MemoryValue::F64(value),
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_big_endian() {
+        let encoding = ValueEncoding::big_endian();
+        assert_eq!(
+            encoding.decode(&[0x12, 0x34, 0x56, 0x78], ValueType::U32),
+            Some(MemoryValue::U32(0x12345678))
+        );
+        assert_eq!(
+            encoding.decode(&1.5f32.to_be_bytes(), ValueType::F32),
+            Some(MemoryValue::F32(1.5))
+        );
+        assert_eq!(
+            encoding.encode(&MemoryValue::I16(-2)).unwrap(),
+            vec![0xFF, 0xFE]
+        );
+        assert_eq!(encoding.decode(&[1, 2], ValueType::Bytes), None);
+        assert!(encoding.encode(&MemoryValue::Bytes(vec![1])).is_err());
+    }
+
+    #[test]
+    fn test_xor_and_linear_round_trip() {
+        let encoding = ValueEncoding::default()
+            .with_xor(0xDEADBEEF)
+            .with_linear(0.5, 10.0);
+        let stored = encoding.encode(&MemoryValue::I32(110)).unwrap();
+        // 110 = stored * 0.5 + 10, so the raw value is 200 before the XOR
+        assert_eq!(
+            stored,
+            (200i32 ^ 0xDEADBEEFu32 as i32).to_le_bytes().to_vec()
+        );
+        assert_eq!(
+            encoding.decode(&stored, ValueType::I32),
+            Some(MemoryValue::I32(110))
+        );
+
+        let floats = ValueEncoding::big_endian().with_linear(100.0, 0.0);
+        let stored = floats.encode(&MemoryValue::F64(2.5)).unwrap();
+        assert_eq!(stored, 0.025f64.to_be_bytes().to_vec());
+        assert!(ValueEncoding::default()
+            .with_linear(0.0, 1.0)
+            .encode(&MemoryValue::U8(1))
+            .is_err());
+    }
+
+    #[test]
+    fn test_serialization() {
+        let encoding: ValueEncoding =
+            serde_json::from_str(r#"{"byte_order":"big","transforms":[{"kind":"xor","key":255}]}"#)
+                .unwrap();
+        assert_eq!(encoding, ValueEncoding::big_endian().with_xor(0xFF));
+        let plain: ValueEncoding = serde_json::from_str("{}").unwrap();
+        assert!(plain.is_identity());
+    }
+}
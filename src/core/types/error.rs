@@ -36,6 +36,9 @@ pub enum MemoryError {
     #[error("Invalid pattern format: {0}")]
     InvalidPattern(String),
 
+    #[error("Invalid structure definition: {0}")]
+    InvalidStruct(String),
+
     #[error("Pointer chain broken at level {level}: {reason}")]
     PointerChainBroken { level: usize, reason: String },
 
@@ -45,12 +48,25 @@ pub enum MemoryError {
     #[error("Memory protection error: {0}")]
     ProtectionError(String),
 
+    #[error("Read of {size} bytes at {address} exceeds the {limit}-byte limit")]
+    ReadLimitExceeded {
+        address: String,
+        size: usize,
+        limit: usize,
+    },
+
+    #[error("Write to protected memory at {address}: {reason}")]
+    WriteProtected { address: String, reason: String },
+
     #[error("Buffer too small: expected {expected}, got {actual}")]
     BufferTooSmall { expected: usize, actual: usize },
 
     #[error("Unsupported operation: {0}")]
     UnsupportedOperation(String),
 
+    #[error("Invalid argument: {0}")]
+    InvalidArgument(String),
+
     #[error("Permission denied: {0}")]
     PermissionDenied(String),
 
@@ -60,6 +76,7 @@ pub enum MemoryError {
     #[error("Process already attached: {0}")]
     ProcessAlreadyAttached(u32),
 
+    #[cfg(windows)]
     #[error("Windows API error: {0}")]
     WindowsApiError(#[from] windows::core::Error),
 
@@ -84,10 +101,17 @@ pub type MemoryResult<T> = Result<T, MemoryError>;
 
 impl MemoryError {
     /// Creates a new Windows API error with the last error code
+    #[cfg(windows)]
     pub fn last_os_error() -> Self {
         MemoryError::WindowsApiError(windows::core::Error::from_win32())
     }
 
+    /// Creates an I/O error from the last `errno` value
+    #[cfg(not(windows))]
+    pub fn last_os_error() -> Self {
+        MemoryError::IoError(std::io::Error::last_os_error())
+    }
+
     /// Creates an access denied error for a process
     pub fn access_denied(pid: u32, reason: impl Into<String>) -> Self {
         MemoryError::AccessDenied {
@@ -112,6 +136,23 @@ impl MemoryError {
         }
     }
 
+    /// Creates an error for a read larger than the configured limit
+    pub fn read_limit_exceeded(address: impl fmt::Display, size: usize, limit: usize) -> Self {
+        MemoryError::ReadLimitExceeded {
+            address: address.to_string(),
+            size,
+            limit,
+        }
+    }
+
+    /// Creates an error for a write refused by write protection
+    pub fn write_protected(address: impl fmt::Display, reason: impl Into<String>) -> Self {
+        MemoryError::WriteProtected {
+            address: address.to_string(),
+            reason: reason.into(),
+        }
+    }
+
     /// Creates a pointer chain broken error
     pub fn pointer_chain_broken(level: usize, reason: impl Into<String>) -> Self {
         MemoryError::PointerChainBroken {
@@ -191,6 +232,10 @@ mod tests {
                 MemoryError::InvalidPattern("?? ?? XX".to_string()),
                 "Invalid pattern format: ?? ?? XX",
             ),
+            (
+                MemoryError::InvalidStruct("unknown structure 'Item'".to_string()),
+                "Invalid structure definition: unknown structure 'Item'",
+            ),
             (
                 MemoryError::PointerChainBroken {
                     level: 3,
@@ -206,6 +251,14 @@ mod tests {
                 MemoryError::ProtectionError("PAGE_NOACCESS".to_string()),
                 "Memory protection error: PAGE_NOACCESS",
             ),
+            (
+                MemoryError::read_limit_exceeded("0x3000", 4096, 1024),
+                "Read of 4096 bytes at 0x3000 exceeds the 1024-byte limit",
+            ),
+            (
+                MemoryError::write_protected("0x4000", "executable region"),
+                "Write to protected memory at 0x4000: executable region",
+            ),
             (
                 MemoryError::BufferTooSmall {
                     expected: 100,
@@ -217,6 +270,10 @@ mod tests {
                 MemoryError::UnsupportedOperation("AOB scan".to_string()),
                 "Unsupported operation: AOB scan",
             ),
+            (
+                MemoryError::InvalidArgument("unknown id 3".to_string()),
+                "Invalid argument: unknown id 3",
+            ),
             (
                 MemoryError::Unknown("something went wrong".to_string()),
                 "Unknown error: something went wrong",
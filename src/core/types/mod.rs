@@ -4,6 +4,7 @@
 //! including address wrappers, memory values, process information, and error types.
 
 mod address;
+mod encoding;
 mod error;
 mod process_info;
 mod scan_result;
@@ -11,10 +12,11 @@ mod value;
 
 // Re-export all public types
 pub use address::Address;
+pub use encoding::{ByteOrder, ValueEncoding, ValueTransform};
 pub use error::{MemoryError, MemoryResult};
 pub use process_info::{ModuleInfo, ProcessArchitecture, ProcessInfo};
 pub use scan_result::{RegionInfo, ScanResult, ScanSession, ScanType};
-pub use value::{MemoryValue, ValueType};
+pub use value::{ElementType, MemoryValue, ValueType};
 
 // Common type aliases
 pub type ProcessId = u32;
@@ -50,6 +50,9 @@ pub struct ScanSession {
     pub id: String,
     pub scan_type: ScanType,
     pub value_type: super::value::ValueType,
+    /// How values are stored in memory
+    #[serde(default)]
+    pub encoding: super::encoding::ValueEncoding,
     pub results: Vec<ScanResult>,
     pub scan_count: u32,
     pub created_at: u64,
@@ -68,6 +71,7 @@ impl ScanSession {
             id,
             scan_type,
             value_type,
+            encoding: Default::default(),
             results: Vec::new(),
             scan_count: 0,
             created_at: now,
@@ -109,9 +113,28 @@ pub enum ScanType {
     Between,
     BiggerThan,
     SmallerThan,
+    ChangedByPercent,
 }
 
 impl ScanType {
+    /// Returns every supported scan type
+    pub fn all() -> &'static [ScanType] {
+        &[
+            ScanType::Exact,
+            ScanType::Unknown,
+            ScanType::Increased,
+            ScanType::IncreasedBy,
+            ScanType::Decreased,
+            ScanType::DecreasedBy,
+            ScanType::Changed,
+            ScanType::Unchanged,
+            ScanType::Between,
+            ScanType::BiggerThan,
+            ScanType::SmallerThan,
+            ScanType::ChangedByPercent,
+        ]
+    }
+
     /// Checks if this scan type requires a previous value
     pub fn requires_previous(&self) -> bool {
         matches!(
@@ -122,6 +145,7 @@ impl ScanType {
                 | ScanType::DecreasedBy
                 | ScanType::Changed
                 | ScanType::Unchanged
+                | ScanType::ChangedByPercent
         )
     }
 
@@ -135,6 +159,7 @@ impl ScanType {
                 | ScanType::Between
                 | ScanType::BiggerThan
                 | ScanType::SmallerThan
+                | ScanType::ChangedByPercent
         )
     }
 }
@@ -359,6 +384,7 @@ mod tests {
             ScanType::Between,
             ScanType::BiggerThan,
             ScanType::SmallerThan,
+            ScanType::ChangedByPercent,
         ];
 
         for scan_type in types {
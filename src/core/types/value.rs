@@ -1,5 +1,8 @@
 //! Memory value enum for handling different data types
 
+use super::address::Address;
+use super::error::MemoryError;
+use super::process_info::ProcessArchitecture;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
@@ -19,23 +22,55 @@ pub enum MemoryValue {
     F64(f64),
     Bytes(Vec<u8>),
     String(String),
+    /// UTF-16LE string
+    WString(String),
+    /// Pointer-sized address in the target
+    Pointer(Address),
+    Bool(bool),
+    I128(i128),
+    U128(u128),
+    /// Fixed-length array of scalar elements
+    Array {
+        elem: ElementType,
+        values: Vec<MemoryValue>,
+    },
 }
 
 impl MemoryValue {
     /// Returns the size in bytes of the value
+    ///
+    /// Pointers take the host's pointer width; see [`MemoryValue::size_for`].
     pub fn size(&self) -> usize {
+        self.size_for(ProcessArchitecture::Unknown)
+    }
+
+    /// Returns the size in bytes of the value in a process of `architecture`
+    pub fn size_for(&self, architecture: ProcessArchitecture) -> usize {
         match self {
-            MemoryValue::I8(_) | MemoryValue::U8(_) => 1,
+            MemoryValue::I8(_) | MemoryValue::U8(_) | MemoryValue::Bool(_) => 1,
             MemoryValue::I16(_) | MemoryValue::U16(_) => 2,
             MemoryValue::I32(_) | MemoryValue::U32(_) | MemoryValue::F32(_) => 4,
             MemoryValue::I64(_) | MemoryValue::U64(_) | MemoryValue::F64(_) => 8,
+            MemoryValue::I128(_) | MemoryValue::U128(_) => 16,
+            MemoryValue::Pointer(_) => architecture.pointer_size(),
             MemoryValue::Bytes(b) => b.len(),
             MemoryValue::String(s) => s.len(),
+            MemoryValue::WString(s) => s.encode_utf16().count() * 2,
+            MemoryValue::Array { values, .. } => {
+                values.iter().map(|v| v.size_for(architecture)).sum()
+            }
         }
     }
 
     /// Converts the value to bytes
+    ///
+    /// Pointers take the host's pointer width; see [`MemoryValue::to_bytes_for`].
     pub fn to_bytes(&self) -> Vec<u8> {
+        self.to_bytes_for(ProcessArchitecture::Unknown)
+    }
+
+    /// Converts the value to bytes as stored in a process of `architecture`
+    pub fn to_bytes_for(&self, architecture: ProcessArchitecture) -> Vec<u8> {
         match self {
             MemoryValue::I8(v) => v.to_le_bytes().to_vec(),
             MemoryValue::I16(v) => v.to_le_bytes().to_vec(),
@@ -49,11 +84,33 @@ impl MemoryValue {
             MemoryValue::F64(v) => v.to_le_bytes().to_vec(),
             MemoryValue::Bytes(b) => b.clone(),
             MemoryValue::String(s) => s.as_bytes().to_vec(),
+            MemoryValue::WString(s) => s.encode_utf16().flat_map(u16::to_le_bytes).collect(),
+            MemoryValue::Pointer(a) => {
+                (a.as_usize() as u64).to_le_bytes()[..architecture.pointer_size()].to_vec()
+            }
+            MemoryValue::Bool(v) => vec![*v as u8],
+            MemoryValue::I128(v) => v.to_le_bytes().to_vec(),
+            MemoryValue::U128(v) => v.to_le_bytes().to_vec(),
+            MemoryValue::Array { values, .. } => values
+                .iter()
+                .flat_map(|v| v.to_bytes_for(architecture))
+                .collect(),
         }
     }
 
     /// Creates a value from bytes based on the specified type
+    ///
+    /// Pointers take the host's pointer width; see [`MemoryValue::from_bytes_for`].
     pub fn from_bytes(bytes: &[u8], value_type: ValueType) -> Option<Self> {
+        Self::from_bytes_for(bytes, value_type, ProcessArchitecture::Unknown)
+    }
+
+    /// Creates a value from bytes read from a process of `architecture`
+    pub fn from_bytes_for(
+        bytes: &[u8],
+        value_type: ValueType,
+        architecture: ProcessArchitecture,
+    ) -> Option<Self> {
         match value_type {
             ValueType::I8 => bytes.first().map(|&b| MemoryValue::I8(b as i8)),
             ValueType::I16 => {
@@ -132,6 +189,42 @@ impl MemoryValue {
             ValueType::String => String::from_utf8(bytes.to_vec())
                 .ok()
                 .map(MemoryValue::String),
+            ValueType::WString => {
+                if bytes.len() % 2 != 0 {
+                    return None;
+                }
+                let units: Vec<u16> = bytes
+                    .chunks_exact(2)
+                    .map(|c| u16::from_le_bytes([c[0], c[1]]))
+                    .collect();
+                String::from_utf16(&units).ok().map(MemoryValue::WString)
+            }
+            ValueType::Pointer => {
+                let size = architecture.pointer_size();
+                let mut raw = [0u8; 8];
+                raw[..size].copy_from_slice(bytes.get(..size)?);
+                Some(MemoryValue::Pointer(Address::new(
+                    u64::from_le_bytes(raw) as usize
+                )))
+            }
+            ValueType::Bool => bytes.first().map(|&b| MemoryValue::Bool(b != 0)),
+            ValueType::I128 => Some(MemoryValue::I128(i128::from_le_bytes(
+                bytes.get(..16)?.try_into().ok()?,
+            ))),
+            ValueType::U128 => Some(MemoryValue::U128(u128::from_le_bytes(
+                bytes.get(..16)?.try_into().ok()?,
+            ))),
+            ValueType::Array { elem, len } => {
+                let elem_type = elem.value_type();
+                let size = elem_type.size_for(architecture)?;
+                let values = (0..len)
+                    .map(|i| {
+                        let bytes = bytes.get(i * size..(i + 1) * size)?;
+                        Self::from_bytes_for(bytes, elem_type, architecture)
+                    })
+                    .collect::<Option<Vec<_>>>()?;
+                Some(MemoryValue::Array { elem, values })
+            }
         }
     }
 
@@ -150,12 +243,21 @@ impl MemoryValue {
             MemoryValue::F64(_) => ValueType::F64,
             MemoryValue::Bytes(_) => ValueType::Bytes,
             MemoryValue::String(_) => ValueType::String,
+            MemoryValue::WString(_) => ValueType::WString,
+            MemoryValue::Pointer(_) => ValueType::Pointer,
+            MemoryValue::Bool(_) => ValueType::Bool,
+            MemoryValue::I128(_) => ValueType::I128,
+            MemoryValue::U128(_) => ValueType::U128,
+            MemoryValue::Array { elem, values } => ValueType::Array {
+                elem: *elem,
+                len: values.len(),
+            },
         }
     }
 }
 
 /// Enum representing the type of a memory value
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum ValueType {
     I8,
@@ -170,21 +272,166 @@ pub enum ValueType {
     F64,
     Bytes,
     String,
+    /// UTF-16LE string
+    WString,
+    /// Address sized by the target's architecture
+    Pointer,
+    Bool,
+    I128,
+    U128,
+    /// `len` consecutive elements, e.g. `{"array": {"elem": "u32", "len": 4}}`
+    Array {
+        elem: ElementType,
+        len: usize,
+    },
 }
 
 impl ValueType {
+    /// Returns every supported value type except arrays
+    ///
+    /// New types are appended; saved sessions refer to types by position.
+    pub fn all() -> &'static [ValueType] {
+        &[
+            ValueType::I8,
+            ValueType::I16,
+            ValueType::I32,
+            ValueType::I64,
+            ValueType::U8,
+            ValueType::U16,
+            ValueType::U32,
+            ValueType::U64,
+            ValueType::F32,
+            ValueType::F64,
+            ValueType::Bytes,
+            ValueType::String,
+            ValueType::WString,
+            ValueType::Pointer,
+            ValueType::Bool,
+            ValueType::I128,
+            ValueType::U128,
+        ]
+    }
+
+    /// Array of `len` elements of `elem`, if `elem` is a fixed-size scalar
+    pub fn array(elem: ValueType, len: usize) -> Option<ValueType> {
+        let elem = ElementType::try_from(elem).ok()?;
+        Some(ValueType::Array { elem, len })
+    }
+
+    /// Checks if this is an integer or floating-point type
+    pub fn is_numeric(&self) -> bool {
+        matches!(
+            self,
+            ValueType::I8
+                | ValueType::I16
+                | ValueType::I32
+                | ValueType::I64
+                | ValueType::U8
+                | ValueType::U16
+                | ValueType::U32
+                | ValueType::U64
+                | ValueType::F32
+                | ValueType::F64
+                | ValueType::I128
+                | ValueType::U128
+        )
+    }
+
     /// Returns the size in bytes for this value type
+    ///
+    /// Pointers take the host's pointer width; see [`ValueType::size_for`].
     pub fn size(&self) -> Option<usize> {
+        self.size_for(ProcessArchitecture::Unknown)
+    }
+
+    /// Returns the size in bytes for this value type in a process of `architecture`
+    pub fn size_for(&self, architecture: ProcessArchitecture) -> Option<usize> {
         match self {
-            ValueType::I8 | ValueType::U8 => Some(1),
+            ValueType::I8 | ValueType::U8 | ValueType::Bool => Some(1),
             ValueType::I16 | ValueType::U16 => Some(2),
             ValueType::I32 | ValueType::U32 | ValueType::F32 => Some(4),
             ValueType::I64 | ValueType::U64 | ValueType::F64 => Some(8),
-            ValueType::Bytes | ValueType::String => None, // Variable size
+            ValueType::I128 | ValueType::U128 => Some(16),
+            ValueType::Pointer => Some(architecture.pointer_size()),
+            ValueType::Array { elem, len } => {
+                elem.value_type().size_for(architecture)?.checked_mul(*len)
+            }
+            ValueType::Bytes | ValueType::String | ValueType::WString => None, // Variable size
+        }
+    }
+}
+
+/// Fixed-size scalar type an array can hold
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ElementType {
+    I8,
+    I16,
+    I32,
+    I64,
+    I128,
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+    F32,
+    F64,
+    Bool,
+    Pointer,
+}
+
+impl ElementType {
+    /// The value type of a single element
+    pub fn value_type(&self) -> ValueType {
+        match self {
+            ElementType::I8 => ValueType::I8,
+            ElementType::I16 => ValueType::I16,
+            ElementType::I32 => ValueType::I32,
+            ElementType::I64 => ValueType::I64,
+            ElementType::I128 => ValueType::I128,
+            ElementType::U8 => ValueType::U8,
+            ElementType::U16 => ValueType::U16,
+            ElementType::U32 => ValueType::U32,
+            ElementType::U64 => ValueType::U64,
+            ElementType::U128 => ValueType::U128,
+            ElementType::F32 => ValueType::F32,
+            ElementType::F64 => ValueType::F64,
+            ElementType::Bool => ValueType::Bool,
+            ElementType::Pointer => ValueType::Pointer,
         }
     }
 }
 
+impl TryFrom<ValueType> for ElementType {
+    type Error = MemoryError;
+
+    fn try_from(value_type: ValueType) -> Result<Self, Self::Error> {
+        Ok(match value_type {
+            ValueType::I8 => ElementType::I8,
+            ValueType::I16 => ElementType::I16,
+            ValueType::I32 => ElementType::I32,
+            ValueType::I64 => ElementType::I64,
+            ValueType::I128 => ElementType::I128,
+            ValueType::U8 => ElementType::U8,
+            ValueType::U16 => ElementType::U16,
+            ValueType::U32 => ElementType::U32,
+            ValueType::U64 => ElementType::U64,
+            ValueType::U128 => ElementType::U128,
+            ValueType::F32 => ElementType::F32,
+            ValueType::F64 => ElementType::F64,
+            ValueType::Bool => ElementType::Bool,
+            ValueType::Pointer => ElementType::Pointer,
+            other => {
+                return Err(MemoryError::InvalidValueType(format!(
+                    "{:?} cannot be an array element",
+                    other
+                )))
+            }
+        })
+    }
+}
+
 impl fmt::Display for MemoryValue {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -200,6 +447,21 @@ impl fmt::Display for MemoryValue {
             MemoryValue::F64(v) => write!(f, "{}", v),
             MemoryValue::Bytes(b) => write!(f, "{:?}", b),
             MemoryValue::String(s) => write!(f, "\"{}\"", s),
+            MemoryValue::WString(s) => write!(f, "L\"{}\"", s),
+            MemoryValue::Pointer(a) => write!(f, "{}", a),
+            MemoryValue::Bool(v) => write!(f, "{}", v),
+            MemoryValue::I128(v) => write!(f, "{}", v),
+            MemoryValue::U128(v) => write!(f, "{}", v),
+            MemoryValue::Array { values, .. } => {
+                write!(f, "[")?;
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", value)?;
+                }
+                write!(f, "]")
+            }
         }
     }
 }
@@ -353,6 +615,14 @@ mod tests {
         assert_eq!(ValueType::String.size(), None);
     }
 
+    #[test]
+    fn test_value_type_all() {
+        let all = ValueType::all();
+        assert_eq!(all.len(), 17);
+        assert!(all.contains(&ValueType::F64));
+        assert!(all.contains(&ValueType::String));
+    }
+
     #[test]
     fn test_display_formatting() {
         assert_eq!(format!("{}", MemoryValue::I8(-42)), "-42");
@@ -470,4 +740,91 @@ mod tests {
         let min_i8 = MemoryValue::I8(-128);
         assert_eq!(min_i8.to_bytes(), vec![128]);
     }
+
+    #[test]
+    fn test_extended_types_round_trip() {
+        let values = vec![
+            MemoryValue::WString("Hé".to_string()),
+            MemoryValue::Pointer(Address::new(0x1234)),
+            MemoryValue::Bool(true),
+            MemoryValue::I128(-(1i128 << 100)),
+            MemoryValue::U128(u128::MAX),
+            MemoryValue::Array {
+                elem: ElementType::U16,
+                values: vec![MemoryValue::U16(1), MemoryValue::U16(0xBEEF)],
+            },
+        ];
+
+        for value in values {
+            let value_type = value.value_type();
+            let bytes = value.to_bytes();
+            assert_eq!(bytes.len(), value.size());
+            assert_eq!(value_type.size().unwrap_or(bytes.len()), bytes.len());
+            assert_eq!(
+                MemoryValue::from_bytes(&bytes, value_type),
+                Some(value.clone())
+            );
+
+            let json = serde_json::to_string(&value).unwrap();
+            assert_eq!(serde_json::from_str::<MemoryValue>(&json).unwrap(), value);
+        }
+
+        assert_eq!(
+            MemoryValue::from_bytes(&[2], ValueType::Bool),
+            Some(MemoryValue::Bool(true))
+        );
+        assert!(MemoryValue::from_bytes(&[0x48, 0x00, 0x69], ValueType::WString).is_none());
+        assert!(
+            MemoryValue::from_bytes(&[0; 3], ValueType::array(ValueType::U16, 2).unwrap())
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_pointer_width_follows_architecture() {
+        let x86 = ProcessArchitecture::X86;
+        let x64 = ProcessArchitecture::X64;
+        let bytes = [0x78, 0x56, 0x34, 0x12, 0xFF, 0xFF, 0xFF, 0xFF];
+
+        assert_eq!(ValueType::Pointer.size_for(x86), Some(4));
+        assert_eq!(ValueType::Pointer.size_for(x64), Some(8));
+        let pointers = ValueType::array(ValueType::Pointer, 3).unwrap();
+        assert_eq!(pointers.size_for(x86), Some(12));
+
+        let value = MemoryValue::from_bytes_for(&bytes, ValueType::Pointer, x86).unwrap();
+        assert_eq!(value, MemoryValue::Pointer(Address::new(0x12345678)));
+        assert_eq!(value.size_for(x86), 4);
+        assert_eq!(value.to_bytes_for(x86), bytes[..4].to_vec());
+        assert_eq!(value.to_bytes_for(x64).len(), 8);
+        assert!(MemoryValue::from_bytes_for(&bytes[..4], ValueType::Pointer, x64).is_none());
+    }
+
+    #[test]
+    fn test_extended_type_serialization_and_display() {
+        let array = ValueType::array(ValueType::U32, 4).unwrap();
+        assert_eq!(
+            serde_json::to_value(array).unwrap(),
+            serde_json::json!({ "array": { "elem": "u32", "len": 4 } })
+        );
+        assert_eq!(
+            serde_json::from_str::<ValueType>("\"wstring\"").unwrap(),
+            ValueType::WString
+        );
+        assert!(ValueType::array(ValueType::String, 4).is_none());
+        assert!(ValueType::I128.is_numeric());
+        assert!(!ValueType::Bool.is_numeric());
+
+        assert_eq!(format!("{}", MemoryValue::WString("hi".into())), "L\"hi\"");
+        assert_eq!(
+            format!("{}", MemoryValue::Pointer(Address::new(0x10))),
+            "0x0000000000000010"
+        );
+        assert_eq!(format!("{}", MemoryValue::Bool(false)), "false");
+        let values = vec![MemoryValue::I8(-1), MemoryValue::I8(2)];
+        let array = MemoryValue::Array {
+            elem: ElementType::I8,
+            values,
+        };
+        assert_eq!(format!("{}", array), "[-1, 2]");
+    }
 }
@@ -1,12 +1,14 @@
-//! Memory-MCP library for Windows memory manipulation
+//! Memory-MCP library for process memory manipulation on Windows and Linux
 
 #![allow(dead_code)]
 #![allow(unused_imports)]
 
 pub mod config;
 pub mod core;
+pub mod mcp;
 pub mod memory;
 pub mod process;
+#[cfg(windows)]
 pub mod windows;
 
 // Re-export main types from core module
@@ -1,30 +1,42 @@
 #![allow(dead_code)]
 #![allow(unused_imports)]
 
-mod config;
-mod core;
+use memory_mcp::mcp::McpServer;
+use memory_mcp::{config, core};
 
 use anyhow::Result;
 use tracing::{info, Level};
 
 /// Initialize the logging system
+///
+/// Logs go to stderr because stdout carries the MCP protocol stream.
 fn init_logging() {
     tracing_subscriber::fmt()
         .with_max_level(Level::INFO)
         .with_target(false)
+        .with_writer(std::io::stderr)
         .init();
 }
 
 /// Verify the platform is supported
 fn verify_platform() -> Result<()> {
-    #[cfg(not(target_os = "windows"))]
+    #[cfg(not(any(target_os = "windows", target_os = "linux")))]
     {
-        anyhow::bail!("Memory-MCP only supports Windows platform");
+        anyhow::bail!("Memory-MCP only supports Windows and Linux platforms");
     }
 
     Ok(())
 }
 
+/// Human-readable name of the platform we are running on
+fn platform_name() -> &'static str {
+    if cfg!(target_os = "windows") {
+        "Windows"
+    } else {
+        "Linux"
+    }
+}
+
 /// Get system information for logging
 fn get_system_info() -> (String, String) {
     let version = env!("CARGO_PKG_VERSION");
@@ -37,10 +49,10 @@ fn initialize_server() -> Result<config::Config> {
     let (version, arch) = get_system_info();
     info!("Starting Memory-MCP server v{}", version);
 
-    // Verify Windows platform
+    // Verify the platform is supported
     verify_platform()?;
 
-    info!("Platform check: Windows ✓");
+    info!("Platform check: {} ✓", platform_name());
     info!("Architecture: {}", arch);
 
     // Load configuration
@@ -62,20 +74,22 @@ fn initialize_server() -> Result<config::Config> {
 }
 
 /// Start the MCP server with the given configuration
-async fn start_server(_config: config::Config) -> Result<()> {
-    // TODO: Initialize MCP server with configuration
-    info!("MCP server initialization pending implementation");
-
-    // Placeholder for keeping server running
-    info!("Memory-MCP ready. Press Ctrl+C to shutdown.");
-    tokio::signal::ctrl_c().await?;
+///
+/// Serves JSON-RPC over stdio until stdin is closed or Ctrl+C is received.
+async fn start_server(config: config::Config) -> Result<()> {
+    let server = McpServer::new(config);
+
+    info!("Memory-MCP ready on stdio. Press Ctrl+C to shutdown.");
+    tokio::select! {
+        result = server.serve_stdio() => result?,
+        result = tokio::signal::ctrl_c() => result?,
+    }
 
     info!("Shutting down Memory-MCP server");
     Ok(())
 }
 
-#[tokio::main]
-async fn main() -> Result<()> {
+fn main() -> Result<()> {
     // Initialize logging
     init_logging();
 
@@ -83,7 +97,12 @@ async fn main() -> Result<()> {
     let config = initialize_server()?;
 
     // Start the server
-    start_server(config).await
+    let runtime = tokio::runtime::Runtime::new()?;
+    let result = runtime.block_on(start_server(config));
+
+    // Don't wait for tool calls still running on the blocking pool
+    runtime.shutdown_background();
+    result
 }
 
 #[cfg(test)]
@@ -92,17 +111,26 @@ mod tests {
 
     #[test]
     fn test_verify_platform() {
-        // On Windows, this should succeed
-        #[cfg(target_os = "windows")]
+        // On supported platforms, this should succeed
+        #[cfg(any(target_os = "windows", target_os = "linux"))]
         {
             let result = verify_platform();
             assert!(result.is_ok());
         }
 
-        // Platform verification is compile-time on non-Windows
+        // Platform verification is compile-time on other platforms
         // so we can't test the error case at runtime
     }
 
+    #[test]
+    fn test_platform_name() {
+        #[cfg(target_os = "windows")]
+        assert_eq!(platform_name(), "Windows");
+
+        #[cfg(target_os = "linux")]
+        assert_eq!(platform_name(), "Linux");
+    }
+
     #[test]
     fn test_get_system_info() {
         let (version, arch) = get_system_info();
@@ -136,7 +164,7 @@ mod tests {
         let platform_result = verify_platform();
         assert!(
             platform_result.is_ok(),
-            "Platform verification should succeed on Windows"
+            "Platform verification should succeed on supported platforms"
         );
 
         let (version, arch) = get_system_info();
@@ -214,7 +242,7 @@ mod tests {
         assert!(!arch.is_empty());
 
         let os = std::env::consts::OS;
-        assert_eq!(os, "windows");
+        assert!(os == "windows" || os == "linux");
     }
 
     #[cfg(target_os = "windows")]
@@ -0,0 +1,366 @@
+//! Tool handlers backed by the process and memory subsystems
+
+use crate::config::ScannerConfig;
+use crate::core::types::{
+    Address, MemoryError, MemoryResult, MemoryValue, ScanSession, ScanType, ValueEncoding,
+    ValueType,
+};
+use crate::memory::backend::MemoryBackend;
+use crate::memory::writer::MemoryWrite;
+use crate::memory::{
+    MemoryPolicy, MemoryReader, MemoryScanner, SafeMemoryWriter, ScanCriteria, ScanOptions,
+    ScanPattern, ScanPredicate, ValueScanner, WriteJournal,
+};
+use crate::process::{
+    enumerate_processes, ModuleEnumerator, ProcessHandle, ProcessInfo, ProcessPolicy,
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::str::FromStr;
+
+/// Address argument accepted either as a hex string or as an integer
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum AddressArg {
+    Text(String),
+    Number(usize),
+}
+
+impl AddressArg {
+    /// Resolves the argument into an address
+    pub fn resolve(&self) -> MemoryResult<Address> {
+        match self {
+            AddressArg::Text(text) => Address::from_str(text),
+            AddressArg::Number(value) => Ok(Address::new(*value)),
+        }
+    }
+}
+
+/// Arguments for `list_processes`
+#[derive(Debug, Default, Deserialize)]
+pub struct ListProcessesArgs {
+    /// Optional case-insensitive name filter
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+/// Arguments for `list_modules`
+#[derive(Debug, Deserialize)]
+pub struct ListModulesArgs {
+    pub pid: u32,
+}
+
+/// Arguments for `read_memory`
+#[derive(Debug, Deserialize)]
+pub struct ReadMemoryArgs {
+    pub pid: u32,
+    pub address: AddressArg,
+    pub value_type: ValueType,
+    /// Byte count for `bytes`, maximum characters for strings
+    #[serde(default)]
+    pub length: Option<usize>,
+    /// Byte order and transforms of the stored value
+    #[serde(default)]
+    pub encoding: ValueEncoding,
+}
+
+/// Arguments for `write_memory`
+#[derive(Debug, Deserialize)]
+pub struct WriteMemoryArgs {
+    pub pid: u32,
+    pub address: AddressArg,
+    pub value: MemoryValue,
+    /// Byte order and transforms of the stored value
+    #[serde(default)]
+    pub encoding: ValueEncoding,
+}
+
+/// Arguments for `scan_memory`
+#[derive(Debug, Deserialize)]
+pub struct ScanMemoryArgs {
+    pub pid: u32,
+    #[serde(default = "default_scan_type")]
+    pub scan_type: ScanType,
+    /// Value to search for
+    #[serde(default)]
+    pub value: Option<MemoryValue>,
+    /// AOB pattern such as `"48 8B ?? ?? 89"` or `"48 8B 05 & ?? ?? ?? ?? [0-4] (74|75)"`
+    #[serde(default)]
+    pub pattern: Option<String>,
+    /// Comparison for a typed value scan, e.g. `{"op": "between", "low": ..., "high": ...}`
+    #[serde(default)]
+    pub predicate: Option<ScanPredicate>,
+    /// Type read by a predicate scan; defaults to the type of the predicate's value
+    #[serde(default)]
+    pub value_type: Option<ValueType>,
+    #[serde(default)]
+    pub max_results: Option<usize>,
+    #[serde(default)]
+    pub writable_only: bool,
+    #[serde(default)]
+    pub executable_only: bool,
+}
+
+/// Arguments for `watch_memory`
+#[derive(Debug, Deserialize)]
+pub struct WatchMemoryArgs {
+    pub pid: u32,
+    pub address: AddressArg,
+    pub value_type: ValueType,
+    /// Byte count for `bytes`, maximum characters for strings
+    #[serde(default)]
+    pub length: Option<usize>,
+    /// Name reported in change notifications
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+/// Arguments for `unwatch_memory`
+#[derive(Debug, Deserialize)]
+pub struct UnwatchMemoryArgs {
+    pub pid: u32,
+    /// Id returned by `watch_memory`
+    pub id: u64,
+}
+
+fn default_scan_type() -> ScanType {
+    ScanType::Exact
+}
+
+/// Converts process information into a JSON object
+fn process_to_json(process: &ProcessInfo) -> Value {
+    json!({
+        "pid": process.pid,
+        "name": process.name,
+        "path": process.path.as_ref().map(|p| p.display().to_string()),
+        "parent_pid": process.parent_pid,
+        "architecture": process.architecture.to_string(),
+        "thread_count": process.thread_count,
+        "is_wow64": process.is_wow64,
+    })
+}
+
+/// Lists running processes
+pub fn list_processes(args: ListProcessesArgs) -> MemoryResult<Value> {
+    let processes = enumerate_processes()?;
+    let listed: Vec<Value> = processes
+        .iter()
+        .filter(|p| args.name.as_deref().map_or(true, |n| p.name_matches(n)))
+        .map(process_to_json)
+        .collect();
+    Ok(json!({ "count": listed.len(), "processes": listed }))
+}
+
+/// Lists modules loaded in a process
+pub fn list_modules(args: ListModulesArgs, processes: &ProcessPolicy) -> MemoryResult<Value> {
+    let access = processes.check(args.pid, false)?;
+    let handle = ProcessHandle::open_for_read(args.pid)?.with_policy_access(access);
+    let modules = ModuleEnumerator::new(handle).enumerate()?;
+    Ok(json!({ "pid": args.pid, "count": modules.len(), "modules": modules }))
+}
+
+/// Reads a typed value from process memory, within the policy's read limit
+pub fn read_memory(
+    args: ReadMemoryArgs,
+    policy: &MemoryPolicy,
+    processes: &ProcessPolicy,
+) -> MemoryResult<Value> {
+    let address = args.address.resolve()?;
+    let access = processes.check(args.pid, false)?;
+    let handle = ProcessHandle::open_for_read(args.pid)?.with_policy_access(access);
+    let reader = MemoryReader::new(&handle).with_policy(policy.clone());
+    let value = if args.encoding.is_identity() {
+        reader.read_value_len(address, args.value_type, args.length)?
+    } else {
+        reader.read_encoded(address, args.value_type, &args.encoding)?
+    };
+    Ok(json!({
+        "address": address.to_string(),
+        "value": value,
+        "display": value.to_string(),
+    }))
+}
+
+/// Writes a typed value to process memory, subject to the policy's write protection
+///
+/// With backups enabled, the replaced bytes are recorded in `journal`.
+pub fn write_memory(
+    args: WriteMemoryArgs,
+    policy: &MemoryPolicy,
+    journal: &WriteJournal,
+    processes: &ProcessPolicy,
+) -> MemoryResult<Value> {
+    let address = args.address.resolve()?;
+    let access = processes.check(args.pid, true)?;
+    let handle = ProcessHandle::open_for_read_write(args.pid)?.with_policy_access(access);
+    let writer = SafeMemoryWriter::with_policy(&handle, policy, journal);
+    writer.write_encoded(address, &args.value, &args.encoding)?;
+    Ok(json!({
+        "address": address.to_string(),
+        "bytes_written": args.value.size_for(handle.architecture()),
+    }))
+}
+
+/// Scans process memory for a value or byte pattern with the configured threads and chunk size
+///
+/// With a predicate, typed values satisfying it are listed instead.
+pub fn scan_memory(
+    args: ScanMemoryArgs,
+    scanner: &ScannerConfig,
+    processes: &ProcessPolicy,
+) -> MemoryResult<Value> {
+    let mut options = ScanOptions {
+        writable_only: args.writable_only,
+        executable_only: args.executable_only,
+        ..ScanOptions::default()
+    };
+    if args.max_results.is_some() {
+        options.max_results = args.max_results;
+    }
+
+    if let Some(predicate) = args.predicate {
+        return scan_values(args.pid, predicate, args.value_type, &options, processes);
+    }
+    if args.scan_type != ScanType::Exact {
+        return Err(MemoryError::UnsupportedOperation(format!(
+            "Scan type {:?} needs a 'predicate' instead of a value or pattern",
+            args.scan_type
+        )));
+    }
+
+    let pattern = match (&args.pattern, &args.value) {
+        (Some(pattern), _) => ScanPattern::from_hex_string(pattern)?,
+        (None, Some(value)) => ScanPattern::Exact(value.to_bytes()),
+        (None, None) => {
+            return Err(MemoryError::InvalidPattern(
+                "Either 'value' or 'pattern' is required".to_string(),
+            ))
+        }
+    };
+
+    let access = processes.check(args.pid, false)?;
+    let handle = ProcessHandle::open_for_read(args.pid)?.with_policy_access(access);
+    let matches = MemoryScanner::with_config(&handle, scanner).scan_matches(&pattern, options)?;
+    let listed: Vec<String> = matches.iter().map(|m| m.address.to_string()).collect();
+    let mut result = json!({ "count": listed.len(), "addresses": listed });
+
+    if matches!(&pattern, ScanPattern::Signature(s) if s.capture().is_some()) {
+        let captures: Vec<Option<usize>> = matches.iter().map(|m| m.capture_offset).collect();
+        result["capture_offsets"] = json!(captures);
+    }
+    Ok(result)
+}
+
+/// Lists typed values satisfying `predicate`, converted to criteria once for the whole scan
+fn scan_values(
+    pid: u32,
+    predicate: ScanPredicate,
+    value_type: Option<ValueType>,
+    options: &ScanOptions,
+    processes: &ProcessPolicy,
+) -> MemoryResult<Value> {
+    let criteria = ScanCriteria::from(predicate);
+    let value_type = value_type
+        .or_else(|| criteria.value.as_ref().map(MemoryValue::value_type))
+        .ok_or_else(|| {
+            MemoryError::InvalidArgument(
+                "'value_type' is required for a predicate without a value".to_string(),
+            )
+        })?;
+    criteria.validate(value_type, false)?;
+
+    let access = processes.check(pid, false)?;
+    let handle = ProcessHandle::open_for_read(pid)?.with_policy_access(access);
+    let mut session = ScanSession::new(String::new(), criteria.scan_type, value_type);
+    ValueScanner::new(&handle).first_scan(&mut session, &criteria, options)?;
+    let listed: Vec<Value> = session
+        .results
+        .iter()
+        .map(|r| json!({ "address": r.address.to_string(), "value": r.value }))
+        .collect();
+    Ok(json!({ "count": listed.len(), "results": listed }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_address_arg_resolve() {
+        let text: AddressArg = serde_json::from_value(json!("0x1000")).unwrap();
+        assert_eq!(text.resolve().unwrap(), Address::new(0x1000));
+
+        let number: AddressArg = serde_json::from_value(json!(4096)).unwrap();
+        assert_eq!(number.resolve().unwrap(), Address::new(0x1000));
+
+        let bad: AddressArg = serde_json::from_value(json!("0xZZ")).unwrap();
+        assert!(bad.resolve().is_err());
+    }
+
+    #[test]
+    fn test_scan_args_defaults() {
+        let args: ScanMemoryArgs =
+            serde_json::from_value(json!({ "pid": 1, "pattern": "90 90" })).unwrap();
+        assert_eq!(args.scan_type, ScanType::Exact);
+        assert!(args.value.is_none());
+        assert!(!args.writable_only);
+    }
+
+    #[test]
+    fn test_scan_requires_value_or_pattern() {
+        let args: ScanMemoryArgs = serde_json::from_value(json!({ "pid": 1 })).unwrap();
+        assert!(matches!(
+            scan_memory(
+                args,
+                &crate::config::Config::default().scanner,
+                &ProcessPolicy::default()
+            ),
+            Err(MemoryError::InvalidPattern(_))
+        ));
+    }
+
+    #[test]
+    fn test_predicate_scan_arguments() {
+        let scan = |arguments: Value| {
+            let args: ScanMemoryArgs = serde_json::from_value(arguments).unwrap();
+            scan_memory(
+                args,
+                &crate::config::Config::default().scanner,
+                &ProcessPolicy::default(),
+            )
+        };
+
+        let args: ScanMemoryArgs = serde_json::from_value(json!({
+            "pid": 1,
+            "predicate": { "op": "bigger_than", "value": { "type": "U32", "value": 10 } }
+        }))
+        .unwrap();
+        assert_eq!(args.predicate.unwrap().scan_type(), ScanType::BiggerThan);
+
+        assert!(matches!(
+            scan(json!({ "pid": 1, "predicate": { "op": "unknown" } })),
+            Err(MemoryError::InvalidArgument(_))
+        ));
+        // A first scan has no previous values to compare against
+        assert!(scan(json!({
+            "pid": 1,
+            "value_type": "u32",
+            "predicate": { "op": "increased" }
+        }))
+        .is_err());
+    }
+
+    #[test]
+    fn test_scan_rejects_unsupported_type() {
+        let args: ScanMemoryArgs =
+            serde_json::from_value(json!({ "pid": 1, "scan_type": "changed" })).unwrap();
+        assert!(matches!(
+            scan_memory(
+                args,
+                &crate::config::Config::default().scanner,
+                &ProcessPolicy::default()
+            ),
+            Err(MemoryError::UnsupportedOperation(_))
+        ));
+    }
+}
@@ -0,0 +1,17 @@
+//! Model Context Protocol server for Memory-MCP
+//!
+//! Implements the MCP `initialize`, `tools/list` and `tools/call` methods over
+//! newline-delimited JSON-RPC 2.0, exposing process and memory operations as tools.
+//! Changes to watched values are pushed to clients as notifications.
+
+pub mod handlers;
+pub mod protocol;
+pub mod schema;
+pub mod server;
+pub mod tools;
+pub mod watches;
+
+pub use protocol::{JsonRpcError, JsonRpcRequest, JsonRpcResponse, PROTOCOL_VERSION};
+pub use server::{McpServer, SERVER_NAME};
+pub use tools::{call_tool, tool_definitions, ToolDefinition, ToolError};
+pub use watches::{WatchHub, CHANGE_NOTIFICATION};
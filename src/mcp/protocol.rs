@@ -0,0 +1,168 @@
+//! JSON-RPC 2.0 message types used by the MCP transport
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// MCP protocol revision implemented by this server
+pub const PROTOCOL_VERSION: &str = "2024-11-05";
+
+/// JSON-RPC version string
+pub const JSONRPC_VERSION: &str = "2.0";
+
+/// Invalid JSON was received
+pub const PARSE_ERROR: i64 = -32700;
+/// The JSON sent is not a valid request object
+pub const INVALID_REQUEST: i64 = -32600;
+/// The method does not exist
+pub const METHOD_NOT_FOUND: i64 = -32601;
+/// Invalid method parameters
+pub const INVALID_PARAMS: i64 = -32602;
+/// Internal server error
+pub const INTERNAL_ERROR: i64 = -32603;
+
+/// Incoming JSON-RPC request or notification
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonRpcRequest {
+    pub jsonrpc: String,
+    /// Request id; `None` for notifications
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<Value>,
+    pub method: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub params: Option<Value>,
+}
+
+impl JsonRpcRequest {
+    /// Creates a new request with the given id
+    pub fn new(id: impl Into<Value>, method: impl Into<String>, params: Option<Value>) -> Self {
+        JsonRpcRequest {
+            jsonrpc: JSONRPC_VERSION.to_string(),
+            id: Some(id.into()),
+            method: method.into(),
+            params,
+        }
+    }
+
+    /// Creates a notification, which carries no id and gets no response
+    pub fn notification(method: impl Into<String>, params: Option<Value>) -> Self {
+        JsonRpcRequest {
+            jsonrpc: JSONRPC_VERSION.to_string(),
+            id: None,
+            method: method.into(),
+            params,
+        }
+    }
+
+    /// Checks if this message is a notification (no response expected)
+    pub fn is_notification(&self) -> bool {
+        self.id.is_none()
+    }
+}
+
+/// Error object carried by a failed response
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JsonRpcError {
+    pub code: i64,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+impl JsonRpcError {
+    /// Creates a new error object
+    pub fn new(code: i64, message: impl Into<String>) -> Self {
+        JsonRpcError {
+            code,
+            message: message.into(),
+            data: None,
+        }
+    }
+
+    /// Creates a method not found error
+    pub fn method_not_found(method: &str) -> Self {
+        Self::new(METHOD_NOT_FOUND, format!("Method not found: {}", method))
+    }
+
+    /// Creates an invalid params error
+    pub fn invalid_params(message: impl Into<String>) -> Self {
+        Self::new(INVALID_PARAMS, message)
+    }
+}
+
+/// Outgoing JSON-RPC response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonRpcResponse {
+    pub jsonrpc: String,
+    pub id: Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<JsonRpcError>,
+}
+
+impl JsonRpcResponse {
+    /// Creates a successful response
+    pub fn success(id: Value, result: Value) -> Self {
+        JsonRpcResponse {
+            jsonrpc: JSONRPC_VERSION.to_string(),
+            id,
+            result: Some(result),
+            error: None,
+        }
+    }
+
+    /// Creates an error response
+    pub fn failure(id: Value, error: JsonRpcError) -> Self {
+        JsonRpcResponse {
+            jsonrpc: JSONRPC_VERSION.to_string(),
+            id,
+            result: None,
+            error: Some(error),
+        }
+    }
+
+    /// Checks if the response carries an error
+    pub fn is_error(&self) -> bool {
+        self.error.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_request_roundtrip() {
+        let request = JsonRpcRequest::new(1, "tools/list", None);
+        let json = serde_json::to_string(&request).unwrap();
+        assert!(!json.contains("params"));
+
+        let parsed: JsonRpcRequest = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.method, "tools/list");
+        assert_eq!(parsed.id, Some(json!(1)));
+        assert!(!parsed.is_notification());
+    }
+
+    #[test]
+    fn test_notification_has_no_id() {
+        let parsed: JsonRpcRequest =
+            serde_json::from_str(r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#)
+                .unwrap();
+        assert!(parsed.is_notification());
+    }
+
+    #[test]
+    fn test_response_serialization() {
+        let ok = JsonRpcResponse::success(json!(7), json!({"ok": true}));
+        let value = serde_json::to_value(&ok).unwrap();
+        assert_eq!(value["id"], json!(7));
+        assert!(value.get("error").is_none());
+
+        let err = JsonRpcResponse::failure(json!("a"), JsonRpcError::method_not_found("nope"));
+        assert!(err.is_error());
+        let value = serde_json::to_value(&err).unwrap();
+        assert_eq!(value["error"]["code"], json!(METHOD_NOT_FOUND));
+        assert!(value.get("result").is_none());
+    }
+}
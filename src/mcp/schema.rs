@@ -0,0 +1,383 @@
+//! JSON schemas for tool arguments, derived from the serde types in `core::types`
+//!
+//! Enum names and tags are taken from the actual serde output of each type so the
+//! advertised schema can never drift from what the deserializer accepts.
+
+use crate::core::types::{ElementType, MemoryValue, ScanType, ValueType};
+use crate::memory::ScanPredicate;
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+
+/// Returns the serde name of a unit enum variant
+fn serde_name<T: Serialize>(value: &T) -> String {
+    match serde_json::to_value(value) {
+        Ok(Value::String(name)) => name,
+        Ok(other) => other.to_string(),
+        Err(_) => String::new(),
+    }
+}
+
+/// Returns a zero-initialised sample value for a value type
+fn sample_value(value_type: ValueType) -> Option<MemoryValue> {
+    MemoryValue::from_bytes(&[0u8; 16], value_type)
+}
+
+/// Serde names of the types an array can hold
+fn element_names() -> Vec<String> {
+    ValueType::all()
+        .iter()
+        .filter_map(|&vt| ElementType::try_from(vt).ok())
+        .map(|elem| serde_name(&elem))
+        .collect()
+}
+
+/// Maps a serialized JSON sample to its JSON schema type
+fn json_kind(sample: &Value) -> Value {
+    match sample {
+        Value::Number(n) if n.is_f64() => json!({ "type": "number" }),
+        Value::Number(_) => json!({ "type": "integer" }),
+        Value::String(_) => json!({ "type": "string" }),
+        Value::Bool(_) => json!({ "type": "boolean" }),
+        Value::Array(_) => json!({ "type": "array", "items": { "type": "integer" } }),
+        _ => json!({}),
+    }
+}
+
+/// Schema for a `ValueType`
+pub fn value_type_schema() -> Value {
+    let names: Vec<String> = ValueType::all().iter().map(serde_name).collect();
+    json!({
+        "oneOf": [
+            { "type": "string", "enum": names },
+            {
+                "type": "object",
+                "properties": {
+                    "array": {
+                        "type": "object",
+                        "properties": {
+                            "elem": { "type": "string", "enum": element_names() },
+                            "len": { "type": "integer", "minimum": 0 }
+                        },
+                        "required": ["elem", "len"]
+                    }
+                },
+                "required": ["array"]
+            }
+        ],
+        "description": "Data type used to interpret memory"
+    })
+}
+
+/// Schema for a `ScanType`
+pub fn scan_type_schema() -> Value {
+    let names: Vec<String> = ScanType::all().iter().map(serde_name).collect();
+    json!({
+        "type": "string",
+        "enum": names,
+        "description": "Comparison performed by the scan"
+    })
+}
+
+/// Schema for a tagged `MemoryValue` (`{"type": "U32", "value": 42}`)
+pub fn memory_value_schema() -> Value {
+    let mut variants: Vec<Value> = ValueType::all()
+        .iter()
+        .filter_map(|&vt| sample_value(vt))
+        .filter_map(|sample| serde_json::to_value(&sample).ok())
+        .map(|sample| {
+            json!({
+                "type": "object",
+                "properties": {
+                    "type": { "const": sample["type"] },
+                    "value": json_kind(&sample["value"]),
+                },
+                "required": ["type", "value"]
+            })
+        })
+        .collect();
+    variants.push(json!({
+        "type": "object",
+        "properties": {
+            "type": { "const": "Array" },
+            "value": {
+                "type": "object",
+                "properties": {
+                    "elem": { "type": "string", "enum": element_names() },
+                    "values": { "type": "array", "items": { "type": "object" } }
+                },
+                "required": ["elem", "values"]
+            }
+        },
+        "required": ["type", "value"]
+    }));
+
+    json!({
+        "oneOf": variants,
+        "description": "Typed memory value"
+    })
+}
+
+/// Schema for a `ScanPredicate` (`{"op": "between", "low": {...}, "high": {...}}`)
+///
+/// Fields holding a `MemoryValue` take the same tagged values as `value`.
+pub fn predicate_schema() -> Value {
+    let value = MemoryValue::U8(0);
+    let tolerance = Some(0.0);
+    let samples = [
+        ScanPredicate::Unknown,
+        ScanPredicate::Exact {
+            value: value.clone(),
+            tolerance,
+        },
+        ScanPredicate::BiggerThan {
+            value: value.clone(),
+        },
+        ScanPredicate::SmallerThan {
+            value: value.clone(),
+        },
+        ScanPredicate::Between {
+            low: value.clone(),
+            high: value.clone(),
+            tolerance,
+        },
+        ScanPredicate::Increased,
+        ScanPredicate::Decreased,
+        ScanPredicate::Changed { tolerance },
+        ScanPredicate::Unchanged { tolerance },
+        ScanPredicate::IncreasedBy {
+            delta: value.clone(),
+            tolerance,
+        },
+        ScanPredicate::DecreasedBy {
+            delta: value,
+            tolerance,
+        },
+        ScanPredicate::ChangedByPercent { min: 0.0, max: 0.0 },
+    ];
+
+    let variants: Vec<Value> = samples
+        .iter()
+        .filter_map(|sample| serde_json::to_value(sample).ok())
+        .filter_map(|sample| sample.as_object().cloned())
+        .map(|fields| {
+            let mut properties = Map::new();
+            let mut required = Vec::new();
+            for (name, sample) in fields {
+                let schema = match (name.as_str(), &sample) {
+                    ("op", op) => json!({ "const": op }),
+                    (_, Value::Object(_)) => json!({
+                        "type": "object",
+                        "description": "Typed memory value such as {\"type\": \"U32\", \"value\": 42}"
+                    }),
+                    _ => json!({ "type": "number" }),
+                };
+                if name != "tolerance" {
+                    required.push(name.clone());
+                }
+                properties.insert(name, schema);
+            }
+            json!({ "type": "object", "properties": properties, "required": required })
+        })
+        .collect();
+
+    json!({
+        "oneOf": variants,
+        "description": "Comparison for a typed value scan; tolerance applies to floats"
+    })
+}
+
+/// Schema for a `ValueEncoding` (`{"byte_order": "big", "transforms": [...]}`)
+pub fn encoding_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "byte_order": { "type": "string", "enum": ["little", "big"] },
+            "transforms": {
+                "type": "array",
+                "items": {
+                    "oneOf": [
+                        {
+                            "type": "object",
+                            "properties": {
+                                "kind": { "const": "xor" },
+                                "key": { "type": "integer" }
+                            },
+                            "required": ["kind", "key"]
+                        },
+                        {
+                            "type": "object",
+                            "properties": {
+                                "kind": { "const": "linear" },
+                                "scale": { "type": "number" },
+                                "offset": { "type": "number" }
+                            },
+                            "required": ["kind", "scale"]
+                        }
+                    ]
+                }
+            }
+        },
+        "description": "How numeric values are stored; defaults to plain little-endian"
+    })
+}
+
+/// Schema for a memory address given as hex string or integer
+pub fn address_schema() -> Value {
+    json!({
+        "type": ["string", "integer"],
+        "description": "Memory address, e.g. \"0x7FF6A0B0C0D0\""
+    })
+}
+
+/// Schema for a process id
+pub fn pid_schema() -> Value {
+    json!({
+        "type": "integer",
+        "minimum": 0,
+        "description": "Target process id"
+    })
+}
+
+/// Builds an object schema from `(name, schema)` properties and required names
+pub fn object_schema(properties: Vec<(&str, Value)>, required: &[&str]) -> Value {
+    let mut props = Map::new();
+    for (name, schema) in properties {
+        props.insert(name.to_string(), schema);
+    }
+    json!({
+        "type": "object",
+        "properties": props,
+        "required": required,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::types::{ByteOrder, ValueTransform};
+
+    #[test]
+    fn test_value_type_schema_matches_serde() {
+        let schema = value_type_schema();
+        let names = schema["oneOf"][0]["enum"].as_array().unwrap();
+        assert_eq!(names.len(), ValueType::all().len());
+        assert!(names.contains(&json!("u32")));
+        assert!(names.contains(&json!("wstring")));
+
+        for name in names {
+            let parsed: ValueType = serde_json::from_value(name.clone()).unwrap();
+            assert_eq!(&json!(serde_name(&parsed)), name);
+        }
+
+        let array = &schema["oneOf"][1]["properties"]["array"];
+        let elems = array["properties"]["elem"]["enum"].as_array().unwrap();
+        assert!(elems.contains(&json!("pointer")));
+        assert!(!elems.contains(&json!("string")));
+        let parsed: ValueType =
+            serde_json::from_value(json!({ "array": { "elem": elems[0], "len": 4 } })).unwrap();
+        assert_eq!(parsed, ValueType::array(ValueType::I8, 4).unwrap());
+    }
+
+    #[test]
+    fn test_scan_type_schema_matches_serde() {
+        let schema = scan_type_schema();
+        let names = schema["enum"].as_array().unwrap();
+        assert!(names.contains(&json!("increased_by")));
+        for name in names {
+            assert!(serde_json::from_value::<ScanType>(name.clone()).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_encoding_schema_matches_serde() {
+        let schema = encoding_schema();
+        for order in schema["properties"]["byte_order"]["enum"]
+            .as_array()
+            .unwrap()
+        {
+            assert!(serde_json::from_value::<ByteOrder>(order.clone()).is_ok());
+        }
+        let kinds: Vec<Value> = schema["properties"]["transforms"]["items"]["oneOf"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|v| v["properties"]["kind"]["const"].clone())
+            .collect();
+        let transforms = [
+            ValueTransform::Xor { key: 1 },
+            ValueTransform::Linear {
+                scale: 1.0,
+                offset: 0.0,
+            },
+        ];
+        for (kind, transform) in kinds.iter().zip(transforms) {
+            assert_eq!(&serde_json::to_value(transform).unwrap()["kind"], kind);
+        }
+    }
+
+    #[test]
+    fn test_memory_value_schema_variants() {
+        let schema = memory_value_schema();
+        let variants = schema["oneOf"].as_array().unwrap();
+        assert_eq!(variants.len(), ValueType::all().len() + 1);
+
+        let u32_variant = variants
+            .iter()
+            .find(|v| v["properties"]["type"]["const"] == json!("U32"))
+            .unwrap();
+        assert_eq!(u32_variant["properties"]["value"]["type"], json!("integer"));
+
+        let f64_variant = variants
+            .iter()
+            .find(|v| v["properties"]["type"]["const"] == json!("F64"))
+            .unwrap();
+        assert_eq!(f64_variant["properties"]["value"]["type"], json!("number"));
+
+        let bool_variant = variants
+            .iter()
+            .find(|v| v["properties"]["type"]["const"] == json!("Bool"))
+            .unwrap();
+        assert_eq!(
+            bool_variant["properties"]["value"]["type"],
+            json!("boolean")
+        );
+    }
+
+    #[test]
+    fn test_predicate_schema_matches_serde() {
+        let schema = predicate_schema();
+        let variants = schema["oneOf"].as_array().unwrap();
+        assert_eq!(variants.len(), 12);
+
+        let between = variants
+            .iter()
+            .find(|v| v["properties"]["op"]["const"] == json!("between"))
+            .unwrap();
+        assert_eq!(between["required"], json!(["high", "low", "op"]));
+        assert!(between["properties"]["tolerance"].is_object());
+
+        for variant in variants {
+            let mut sample = Map::new();
+            for name in variant["required"].as_array().unwrap() {
+                let property = &variant["properties"][name.as_str().unwrap()];
+                let value = match property.get("const") {
+                    Some(op) => op.clone(),
+                    None if property["type"] == json!("object") => {
+                        json!({ "type": "U8", "value": 1 })
+                    }
+                    None => json!(1.0),
+                };
+                sample.insert(name.as_str().unwrap().to_string(), value);
+            }
+            assert!(serde_json::from_value::<ScanPredicate>(Value::Object(sample)).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_object_schema() {
+        let schema = object_schema(vec![("pid", pid_schema())], &["pid"]);
+        assert_eq!(schema["type"], json!("object"));
+        assert_eq!(schema["required"], json!(["pid"]));
+        assert!(schema["properties"]["pid"].is_object());
+    }
+}
@@ -0,0 +1,535 @@
+//! MCP server speaking newline-delimited JSON-RPC over any async byte stream
+
+use super::protocol::{
+    JsonRpcError, JsonRpcRequest, JsonRpcResponse, INVALID_REQUEST, JSONRPC_VERSION, PARSE_ERROR,
+    PROTOCOL_VERSION,
+};
+use super::tools::{call_tool, parse_args, tool_definitions, ToolError};
+use super::watches::{change_notification, WatchHub};
+use crate::config::Config;
+use crate::core::types::MemoryResult;
+use crate::memory::{MemoryPolicy, WriteJournal};
+use crate::process::ProcessPolicy;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::mpsc;
+use tracing::{debug, warn};
+
+/// Name reported to clients during initialization
+pub const SERVER_NAME: &str = "memory-mcp";
+
+/// Parameters of a `tools/call` request
+#[derive(Debug, Deserialize)]
+struct ToolCallParams {
+    name: String,
+    #[serde(default)]
+    arguments: Value,
+}
+
+/// A raw message after parsing
+enum Parsed {
+    Request(JsonRpcRequest),
+    /// Not a valid request; carries the error response, or `None` when the
+    /// message has no id and so cannot be answered
+    Invalid(Option<JsonRpcResponse>),
+}
+
+/// Model Context Protocol server exposing memory tools
+///
+/// Clones share the same configuration, watch lists and write journal.
+#[derive(Clone)]
+pub struct McpServer {
+    config: Arc<Config>,
+    watches: Arc<WatchHub>,
+    processes: Arc<ProcessPolicy>,
+    /// Backups of the bytes replaced by `write_memory`
+    journal: Arc<WriteJournal>,
+}
+
+impl McpServer {
+    /// Create a new server with the given configuration
+    pub fn new(config: Config) -> Self {
+        let watches = WatchHub::new(Duration::from_millis(config.memory.watch_interval_ms))
+            .with_policy(MemoryPolicy::from_config(&config.memory));
+        let processes = ProcessPolicy::from_config(&config.policy);
+        McpServer {
+            config: Arc::new(config),
+            watches: Arc::new(watches),
+            processes: Arc::new(processes),
+            journal: Arc::new(WriteJournal::new()),
+        }
+    }
+
+    /// Get the server configuration
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Serve requests on the process stdin/stdout until stdin is closed
+    pub async fn serve_stdio(&self) -> MemoryResult<()> {
+        self.serve(tokio::io::stdin(), tokio::io::stdout()).await
+    }
+
+    /// Serve requests read from `reader`, writing responses to `writer`
+    ///
+    /// Each message is a single line of JSON. Tool calls run on the blocking
+    /// thread pool and may answer out of order, so a long scan does not hold
+    /// up other requests or notifications. The connection is subscribed to
+    /// watch events for its lifetime and receives each change as a
+    /// notification. Returns once the reader reaches EOF and every pending
+    /// tool call has answered.
+    pub async fn serve<R, W>(&self, reader: R, mut writer: W) -> MemoryResult<()>
+    where
+        R: AsyncRead + Unpin,
+        W: AsyncWrite + Unpin,
+    {
+        let mut lines = BufReader::new(reader).lines();
+        let mut events = self.watches.subscribe();
+        let (responses_tx, mut responses) = mpsc::unbounded_channel();
+
+        loop {
+            tokio::select! {
+                line = lines.next_line() => {
+                    let Some(line) = line? else {
+                        break;
+                    };
+                    if line.trim().is_empty() {
+                        continue;
+                    }
+                    let request = match self.parse_message(&line) {
+                        Parsed::Request(request) => request,
+                        Parsed::Invalid(response) => {
+                            if let Some(response) = response {
+                                write_message(&mut writer, &response).await?;
+                            }
+                            continue;
+                        }
+                    };
+                    if request.method == "tools/call" {
+                        self.spawn_request(request, responses_tx.clone());
+                    } else if let Some(response) = self.handle_request(request) {
+                        write_message(&mut writer, &response).await?;
+                    }
+                }
+                Some(response) = responses.recv() => {
+                    write_message(&mut writer, &response).await?;
+                }
+                event = events.recv() => match event {
+                    Ok(event) => write_message(&mut writer, &change_notification(&event)).await?,
+                    Err(RecvError::Lagged(missed)) => {
+                        warn!("Client missed {} watch notifications", missed)
+                    }
+                    // The hub owns the sender, so this only happens while shutting down
+                    Err(RecvError::Closed) => break,
+                },
+            }
+        }
+
+        // Answer the tool calls still running before closing
+        drop(responses_tx);
+        while let Some(response) = responses.recv().await {
+            write_message(&mut writer, &response).await?;
+        }
+        Ok(())
+    }
+
+    /// Handle a request on the blocking thread pool, sending its response to `responses`
+    fn spawn_request(
+        &self,
+        request: JsonRpcRequest,
+        responses: mpsc::UnboundedSender<JsonRpcResponse>,
+    ) {
+        let server = self.clone();
+        let method = request.method.clone();
+        tokio::spawn(async move {
+            match tokio::task::spawn_blocking(move || server.handle_request(request)).await {
+                Ok(Some(response)) => {
+                    // The receiver only goes away when serving failed
+                    let _ = responses.send(response);
+                }
+                Ok(None) => {}
+                Err(e) => warn!("MCP request {} did not complete: {}", method, e),
+            }
+        });
+    }
+
+    /// Handle one raw JSON message, returning the response if one is due
+    pub fn handle_message(&self, message: &str) -> Option<JsonRpcResponse> {
+        match self.parse_message(message) {
+            Parsed::Request(request) => self.handle_request(request),
+            Parsed::Invalid(response) => response,
+        }
+    }
+
+    /// Parse one raw JSON message into a request
+    fn parse_message(&self, message: &str) -> Parsed {
+        let value: Value = match serde_json::from_str(message) {
+            Ok(value) => value,
+            Err(e) => {
+                return Parsed::Invalid(Some(JsonRpcResponse::failure(
+                    Value::Null,
+                    JsonRpcError::new(PARSE_ERROR, format!("Parse error: {}", e)),
+                )))
+            }
+        };
+
+        let id = value.get("id").cloned().filter(|id| !id.is_null());
+        let error = match serde_json::from_value::<JsonRpcRequest>(value) {
+            Ok(request) if request.jsonrpc == JSONRPC_VERSION => return Parsed::Request(request),
+            Ok(_) => JsonRpcError::new(INVALID_REQUEST, "Unsupported JSON-RPC version"),
+            Err(e) => JsonRpcError::new(INVALID_REQUEST, format!("Invalid request: {}", e)),
+        };
+        match id {
+            Some(id) => Parsed::Invalid(Some(JsonRpcResponse::failure(id, error))),
+            None => {
+                warn!("Ignoring invalid notification: {}", error.message);
+                Parsed::Invalid(None)
+            }
+        }
+    }
+
+    /// Handle a parsed request, returning `None` for notifications
+    pub fn handle_request(&self, request: JsonRpcRequest) -> Option<JsonRpcResponse> {
+        debug!("MCP request: {}", request.method);
+
+        let result = match request.method.as_str() {
+            "initialize" => Ok(self.initialize_result()),
+            "ping" => Ok(json!({})),
+            "tools/list" => Ok(json!({ "tools": tool_definitions() })),
+            "tools/call" => self.call_tool(request.params.clone()),
+            method if method.starts_with("notifications/") => {
+                // Notifications never get a response, known or not
+                return None;
+            }
+            method => Err(JsonRpcError::method_not_found(method)),
+        };
+
+        let id = request.id?;
+        Some(match result {
+            Ok(result) => JsonRpcResponse::success(id, result),
+            Err(error) => JsonRpcResponse::failure(id, error),
+        })
+    }
+
+    /// Result of the `initialize` handshake
+    fn initialize_result(&self) -> Value {
+        json!({
+            "protocolVersion": PROTOCOL_VERSION,
+            "capabilities": {
+                "tools": { "listChanged": false }
+            },
+            "serverInfo": {
+                "name": SERVER_NAME,
+                "version": crate::core::VERSION,
+            }
+        })
+    }
+
+    /// Execute a `tools/call` request
+    ///
+    /// Execution failures are reported inside the tool result with `isError` set,
+    /// so the client model can see and react to them. Malformed calls are protocol errors.
+    fn call_tool(&self, params: Option<Value>) -> Result<Value, JsonRpcError> {
+        let params: ToolCallParams = params
+            .ok_or_else(|| JsonRpcError::invalid_params("Missing tools/call params"))
+            .and_then(|p| {
+                serde_json::from_value(p).map_err(|e| JsonRpcError::invalid_params(e.to_string()))
+            })?;
+
+        let result = match params.name.as_str() {
+            "watch_memory" => parse_args(&params.name, params.arguments)
+                .and_then(|args| Ok(self.watches.watch(args, &self.processes)?)),
+            "unwatch_memory" => parse_args(&params.name, params.arguments)
+                .and_then(|args| Ok(self.watches.unwatch(args)?)),
+            name => call_tool(
+                name,
+                params.arguments,
+                &MemoryPolicy::from_config(&self.config.memory),
+                &self.journal,
+                &self.config.scanner,
+                &self.processes,
+            ),
+        };
+        match result {
+            Ok(value) => Ok(tool_result(&value, false)),
+            Err(ToolError::Memory(e)) => {
+                warn!("Tool {} failed: {}", params.name, e);
+                Ok(tool_result(&json!({ "error": e.to_string() }), true))
+            }
+            Err(e) => Err(JsonRpcError::invalid_params(e.to_string())),
+        }
+    }
+}
+
+/// Write one message as a line of JSON
+async fn write_message<W: AsyncWrite + Unpin>(
+    writer: &mut W,
+    message: &impl Serialize,
+) -> MemoryResult<()> {
+    let mut encoded = serde_json::to_vec(message)?;
+    encoded.push(b'\n');
+    writer.write_all(&encoded).await?;
+    writer.flush().await?;
+    Ok(())
+}
+
+/// Wraps a JSON value as MCP text content
+fn tool_result(value: &Value, is_error: bool) -> Value {
+    let text = serde_json::to_string_pretty(value).unwrap_or_else(|_| value.to_string());
+    json!({
+        "content": [{ "type": "text", "text": text }],
+        "isError": is_error,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mcp::protocol::{INVALID_PARAMS, METHOD_NOT_FOUND};
+
+    fn server() -> McpServer {
+        McpServer::new(Config::default())
+    }
+
+    #[test]
+    fn test_initialize() {
+        let response = server()
+            .handle_message(r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{}}"#)
+            .unwrap();
+        let result = response.result.unwrap();
+        assert_eq!(result["protocolVersion"], json!(PROTOCOL_VERSION));
+        assert_eq!(result["serverInfo"]["name"], json!(SERVER_NAME));
+        assert!(result["capabilities"]["tools"].is_object());
+    }
+
+    #[test]
+    fn test_notification_has_no_response() {
+        let response =
+            server().handle_message(r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#);
+        assert!(response.is_none());
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let response = server().handle_message("{not json").unwrap();
+        assert_eq!(response.error.unwrap().code, PARSE_ERROR);
+        assert_eq!(response.id, Value::Null);
+    }
+
+    #[test]
+    fn test_invalid_version() {
+        let response = server()
+            .handle_message(r#"{"jsonrpc":"1.0","id":3,"method":"ping"}"#)
+            .unwrap();
+        assert_eq!(response.error.unwrap().code, INVALID_REQUEST);
+        assert_eq!(response.id, json!(3));
+    }
+
+    #[test]
+    fn test_invalid_notification_has_no_response() {
+        let server = server();
+        assert!(server
+            .handle_message(r#"{"jsonrpc":"1.0","method":"ping"}"#)
+            .is_none());
+        assert!(server.handle_message(r#"{"jsonrpc":"2.0"}"#).is_none());
+        assert!(server
+            .handle_message(r#"{"jsonrpc":"2.0","method":"tools/call"}"#)
+            .is_none());
+    }
+
+    #[test]
+    fn test_unknown_method() {
+        let response = server()
+            .handle_message(r#"{"jsonrpc":"2.0","id":2,"method":"resources/list"}"#)
+            .unwrap();
+        assert_eq!(response.error.unwrap().code, METHOD_NOT_FOUND);
+    }
+
+    #[test]
+    fn test_tools_list() {
+        let response = server()
+            .handle_message(r#"{"jsonrpc":"2.0","id":4,"method":"tools/list"}"#)
+            .unwrap();
+        let tools = response.result.unwrap()["tools"].clone();
+        assert!(tools
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|t| t["name"] == json!("read_memory")));
+    }
+
+    #[test]
+    fn test_tools_call_unknown_tool() {
+        let response = server()
+            .handle_message(
+                r#"{"jsonrpc":"2.0","id":5,"method":"tools/call","params":{"name":"nope"}}"#,
+            )
+            .unwrap();
+        assert_eq!(response.error.unwrap().code, INVALID_PARAMS);
+    }
+
+    #[test]
+    fn test_tools_call_execution_error() {
+        let response = server()
+            .handle_message(
+                r#"{"jsonrpc":"2.0","id":6,"method":"tools/call","params":{"name":"scan_memory","arguments":{"pid":1}}}"#,
+            )
+            .unwrap();
+        let result = response.result.unwrap();
+        assert_eq!(result["isError"], json!(true));
+        assert_eq!(result["content"][0]["type"], json!("text"));
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "FFI not supported in Miri")]
+    fn test_process_policy_enforced() {
+        static TARGET: u32 = 7;
+        let call = |server: &McpServer, name: &str, arguments: Value| {
+            let request = json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "tools/call",
+                "params": { "name": name, "arguments": arguments }
+            });
+            let result = server.handle_message(&request.to_string()).unwrap();
+            result.result.unwrap()
+        };
+        let read = json!({
+            "pid": std::process::id(),
+            "address": &TARGET as *const u32 as usize,
+            "value_type": "u32"
+        });
+        let write = json!({
+            "pid": std::process::id(),
+            "address": &TARGET as *const u32 as usize,
+            "value": { "type": "U32", "value": 8 }
+        });
+
+        let mut config = Config::default();
+        config.policy.read_only = vec!["*".to_string()];
+        let server = McpServer::new(config);
+        assert_eq!(
+            call(&server, "read_memory", read.clone())["isError"],
+            json!(false)
+        );
+        let result = call(&server, "write_memory", write);
+        assert_eq!(result["isError"], json!(true));
+        let text = result["content"][0]["text"].as_str().unwrap();
+        assert!(text.contains("read-only by rule '*'"), "{}", text);
+
+        let mut config = Config::default();
+        config.policy.deny = vec!["*".to_string()];
+        let server = McpServer::new(config);
+        let result = call(&server, "read_memory", read);
+        assert_eq!(result["isError"], json!(true));
+        let text = result["content"][0]["text"].as_str().unwrap();
+        assert!(text.contains("deny rule '*'"), "{}", text);
+    }
+
+    #[tokio::test]
+    async fn test_serve_over_duplex() {
+        let (mut client_write, server_read) = tokio::io::duplex(64 * 1024);
+        let (server_write, client_read) = tokio::io::duplex(64 * 1024);
+
+        let task = tokio::spawn(async move { server().serve(server_read, server_write).await });
+
+        client_write
+            .write_all(
+                b"{\"jsonrpc\":\"2.0\",\"id\":1,\"method\":\"ping\"}\n\
+                  {\"jsonrpc\":\"2.0\",\"method\":\"notifications/initialized\"}\n\
+                  {\"jsonrpc\":\"2.0\",\"id\":2,\"method\":\"tools/list\"}\n",
+            )
+            .await
+            .unwrap();
+        drop(client_write);
+
+        let mut lines = BufReader::new(client_read).lines();
+        let first: JsonRpcResponse =
+            serde_json::from_str(&lines.next_line().await.unwrap().unwrap()).unwrap();
+        let second: JsonRpcResponse =
+            serde_json::from_str(&lines.next_line().await.unwrap().unwrap()).unwrap();
+
+        assert_eq!(first.id, json!(1));
+        assert_eq!(second.id, json!(2));
+        assert!(task.await.unwrap().is_ok());
+    }
+
+    #[tokio::test]
+    #[cfg_attr(miri, ignore = "FFI not supported in Miri")]
+    async fn test_watch_notifications() {
+        use std::sync::atomic::{AtomicU32, Ordering};
+        static WATCHED: AtomicU32 = AtomicU32::new(1);
+
+        let mut config = Config::default();
+        config.memory.watch_interval_ms = 10;
+        let server = std::sync::Arc::new(McpServer::new(config));
+        let (mut client_write, server_read) = tokio::io::duplex(64 * 1024);
+        let (server_write, client_read) = tokio::io::duplex(64 * 1024);
+        let serving = server.clone();
+        let task = tokio::spawn(async move { serving.serve(server_read, server_write).await });
+        let mut lines = BufReader::new(client_read).lines();
+
+        let call = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "tools/call",
+            "params": {
+                "name": "watch_memory",
+                "arguments": {
+                    "pid": std::process::id(),
+                    "address": WATCHED.as_ptr() as usize,
+                    "value_type": "u32",
+                    "label": "watched"
+                }
+            }
+        });
+        client_write
+            .write_all(format!("{}\n", call).as_bytes())
+            .await
+            .unwrap();
+        let response: JsonRpcResponse =
+            serde_json::from_str(&lines.next_line().await.unwrap().unwrap()).unwrap();
+        let result = response.result.unwrap();
+        assert_eq!(result["isError"], json!(false));
+        let text = result["content"][0]["text"].as_str().unwrap();
+        let id = serde_json::from_str::<Value>(text).unwrap()["id"].clone();
+
+        // Let the first poll record the value before changing it
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        WATCHED.store(2, Ordering::SeqCst);
+        let line = tokio::time::timeout(Duration::from_secs(5), lines.next_line())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        let notification: JsonRpcRequest = serde_json::from_str(&line).unwrap();
+        assert!(notification.is_notification());
+        assert_eq!(notification.method, crate::mcp::CHANGE_NOTIFICATION);
+        let params = notification.params.unwrap();
+        assert_eq!(params["label"], json!("watched"));
+        assert_eq!(params["id"], id);
+        assert_eq!(params["new"], json!({ "type": "U32", "value": 2 }));
+
+        let unwatch = server.handle_message(
+            &json!({
+                "jsonrpc": "2.0",
+                "id": 2,
+                "method": "tools/call",
+                "params": {
+                    "name": "unwatch_memory",
+                    "arguments": { "pid": std::process::id(), "id": id }
+                }
+            })
+            .to_string(),
+        );
+        let text = unwatch.unwrap().result.unwrap()["content"][0]["text"].clone();
+        assert!(text.as_str().unwrap().contains("true"));
+
+        drop(client_write);
+        assert!(task.await.unwrap().is_ok());
+    }
+}
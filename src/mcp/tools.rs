@@ -0,0 +1,256 @@
+//! MCP tool registry: definitions advertised by `tools/list` and dispatch for `tools/call`
+
+use super::handlers;
+use super::schema::{
+    address_schema, encoding_schema, memory_value_schema, object_schema, pid_schema,
+    predicate_schema, scan_type_schema, value_type_schema,
+};
+use crate::config::ScannerConfig;
+use crate::core::types::{MemoryError, MemoryResult};
+use crate::memory::{MemoryPolicy, WriteJournal};
+use crate::process::ProcessPolicy;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{json, Value};
+use thiserror::Error;
+
+/// Tool description returned by `tools/list`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolDefinition {
+    pub name: String,
+    pub description: String,
+    pub input_schema: Value,
+}
+
+impl ToolDefinition {
+    fn new(name: &str, description: &str, input_schema: Value) -> Self {
+        ToolDefinition {
+            name: name.to_string(),
+            description: description.to_string(),
+            input_schema,
+        }
+    }
+}
+
+/// Errors raised while dispatching a tool call
+#[derive(Debug, Error)]
+pub enum ToolError {
+    #[error("Unknown tool: {0}")]
+    UnknownTool(String),
+
+    #[error("Invalid arguments for {tool}: {reason}")]
+    InvalidArguments { tool: String, reason: String },
+
+    #[error(transparent)]
+    Memory(#[from] MemoryError),
+}
+
+/// Returns the definitions of all available tools
+pub fn tool_definitions() -> Vec<ToolDefinition> {
+    vec![
+        ToolDefinition::new(
+            "list_processes",
+            "List running processes, optionally filtered by name",
+            object_schema(
+                vec![(
+                    "name",
+                    json!({ "type": "string", "description": "Process name filter" }),
+                )],
+                &[],
+            ),
+        ),
+        ToolDefinition::new(
+            "list_modules",
+            "List modules loaded in a process",
+            object_schema(vec![("pid", pid_schema())], &["pid"]),
+        ),
+        ToolDefinition::new(
+            "read_memory",
+            "Read a typed value from process memory",
+            object_schema(
+                vec![
+                    ("pid", pid_schema()),
+                    ("address", address_schema()),
+                    ("value_type", value_type_schema()),
+                    (
+                        "length",
+                        json!({
+                            "type": "integer",
+                            "minimum": 0,
+                            "description": "Byte count for bytes, maximum characters for strings"
+                        }),
+                    ),
+                    ("encoding", encoding_schema()),
+                ],
+                &["pid", "address", "value_type"],
+            ),
+        ),
+        ToolDefinition::new(
+            "write_memory",
+            "Write a typed value to process memory",
+            object_schema(
+                vec![
+                    ("pid", pid_schema()),
+                    ("address", address_schema()),
+                    ("value", memory_value_schema()),
+                    ("encoding", encoding_schema()),
+                ],
+                &["pid", "address", "value"],
+            ),
+        ),
+        ToolDefinition::new(
+            "scan_memory",
+            "Scan process memory for a value, an AOB pattern such as \"48 8B ?? ?? 89\", or typed values matching a predicate",
+            object_schema(
+                vec![
+                    ("pid", pid_schema()),
+                    ("scan_type", scan_type_schema()),
+                    ("value", memory_value_schema()),
+                    (
+                        "pattern",
+                        json!({ "type": "string", "description": "Hex byte pattern with ?? and nibble wildcards, [min-max] jumps, (a|b) alternatives and an & capture marker" }),
+                    ),
+                    ("predicate", predicate_schema()),
+                    ("value_type", value_type_schema()),
+                    ("max_results", json!({ "type": "integer", "minimum": 1 })),
+                    ("writable_only", json!({ "type": "boolean" })),
+                    ("executable_only", json!({ "type": "boolean" })),
+                ],
+                &["pid"],
+            ),
+        ),
+        ToolDefinition::new(
+            "watch_memory",
+            "Watch a typed value; each change is sent as a notifications/memory/changed notification",
+            object_schema(
+                vec![
+                    ("pid", pid_schema()),
+                    ("address", address_schema()),
+                    ("value_type", value_type_schema()),
+                    (
+                        "length",
+                        json!({
+                            "type": "integer",
+                            "minimum": 1,
+                            "description": "Byte count for bytes, maximum characters for strings"
+                        }),
+                    ),
+                    (
+                        "label",
+                        json!({ "type": "string", "description": "Name reported in notifications" }),
+                    ),
+                ],
+                &["pid", "address", "value_type"],
+            ),
+        ),
+        ToolDefinition::new(
+            "unwatch_memory",
+            "Stop watching a value",
+            object_schema(
+                vec![
+                    ("pid", pid_schema()),
+                    (
+                        "id",
+                        json!({ "type": "integer", "minimum": 0, "description": "Id returned by watch_memory" }),
+                    ),
+                ],
+                &["pid", "id"],
+            ),
+        ),
+    ]
+}
+
+/// Deserializes tool arguments, mapping failures to `ToolError::InvalidArguments`
+pub(super) fn parse_args<T: DeserializeOwned>(
+    tool: &str,
+    arguments: Value,
+) -> Result<T, ToolError> {
+    let arguments = if arguments.is_null() {
+        json!({})
+    } else {
+        arguments
+    };
+    serde_json::from_value(arguments).map_err(|e| ToolError::InvalidArguments {
+        tool: tool.to_string(),
+        reason: e.to_string(),
+    })
+}
+
+/// Executes a tool by name
+///
+/// Watch tools need server state and are dispatched by [`McpServer`](super::McpServer).
+/// Reads and writes enforce `policy`, with backups recorded in `journal`;
+/// scans use the threads and chunk size of `scanner`, and processes are only
+/// opened if `processes` allows.
+pub fn call_tool(
+    name: &str,
+    arguments: Value,
+    policy: &MemoryPolicy,
+    journal: &WriteJournal,
+    scanner: &ScannerConfig,
+    processes: &ProcessPolicy,
+) -> Result<Value, ToolError> {
+    let result: MemoryResult<Value> = match name {
+        "list_processes" => handlers::list_processes(parse_args(name, arguments)?),
+        "list_modules" => handlers::list_modules(parse_args(name, arguments)?, processes),
+        "read_memory" => handlers::read_memory(parse_args(name, arguments)?, policy, processes),
+        "write_memory" => {
+            handlers::write_memory(parse_args(name, arguments)?, policy, journal, processes)
+        }
+        "scan_memory" => handlers::scan_memory(parse_args(name, arguments)?, scanner, processes),
+        _ => return Err(ToolError::UnknownTool(name.to_string())),
+    };
+    Ok(result?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tool_definitions_unique() {
+        let tools = tool_definitions();
+        let mut names: Vec<&str> = tools.iter().map(|t| t.name.as_str()).collect();
+        names.sort_unstable();
+        names.dedup();
+        assert_eq!(names.len(), tools.len());
+    }
+
+    #[test]
+    fn test_tool_definition_serialization() {
+        let tools = tool_definitions();
+        let value = serde_json::to_value(&tools[0]).unwrap();
+        assert!(value.get("inputSchema").is_some());
+        assert!(value.get("input_schema").is_none());
+    }
+
+    #[test]
+    fn test_call_unknown_tool() {
+        let result = call_tool(
+            "format_disk",
+            json!({}),
+            &MemoryPolicy::default(),
+            &WriteJournal::new(),
+            &crate::config::Config::default().scanner,
+            &ProcessPolicy::default(),
+        );
+        assert!(matches!(result, Err(ToolError::UnknownTool(_))));
+    }
+
+    #[test]
+    fn test_call_with_invalid_arguments() {
+        let result = call_tool(
+            "read_memory",
+            json!({ "pid": "not a number" }),
+            &MemoryPolicy::default(),
+            &WriteJournal::new(),
+            &crate::config::Config::default().scanner,
+            &ProcessPolicy::default(),
+        );
+        match result {
+            Err(ToolError::InvalidArguments { tool, .. }) => assert_eq!(tool, "read_memory"),
+            other => panic!("Expected InvalidArguments, got {:?}", other),
+        }
+    }
+}
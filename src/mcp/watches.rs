@@ -0,0 +1,126 @@
+//! Watch lists owned by the server, whose changes reach clients as notifications
+
+use super::handlers::{UnwatchMemoryArgs, WatchMemoryArgs};
+use super::protocol::JsonRpcRequest;
+use crate::core::types::MemoryResult;
+use crate::memory::watch::{ChangeEvent, WatchSpec, Watcher, EVENT_CAPACITY};
+use crate::memory::MemoryPolicy;
+use crate::process::{ProcessHandle, ProcessPolicy};
+use serde_json::{json, Value};
+use std::collections::hash_map::{Entry, HashMap};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+
+/// Method of the notification sent for each change
+pub const CHANGE_NOTIFICATION: &str = "notifications/memory/changed";
+
+struct ProcessWatches {
+    /// Keeps the process open; the watcher only holds a weak reference
+    _handle: Arc<ProcessHandle>,
+    watcher: Arc<Watcher>,
+    task: JoinHandle<()>,
+}
+
+/// Watchers for every process with watched values, sharing one event channel
+pub struct WatchHub {
+    interval: Duration,
+    policy: MemoryPolicy,
+    processes: Mutex<HashMap<u32, ProcessWatches>>,
+    events: broadcast::Sender<ChangeEvent>,
+}
+
+impl WatchHub {
+    /// Create a hub polling every `interval`
+    pub fn new(interval: Duration) -> Self {
+        WatchHub {
+            interval,
+            policy: MemoryPolicy::default(),
+            processes: Mutex::new(HashMap::new()),
+            events: broadcast::channel(EVENT_CAPACITY).0,
+        }
+    }
+
+    /// Read watched values under `policy` instead of the default one
+    pub fn with_policy(mut self, policy: MemoryPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Receive the changes of every watched value
+    pub fn subscribe(&self) -> broadcast::Receiver<ChangeEvent> {
+        self.events.subscribe()
+    }
+
+    /// Start watching a value, polling its process from now on
+    ///
+    /// A process not yet watched is opened only if `policy` allows it.
+    pub fn watch(&self, args: WatchMemoryArgs, policy: &ProcessPolicy) -> MemoryResult<Value> {
+        let mut spec = WatchSpec::new(args.address.resolve()?, args.value_type);
+        if let Some(length) = args.length {
+            spec = spec.with_length(length);
+        }
+        if let Some(label) = args.label {
+            spec = spec.with_label(label);
+        }
+
+        let mut processes = self.processes.lock().unwrap();
+        let process = match processes.entry(args.pid) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                policy.check(args.pid, false)?;
+                let handle = Arc::new(ProcessHandle::open_for_read(args.pid)?);
+                let watcher = Arc::new(
+                    Watcher::new(Arc::downgrade(&handle))
+                        .with_interval(self.interval)
+                        .with_policy(self.policy.clone())
+                        .with_events(self.events.clone()),
+                );
+                let task = watcher.spawn()?;
+                entry.insert(ProcessWatches {
+                    _handle: handle,
+                    watcher,
+                    task,
+                })
+            }
+        };
+        let id = process.watcher.add(spec);
+        if id.is_err() && process.watcher.list().is_empty() {
+            if let Some(process) = processes.remove(&args.pid) {
+                process.task.abort();
+            }
+        }
+        Ok(json!({ "pid": args.pid, "id": id? }))
+    }
+
+    /// Stop watching a value, closing its process once nothing there is watched
+    pub fn unwatch(&self, args: UnwatchMemoryArgs) -> MemoryResult<Value> {
+        let mut processes = self.processes.lock().unwrap();
+        let removed = processes
+            .get(&args.pid)
+            .is_some_and(|p| p.watcher.remove(args.id));
+        if processes
+            .get(&args.pid)
+            .is_some_and(|p| p.watcher.list().is_empty())
+        {
+            if let Some(process) = processes.remove(&args.pid) {
+                process.task.abort();
+            }
+        }
+        Ok(json!({ "removed": removed }))
+    }
+}
+
+impl Drop for WatchHub {
+    fn drop(&mut self) {
+        for process in self.processes.get_mut().unwrap().values() {
+            process.task.abort();
+        }
+    }
+}
+
+/// Notification telling a client that a watched value changed
+pub fn change_notification(event: &ChangeEvent) -> JsonRpcRequest {
+    JsonRpcRequest::notification(CHANGE_NOTIFICATION, serde_json::to_value(event).ok())
+}
@@ -0,0 +1,506 @@
+//! Linux backend built on `process_vm_readv`/`process_vm_writev` and procfs
+
+use super::MemoryBackend;
+use crate::core::types::{Address, MemoryError, MemoryResult, ModuleInfo, ProcessArchitecture};
+use crate::memory::regions::{RegionInfo, RegionState, RegionType};
+use crate::process::handle::ProcessAccess;
+use crate::process::policy::PolicyAccess;
+use crate::process::ProcessHandle;
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::os::unix::fs::FileExt;
+use std::path::{Path, PathBuf};
+
+const PAGE_SIZE: usize = 4096;
+
+// Windows protection values, so RegionInfo means the same thing on every platform
+const PAGE_NOACCESS: u32 = 0x01;
+const PAGE_READONLY: u32 = 0x02;
+const PAGE_READWRITE: u32 = 0x04;
+const PAGE_EXECUTE: u32 = 0x10;
+const PAGE_EXECUTE_READ: u32 = 0x20;
+const PAGE_EXECUTE_READWRITE: u32 = 0x40;
+
+/// Kernel-provided mappings that `process_vm_readv` cannot access
+const SPECIAL_MAPPINGS: &[&str] = &["[vvar]", "[vvar_vclock]", "[vsyscall]"];
+
+/// One line of `/proc/<pid>/maps`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct MapsEntry {
+    pub start: usize,
+    pub end: usize,
+    pub readable: bool,
+    pub writable: bool,
+    pub executable: bool,
+    pub shared: bool,
+    pub offset: u64,
+    pub path: Option<String>,
+}
+
+impl MapsEntry {
+    /// Parse a line such as `7f1c2a000000-7f1c2a021000 r-xp 00000000 08:01 1234 /usr/lib/libc.so.6`
+    pub fn parse(line: &str) -> Option<Self> {
+        let mut fields = line.splitn(6, ' ');
+        let (start, end) = fields.next()?.split_once('-')?;
+        let perms = fields.next()?.as_bytes();
+        let offset = fields.next()?;
+        let _device = fields.next()?;
+        let _inode = fields.next()?;
+        let path = fields.next().map(str::trim).filter(|p| !p.is_empty());
+
+        if perms.len() < 4 {
+            return None;
+        }
+
+        Some(MapsEntry {
+            start: usize::from_str_radix(start, 16).ok()?,
+            end: usize::from_str_radix(end, 16).ok()?,
+            readable: perms[0] == b'r',
+            writable: perms[1] == b'w',
+            executable: perms[2] == b'x',
+            shared: perms[3] == b's',
+            offset: u64::from_str_radix(offset, 16).ok()?,
+            path: path.map(str::to_string),
+        })
+    }
+
+    /// Whether the mapping is backed by a file on disk
+    pub fn is_file_backed(&self) -> bool {
+        self.path.as_deref().is_some_and(|p| p.starts_with('/'))
+    }
+
+    /// Translate the `rwx` permissions into a `PAGE_*` protection value
+    pub fn protection(&self) -> u32 {
+        if self
+            .path
+            .as_deref()
+            .is_some_and(|p| SPECIAL_MAPPINGS.contains(&p))
+        {
+            return PAGE_NOACCESS;
+        }
+
+        match (
+            self.readable || self.writable,
+            self.writable,
+            self.executable,
+        ) {
+            (_, true, true) => PAGE_EXECUTE_READWRITE,
+            (_, true, false) => PAGE_READWRITE,
+            (true, false, true) => PAGE_EXECUTE_READ,
+            (false, false, true) => PAGE_EXECUTE,
+            (true, false, false) => PAGE_READONLY,
+            (false, false, false) => PAGE_NOACCESS,
+        }
+    }
+}
+
+/// Read and parse `/proc/<pid>/maps`
+pub(crate) fn read_maps(pid: u32) -> MemoryResult<Vec<MapsEntry>> {
+    let contents = fs::read_to_string(format!("/proc/{}/maps", pid))
+        .map_err(|e| proc_error(pid, "maps", e))?;
+    Ok(contents.lines().filter_map(MapsEntry::parse).collect())
+}
+
+/// Check that `/proc/<pid>` exists and its address space is visible to us
+pub(crate) fn check_process(pid: u32) -> MemoryResult<()> {
+    if pid == 0 || !Path::new(&format!("/proc/{}", pid)).exists() {
+        return Err(MemoryError::ProcessNotFound(format!("PID: {}", pid)));
+    }
+    fs::File::open(format!("/proc/{}/maps", pid))
+        .map(drop)
+        .map_err(|e| proc_error(pid, "maps", e))
+}
+
+/// Whether the process still exists
+pub(crate) fn process_exists(pid: u32) -> bool {
+    pid != 0 && Path::new(&format!("/proc/{}", pid)).exists()
+}
+
+fn proc_error(pid: u32, file: &str, error: io::Error) -> MemoryError {
+    match error.kind() {
+        io::ErrorKind::NotFound => MemoryError::ProcessNotFound(format!("PID: {}", pid)),
+        io::ErrorKind::PermissionDenied => {
+            MemoryError::access_denied(pid, format!("cannot open /proc/{}/{}", pid, file))
+        }
+        _ => MemoryError::IoError(error),
+    }
+}
+
+/// Paths of files mapped with execute permission, i.e. loaded images
+fn image_paths(entries: &[MapsEntry]) -> HashSet<&str> {
+    entries
+        .iter()
+        .filter(|e| e.executable && e.is_file_backed())
+        .filter_map(|e| e.path.as_deref())
+        .collect()
+}
+
+/// Convert parsed maps into committed regions
+pub(crate) fn regions_from_maps(entries: &[MapsEntry]) -> Vec<RegionInfo> {
+    let images = image_paths(entries);
+
+    entries
+        .iter()
+        .map(|entry| {
+            let region_type = match entry.path.as_deref() {
+                Some(path) if images.contains(path) => RegionType::Image,
+                _ if entry.is_file_backed() || entry.shared => RegionType::Mapped,
+                _ => RegionType::Private,
+            };
+            let protection = entry.protection();
+
+            RegionInfo {
+                base_address: Address::new(entry.start),
+                size: entry.end - entry.start,
+                state: RegionState::Committed,
+                region_type,
+                protection,
+                allocation_protection: protection,
+                allocation_base: Address::new(entry.start),
+            }
+        })
+        .collect()
+}
+
+/// Architecture named by the `e_machine` field of an ELF header
+pub(crate) fn elf_architecture(header: &[u8]) -> ProcessArchitecture {
+    if header.get(..4) != Some(b"\x7FELF".as_slice()) {
+        return ProcessArchitecture::Unknown;
+    }
+    match header.get(18..20).map(|m| u16::from_le_bytes([m[0], m[1]])) {
+        Some(0x03) => ProcessArchitecture::X86,
+        Some(0x3E) => ProcessArchitecture::X64,
+        Some(0x28) => ProcessArchitecture::Arm,
+        Some(0xB7) => ProcessArchitecture::Arm64,
+        _ => ProcessArchitecture::Unknown,
+    }
+}
+
+/// Group image mappings into modules, with `main_exe` moved to the front
+pub(crate) fn modules_from_maps(entries: &[MapsEntry], main_exe: Option<&Path>) -> Vec<ModuleInfo> {
+    let images = image_paths(entries);
+    let mut modules: Vec<ModuleInfo> = Vec::new();
+
+    for entry in entries {
+        let Some(path) = entry.path.as_deref().filter(|p| images.contains(p)) else {
+            continue;
+        };
+
+        match modules.iter_mut().find(|m| m.path.as_os_str() == path) {
+            Some(module) => {
+                let base = module.base_address.as_usize().min(entry.start);
+                let end = module.end_address().as_usize().max(entry.end);
+                module.base_address = Address::new(base);
+                module.size = end - base;
+            }
+            None => {
+                let path = PathBuf::from(path);
+                let name = path
+                    .file_name()
+                    .map(|n| n.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                let mut module =
+                    ModuleInfo::new(name, Address::new(entry.start), entry.end - entry.start);
+                module.path = path;
+                modules.push(module);
+            }
+        }
+    }
+
+    if let Some(main) = main_exe {
+        if let Some(index) = modules.iter().position(|m| m.path == main) {
+            let module = modules.remove(index);
+            modules.insert(0, module);
+        }
+    }
+
+    modules
+}
+
+fn ensure_access(handle: &ProcessHandle, right: ProcessAccess, action: &str) -> MemoryResult<()> {
+    if handle.access().contains(right) {
+        Ok(())
+    } else {
+        Err(MemoryError::access_denied(
+            handle.pid(),
+            format!("handle was not opened for {}", action),
+        ))
+    }
+}
+
+impl MemoryBackend for ProcessHandle {
+    fn pid(&self) -> u32 {
+        ProcessHandle::pid(self)
+    }
+
+    fn read(&self, address: Address, buffer: &mut [u8]) -> MemoryResult<usize> {
+        ensure_access(self, ProcessAccess::VM_READ, "reading")?;
+        if buffer.is_empty() {
+            return Ok(0);
+        }
+
+        let local = libc::iovec {
+            iov_base: buffer.as_mut_ptr().cast(),
+            iov_len: buffer.len(),
+        };
+        let remote = libc::iovec {
+            iov_base: address.as_usize() as *mut libc::c_void,
+            iov_len: buffer.len(),
+        };
+
+        let read =
+            unsafe { libc::process_vm_readv(self.pid() as libc::pid_t, &local, 1, &remote, 1, 0) };
+
+        if read < 0 {
+            return Err(MemoryError::read_failed(
+                format!("0x{:X}", address.as_usize()),
+                format!("process_vm_readv failed: {}", io::Error::last_os_error()),
+            ));
+        }
+        if (read as usize) < buffer.len() {
+            return Err(MemoryError::read_failed(
+                format!("0x{:X}", address.as_usize()),
+                format!(
+                    "Partial read: expected {} bytes, read {} bytes",
+                    buffer.len(),
+                    read
+                ),
+            ));
+        }
+
+        Ok(read as usize)
+    }
+
+    fn write(&self, address: Address, data: &[u8]) -> MemoryResult<usize> {
+        ensure_access(self, ProcessAccess::VM_WRITE, "writing")?;
+        if data.is_empty() {
+            return Ok(0);
+        }
+
+        let local = libc::iovec {
+            iov_base: data.as_ptr() as *mut libc::c_void,
+            iov_len: data.len(),
+        };
+        let remote = libc::iovec {
+            iov_base: address.as_usize() as *mut libc::c_void,
+            iov_len: data.len(),
+        };
+
+        let written =
+            unsafe { libc::process_vm_writev(self.pid() as libc::pid_t, &local, 1, &remote, 1, 0) };
+
+        if written >= 0 {
+            return Ok(written as usize);
+        }
+
+        // process_vm_writev honours page protection; /proc/<pid>/mem can
+        // write through read-only mappings the same way WriteProcessMemory does
+        let error = io::Error::last_os_error();
+        if error.raw_os_error() == Some(libc::EFAULT) {
+            let mem = fs::OpenOptions::new()
+                .write(true)
+                .open(format!("/proc/{}/mem", self.pid()));
+            if let Ok(written) = mem.and_then(|f| f.write_at(data, address.as_usize() as u64)) {
+                return Ok(written);
+            }
+        }
+
+        Err(MemoryError::write_failed(
+            format!("0x{:X}", address.as_usize()),
+            format!("process_vm_writev failed: {}", error),
+        ))
+    }
+
+    fn query_region(&self, address: Address) -> MemoryResult<RegionInfo> {
+        let entries = read_maps(self.pid())?;
+        let regions = regions_from_maps(&entries);
+
+        if let Some(region) = regions.iter().find(|r| r.contains(address)) {
+            return Ok(region.clone());
+        }
+
+        // Report the gap before the next mapping as free, like VirtualQueryEx
+        let next = regions
+            .iter()
+            .find(|r| r.base_address > address)
+            .ok_or_else(|| {
+                MemoryError::InvalidAddress(format!(
+                    "0x{:X} is beyond the last mapping",
+                    address.as_usize()
+                ))
+            })?;
+        let base = address.align_down(PAGE_SIZE);
+
+        Ok(RegionInfo {
+            base_address: base,
+            size: next.base_address.as_usize() - base.as_usize(),
+            state: RegionState::Free,
+            region_type: RegionType::Private,
+            protection: PAGE_NOACCESS,
+            allocation_protection: 0,
+            allocation_base: Address::null(),
+        })
+    }
+
+    fn enumerate_regions(&self) -> MemoryResult<Vec<RegionInfo>> {
+        Ok(regions_from_maps(&read_maps(self.pid())?))
+    }
+
+    fn enumerate_modules(&self) -> MemoryResult<Vec<ModuleInfo>> {
+        let entries = read_maps(self.pid())?;
+        let main_exe = fs::read_link(format!("/proc/{}/exe", self.pid())).ok();
+        Ok(modules_from_maps(&entries, main_exe.as_deref()))
+    }
+
+    fn policy_access(&self) -> &PolicyAccess {
+        &self.policy
+    }
+
+    fn architecture(&self) -> ProcessArchitecture {
+        let mut header = [0u8; 20];
+        match fs::File::open(format!("/proc/{}/exe", self.pid())) {
+            Ok(file) if file.read_exact_at(&mut header, 0).is_ok() => elf_architecture(&header),
+            _ => ProcessArchitecture::Unknown,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_MAPS: &str = "\
+55d0c0a00000-55d0c0a02000 r--p 00000000 08:01 1234                       /usr/bin/cat
+55d0c0a02000-55d0c0a07000 r-xp 00002000 08:01 1234                       /usr/bin/cat
+55d0c0a07000-55d0c0a09000 rw-p 00007000 08:01 1234                       /usr/bin/cat
+55d0c1000000-55d0c1021000 rw-p 00000000 00:00 0                          [heap]
+7f1c2a000000-7f1c2a028000 r--p 00000000 08:01 5678                       /usr/lib/libc.so.6
+7f1c2a028000-7f1c2a1bd000 r-xp 00028000 08:01 5678                       /usr/lib/libc.so.6
+7f1c2a300000-7f1c2a400000 r--s 00000000 08:01 9999                       /tmp/data file.bin
+7f1c2a400000-7f1c2a401000 ---p 00000000 00:00 0
+7ffd6f000000-7ffd6f021000 rw-p 00000000 00:00 0                          [stack]
+7ffd6f1fc000-7ffd6f200000 r--p 00000000 00:00 0                          [vvar]";
+
+    fn sample() -> Vec<MapsEntry> {
+        SAMPLE_MAPS.lines().filter_map(MapsEntry::parse).collect()
+    }
+
+    #[test]
+    fn test_parse_maps_line() {
+        let entry = MapsEntry::parse(
+            "7f1c2a028000-7f1c2a1bd000 r-xp 00028000 08:01 5678   /usr/lib/libc.so.6",
+        )
+        .unwrap();
+
+        assert_eq!(entry.start, 0x7f1c2a028000);
+        assert_eq!(entry.end, 0x7f1c2a1bd000);
+        assert!(entry.readable && entry.executable && !entry.writable && !entry.shared);
+        assert_eq!(entry.offset, 0x28000);
+        assert_eq!(entry.path.as_deref(), Some("/usr/lib/libc.so.6"));
+        assert_eq!(entry.protection(), PAGE_EXECUTE_READ);
+
+        let anon = MapsEntry::parse("7f1c2a400000-7f1c2a401000 ---p 00000000 00:00 0").unwrap();
+        assert_eq!(anon.path, None);
+        assert_eq!(anon.protection(), PAGE_NOACCESS);
+
+        assert!(MapsEntry::parse("garbage").is_none());
+    }
+
+    #[test]
+    fn test_path_with_spaces() {
+        let entries = sample();
+        assert_eq!(entries.len(), 10);
+        assert_eq!(entries[6].path.as_deref(), Some("/tmp/data file.bin"));
+    }
+
+    #[test]
+    fn test_regions_from_maps() {
+        let regions = regions_from_maps(&sample());
+
+        assert_eq!(regions[0].region_type, RegionType::Image);
+        assert_eq!(regions[2].protection, PAGE_READWRITE);
+        assert_eq!(regions[3].region_type, RegionType::Private);
+        assert_eq!(regions[6].region_type, RegionType::Mapped);
+        assert!(!regions[7].is_readable());
+        // [vvar] cannot be read through process_vm_readv
+        assert!(!regions[9].is_readable());
+        assert!(regions.iter().all(|r| r.state == RegionState::Committed));
+    }
+
+    #[test]
+    fn test_modules_from_maps() {
+        let entries = sample();
+
+        let modules = modules_from_maps(&entries, None);
+        assert_eq!(modules.len(), 2);
+        assert_eq!(modules[0].name, "cat");
+        assert_eq!(modules[0].base_address, Address::new(0x55d0c0a00000));
+        assert_eq!(modules[0].size, 0x9000);
+        assert_eq!(modules[1].name, "libc.so.6");
+        assert_eq!(modules[1].size, 0x1bd000);
+
+        let modules = modules_from_maps(&entries, Some(Path::new("/usr/lib/libc.so.6")));
+        assert_eq!(modules[0].name, "libc.so.6");
+    }
+
+    #[test]
+    fn test_elf_architecture() {
+        let mut header = [0u8; 20];
+        header[..4].copy_from_slice(b"\x7FELF");
+        header[18] = 0x03;
+        assert_eq!(elf_architecture(&header), ProcessArchitecture::X86);
+        header[18] = 0xB7;
+        assert_eq!(elf_architecture(&header), ProcessArchitecture::Arm64);
+        assert_eq!(elf_architecture(&[0u8; 20]), ProcessArchitecture::Unknown);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "File system operations not supported under Miri")]
+    fn test_own_architecture() {
+        let handle = ProcessHandle::open_for_read(std::process::id()).unwrap();
+        let expected = ProcessArchitecture::Unknown.pointer_size();
+        assert_eq!(handle.architecture().pointer_size(), expected);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "FFI not supported in Miri")]
+    fn test_query_unmapped_gap() {
+        let handle = ProcessHandle::open_for_read(std::process::id()).unwrap();
+        let regions = handle.enumerate_regions().unwrap();
+        let gap = regions
+            .windows(2)
+            .find(|pair| pair[0].end_address() < pair[1].base_address)
+            .map(|pair| pair[0].end_address())
+            .unwrap();
+
+        let free = handle.query_region(gap).unwrap();
+        assert_eq!(free.state, RegionState::Free);
+        assert_eq!(free.base_address, gap);
+        assert!(handle.query_region(Address::new(usize::MAX)).is_err());
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "FFI not supported in Miri")]
+    fn test_access_rights_enforced() {
+        let handle = ProcessHandle::open_for_read(std::process::id()).unwrap();
+        let mut value = 7u32;
+        let address = Address::from(&mut value as *mut u32 as usize);
+
+        assert!(matches!(
+            handle.write(address, &[0, 0, 0, 0]),
+            Err(MemoryError::AccessDenied { .. })
+        ));
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "FFI not supported in Miri")]
+    fn test_unmapped_read_fails() {
+        let handle = ProcessHandle::open_for_read(std::process::id()).unwrap();
+        let mut buffer = [0u8; 4];
+        assert!(matches!(
+            handle.read(Address::new(0x10), &mut buffer),
+            Err(MemoryError::ReadFailed { .. })
+        ));
+    }
+}
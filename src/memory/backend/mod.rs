@@ -0,0 +1,158 @@
+//! Platform abstraction for process memory access
+//!
+//! `MemoryBackend` is the seam between the memory subsystems (reader, writer,
+//! scanner, region enumeration) and the operating system. `ProcessHandle`
+//! implements it with kernel32/psapi on Windows and with
+//! `process_vm_readv`/`process_vm_writev` plus `/proc/<pid>/maps` on Linux.
+//! [`SnapshotProcess`] implements it over an in-memory process image.
+
+#[cfg(target_os = "linux")]
+pub(crate) mod linux;
+pub mod snapshot;
+#[cfg(windows)]
+mod windows;
+
+pub use snapshot::{SnapshotProcess, SnapshotRegion};
+
+use crate::core::types::{Address, MemoryResult, ModuleInfo, ProcessArchitecture};
+use crate::memory::regions::RegionInfo;
+use crate::process::policy::PolicyAccess;
+
+/// Low-level access to the address space of a single process
+///
+/// Region protection is always reported using the Windows `PAGE_*` values so
+/// filters and protection checks behave identically on every platform.
+pub trait MemoryBackend: Send + Sync {
+    /// Process ID of the target
+    fn pid(&self) -> u32;
+
+    /// Read `buffer.len()` bytes starting at `address`
+    ///
+    /// Returns the number of bytes read; a partial read is an error.
+    fn read(&self, address: Address, buffer: &mut [u8]) -> MemoryResult<usize>;
+
+    /// Write `data` starting at `address`, returning the number of bytes written
+    ///
+    /// This does not consult [`policy_access`](Self::policy_access); the
+    /// memory writers do.
+    fn write(&self, address: Address, data: &[u8]) -> MemoryResult<usize>;
+
+    /// Describe the region containing `address`
+    ///
+    /// Unmapped addresses below the top of the user address space are
+    /// reported as a free region spanning up to the next allocation.
+    fn query_region(&self, address: Address) -> MemoryResult<RegionInfo>;
+
+    /// List every region of the address space in ascending order
+    fn enumerate_regions(&self) -> MemoryResult<Vec<RegionInfo>> {
+        let mut regions = Vec::new();
+        let mut current = Address::null();
+
+        while let Ok(region) = self.query_region(current) {
+            let next = region
+                .base_address
+                .as_usize()
+                .checked_add(region.size)
+                .filter(|&next| next > current.as_usize());
+            regions.push(region);
+
+            match next {
+                Some(next) => current = Address::new(next),
+                None => break,
+            }
+        }
+
+        Ok(regions)
+    }
+
+    /// List the loaded modules, main executable first
+    fn enumerate_modules(&self) -> MemoryResult<Vec<ModuleInfo>>;
+
+    /// Access the process policy granted to the target
+    ///
+    /// Every memory writer refuses to write when this is read-only.
+    fn policy_access(&self) -> &PolicyAccess;
+
+    /// Architecture of the target, which decides the width of pointers
+    ///
+    /// `Unknown` means the target matches the host.
+    fn architecture(&self) -> ProcessArchitecture {
+        ProcessArchitecture::Unknown
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process::ProcessHandle;
+
+    #[test]
+    #[cfg_attr(miri, ignore = "FFI not supported in Miri")]
+    fn test_backend_read_own_memory() {
+        let handle = ProcessHandle::open_for_read(std::process::id()).unwrap();
+        let value: u64 = 0x1122_3344_5566_7788;
+        let mut buffer = [0u8; 8];
+
+        let read = MemoryBackend::read(
+            &handle,
+            Address::from(&value as *const u64 as usize),
+            &mut buffer,
+        )
+        .unwrap();
+        assert_eq!(read, 8);
+        assert_eq!(u64::from_le_bytes(buffer), value);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "FFI not supported in Miri")]
+    fn test_backend_write_own_memory() {
+        let handle = ProcessHandle::open_for_read_write(std::process::id()).unwrap();
+        let mut value: u32 = 0;
+        let address = Address::from(&mut value as *mut u32 as usize);
+
+        let written = handle.write(address, &0xCAFEBABEu32.to_le_bytes()).unwrap();
+        assert_eq!(written, 4);
+        assert_eq!(unsafe { std::ptr::read_volatile(&value) }, 0xCAFEBABE);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "FFI not supported in Miri")]
+    fn test_backend_query_region() {
+        let handle = ProcessHandle::open_for_read(std::process::id()).unwrap();
+        let value = 42u32;
+        let address = Address::from(&value as *const u32 as usize);
+
+        let region = handle.query_region(address).unwrap();
+        assert!(region.contains(address));
+        assert!(region.is_readable());
+        assert!(region.is_writable());
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "FFI not supported in Miri")]
+    fn test_backend_enumerate_regions_sorted() {
+        let handle = ProcessHandle::open_for_read(std::process::id()).unwrap();
+        let regions = handle.enumerate_regions().unwrap();
+
+        assert!(!regions.is_empty());
+        assert!(regions
+            .windows(2)
+            .all(|pair| pair[0].base_address < pair[1].base_address));
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "FFI not supported in Miri")]
+    fn test_backend_enumerate_modules() {
+        let handle = ProcessHandle::open_for_read(std::process::id()).unwrap();
+        let modules = handle.enumerate_modules().unwrap();
+
+        assert!(!modules.is_empty());
+        let main = &modules[0];
+        let exe = std::env::current_exe().unwrap();
+        assert_eq!(
+            main.name.to_lowercase(),
+            exe.file_name().unwrap().to_string_lossy().to_lowercase()
+        );
+        assert!(main.size > 0);
+    }
+}
@@ -0,0 +1,194 @@
+//! Binary dump format for snapshots
+//!
+//! Layout (all integers little-endian):
+//! - magic `MMCPDUMP`, format version (u32), process ID (u32)
+//! - region count (u32), then per region: base (u64), protection (u32),
+//!   state (u8), type (u8), length (u64) and the raw bytes
+//! - module count (u32), then per module: base (u64), size (u64),
+//!   entry point (u64, 0 if unknown), system flag (u8), name and path as
+//!   length-prefixed (u32) UTF-8
+
+use super::SnapshotRegion;
+use crate::core::types::{Address, MemoryResult, ModuleInfo};
+use crate::memory::binary::{
+    invalid, read_array, read_bytes, read_str, read_u32, read_u64, read_u8, write_str, write_u32,
+    write_u64,
+};
+use crate::memory::regions::{RegionState, RegionType};
+use std::io::{self, Read, Write};
+use std::path::PathBuf;
+
+const MAGIC: &[u8; 8] = b"MMCPDUMP";
+const VERSION: u32 = 1;
+
+/// Contents of a dump file
+pub(super) type Dump = (u32, Vec<SnapshotRegion>, Vec<ModuleInfo>);
+
+fn state_to_u8(state: RegionState) -> u8 {
+    match state {
+        RegionState::Committed => 0,
+        RegionState::Reserved => 1,
+        RegionState::Free => 2,
+    }
+}
+
+fn state_from_u8(value: u8) -> io::Result<RegionState> {
+    match value {
+        0 => Ok(RegionState::Committed),
+        1 => Ok(RegionState::Reserved),
+        2 => Ok(RegionState::Free),
+        other => Err(invalid(format!("Unknown region state {}", other))),
+    }
+}
+
+fn type_to_u8(region_type: RegionType) -> u8 {
+    match region_type {
+        RegionType::Private => 0,
+        RegionType::Mapped => 1,
+        RegionType::Image => 2,
+    }
+}
+
+fn type_from_u8(value: u8) -> io::Result<RegionType> {
+    match value {
+        0 => Ok(RegionType::Private),
+        1 => Ok(RegionType::Mapped),
+        2 => Ok(RegionType::Image),
+        other => Err(invalid(format!("Unknown region type {}", other))),
+    }
+}
+
+/// Serialize a snapshot into `writer`
+pub(super) fn write_dump(
+    writer: &mut impl Write,
+    pid: u32,
+    regions: &[SnapshotRegion],
+    modules: &[ModuleInfo],
+) -> MemoryResult<()> {
+    writer.write_all(MAGIC)?;
+    write_u32(writer, VERSION)?;
+    write_u32(writer, pid)?;
+
+    write_u32(writer, regions.len() as u32)?;
+    for region in regions {
+        write_u64(writer, region.base_address.as_usize() as u64)?;
+        write_u32(writer, region.protection)?;
+        writer.write_all(&[state_to_u8(region.state), type_to_u8(region.region_type)])?;
+        write_u64(writer, region.data.len() as u64)?;
+        writer.write_all(&region.data)?;
+    }
+
+    write_u32(writer, modules.len() as u32)?;
+    for module in modules {
+        write_u64(writer, module.base_address.as_usize() as u64)?;
+        write_u64(writer, module.size as u64)?;
+        write_u64(
+            writer,
+            module
+                .entry_point
+                .map_or(0, |entry| entry.as_usize() as u64),
+        )?;
+        writer.write_all(&[module.is_system as u8])?;
+        write_str(writer, &module.name)?;
+        write_str(writer, &module.path.to_string_lossy())?;
+    }
+
+    Ok(())
+}
+
+/// Deserialize a snapshot written by [`write_dump`]
+pub(super) fn read_dump(reader: &mut impl Read) -> MemoryResult<Dump> {
+    if &read_array::<8>(reader)? != MAGIC {
+        return Err(invalid("Not a memory dump file").into());
+    }
+    let version = read_u32(reader)?;
+    if version != VERSION {
+        return Err(invalid(format!("Unsupported dump version {}", version)).into());
+    }
+    let pid = read_u32(reader)?;
+
+    let region_count = read_u32(reader)?;
+    let mut regions = Vec::new();
+    for _ in 0..region_count {
+        let base = read_u64(reader)?;
+        let protection = read_u32(reader)?;
+        let state = state_from_u8(read_u8(reader)?)?;
+        let region_type = type_from_u8(read_u8(reader)?)?;
+        let len = read_u64(reader)?;
+        let data = read_bytes(reader, len)?;
+
+        regions.push(
+            SnapshotRegion::new(Address::new(base as usize), data, protection)
+                .with_state(state)
+                .with_type(region_type),
+        );
+    }
+
+    let module_count = read_u32(reader)?;
+    let mut modules = Vec::new();
+    for _ in 0..module_count {
+        let base = read_u64(reader)?;
+        let size = read_u64(reader)?;
+        let entry_point = read_u64(reader)?;
+        let is_system = read_u8(reader)? != 0;
+        let name = read_str(reader)?;
+        let path = read_str(reader)?;
+
+        let mut module = ModuleInfo::new(name, Address::new(base as usize), size as usize);
+        module.path = PathBuf::from(path);
+        module.entry_point = (entry_point != 0).then(|| Address::new(entry_point as usize));
+        module.is_system = is_system;
+        modules.push(module);
+    }
+
+    Ok((pid, regions, modules))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Dump {
+        let regions = vec![
+            SnapshotRegion::new(Address::new(0x10000), vec![1, 2, 3, 4], 0x04),
+            SnapshotRegion::new(Address::new(0x20000), vec![0x90; 16], 0x20)
+                .with_type(RegionType::Image),
+        ];
+        let mut module = ModuleInfo::new("game.exe".to_string(), Address::new(0x20000), 16);
+        module.path = PathBuf::from("C:\\Games\\game.exe");
+        module.entry_point = Some(Address::new(0x20004));
+
+        (1234, regions, vec![module])
+    }
+
+    #[test]
+    fn test_dump_round_trip() {
+        let (pid, regions, modules) = sample();
+        let mut bytes = Vec::new();
+        write_dump(&mut bytes, pid, &regions, &modules).unwrap();
+
+        let (read_pid, read_regions, read_modules) = read_dump(&mut bytes.as_slice()).unwrap();
+        assert_eq!(read_pid, pid);
+        assert_eq!(read_regions, regions);
+        assert_eq!(read_modules.len(), 1);
+        assert_eq!(read_modules[0].name, "game.exe");
+        assert_eq!(read_modules[0].path, modules[0].path);
+        assert_eq!(read_modules[0].entry_point, Some(Address::new(0x20004)));
+    }
+
+    #[test]
+    fn test_dump_rejects_bad_input() {
+        assert!(read_dump(&mut &b"NOTADUMP"[..]).is_err());
+
+        let (pid, regions, modules) = sample();
+        let mut bytes = Vec::new();
+        write_dump(&mut bytes, pid, &regions, &modules).unwrap();
+
+        // Truncated file
+        assert!(read_dump(&mut &bytes[..bytes.len() - 4]).is_err());
+
+        // Unknown version
+        bytes[8] = 99;
+        assert!(read_dump(&mut bytes.as_slice()).is_err());
+    }
+}
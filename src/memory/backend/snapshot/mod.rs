@@ -0,0 +1,450 @@
+//! In-memory process image for deterministic, platform-independent testing
+//!
+//! A `SnapshotProcess` holds synthetic regions and modules and implements
+//! [`MemoryBackend`], so readers, writers, the scanner and region filters run
+//! against it exactly as they do against a live process. Snapshots can also be
+//! captured from a live backend and saved to a dump file for offline replay.
+
+mod dump;
+
+use super::MemoryBackend;
+use crate::core::types::{Address, MemoryError, MemoryResult, ModuleInfo, ProcessArchitecture};
+use crate::memory::regions::{RegionInfo, RegionState, RegionType};
+use crate::process::policy::PolicyAccess;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::Path;
+use std::sync::RwLock;
+
+const PAGE_NOACCESS: u32 = 0x01;
+
+/// A synthetic memory region with its contents
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnapshotRegion {
+    /// Base address of the region
+    pub base_address: Address,
+    /// Region contents; the length is the region size
+    pub data: Vec<u8>,
+    /// Windows `PAGE_*` protection value
+    pub protection: u32,
+    /// State of the region
+    pub state: RegionState,
+    /// Type of the region
+    pub region_type: RegionType,
+}
+
+impl SnapshotRegion {
+    /// Create a committed private region
+    pub fn new(base_address: Address, data: Vec<u8>, protection: u32) -> Self {
+        SnapshotRegion {
+            base_address,
+            data,
+            protection,
+            state: RegionState::Committed,
+            region_type: RegionType::Private,
+        }
+    }
+
+    /// Set the region state
+    pub fn with_state(mut self, state: RegionState) -> Self {
+        self.state = state;
+        self
+    }
+
+    /// Set the region type
+    pub fn with_type(mut self, region_type: RegionType) -> Self {
+        self.region_type = region_type;
+        self
+    }
+
+    /// Size of the region in bytes
+    pub fn size(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Describe the region the way a live backend would
+    pub fn info(&self) -> RegionInfo {
+        RegionInfo {
+            base_address: self.base_address,
+            size: self.size(),
+            state: self.state,
+            region_type: self.region_type,
+            protection: self.protection,
+            allocation_protection: self.protection,
+            allocation_base: self.base_address,
+        }
+    }
+}
+
+/// A process image held entirely in memory
+#[derive(Debug)]
+pub struct SnapshotProcess {
+    pid: u32,
+    /// Sorted by base address, never overlapping
+    regions: RwLock<Vec<SnapshotRegion>>,
+    modules: Vec<ModuleInfo>,
+    architecture: ProcessArchitecture,
+    policy: PolicyAccess,
+}
+
+impl SnapshotProcess {
+    /// Create an empty snapshot reporting the given process ID
+    pub fn new(pid: u32) -> Self {
+        SnapshotProcess {
+            pid,
+            regions: RwLock::new(Vec::new()),
+            modules: Vec::new(),
+            architecture: ProcessArchitecture::Unknown,
+            policy: PolicyAccess::ReadWrite,
+        }
+    }
+
+    /// Snapshot of `pid` holding one region of `data` at `base`
+    #[cfg(test)]
+    pub(crate) fn with_region(pid: u32, base: usize, data: Vec<u8>, protection: u32) -> Self {
+        let mut snapshot = SnapshotProcess::new(pid);
+        snapshot
+            .add_region(SnapshotRegion::new(Address::new(base), data, protection))
+            .unwrap();
+        snapshot
+    }
+
+    /// Report `architecture` as the snapshot's architecture
+    pub fn with_architecture(mut self, architecture: ProcessArchitecture) -> Self {
+        self.architecture = architecture;
+        self
+    }
+
+    /// Report `policy` as the access the process policy granted
+    pub fn with_policy_access(mut self, policy: PolicyAccess) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Add a region; it must be non-empty and must not overlap existing regions
+    pub fn add_region(&mut self, region: SnapshotRegion) -> MemoryResult<()> {
+        let base = region.base_address.as_usize();
+        let end = base
+            .checked_add(region.size())
+            .filter(|_| region.size() > 0)
+            .ok_or_else(|| {
+                MemoryError::InvalidAddress(format!(
+                    "0x{:X} - Region is empty or overflows the address space",
+                    base
+                ))
+            })?;
+
+        let regions = self.regions.get_mut().unwrap();
+        let index = regions.partition_point(|r| r.base_address.as_usize() < base);
+        let overlaps_previous =
+            index > 0 && regions[index - 1].info().end_address().as_usize() > base;
+        let overlaps_next = regions
+            .get(index)
+            .is_some_and(|next| next.base_address.as_usize() < end);
+
+        if overlaps_previous || overlaps_next {
+            return Err(MemoryError::InvalidAddress(format!(
+                "0x{:X} - Region overlaps an existing region",
+                base
+            )));
+        }
+
+        regions.insert(index, region);
+        Ok(())
+    }
+
+    /// Add a module; the first module added is reported as the main executable
+    pub fn add_module(&mut self, module: ModuleInfo) {
+        self.modules.push(module);
+    }
+
+    /// Copy of the current regions, including any writes made so far
+    pub fn regions(&self) -> Vec<SnapshotRegion> {
+        self.regions.read().unwrap().clone()
+    }
+
+    /// Loaded modules, main executable first
+    pub fn modules(&self) -> &[ModuleInfo] {
+        &self.modules
+    }
+
+    /// Capture every committed, readable region and the module list of a live backend
+    ///
+    /// Regions that cannot be read (for example kernel-owned mappings) are skipped.
+    pub fn capture<B: MemoryBackend>(backend: &B) -> MemoryResult<Self> {
+        let mut snapshot = SnapshotProcess::new(backend.pid());
+
+        for region in backend.enumerate_regions()? {
+            if region.state != RegionState::Committed || !region.is_readable() {
+                continue;
+            }
+
+            let mut data = vec![0u8; region.size];
+            if backend.read(region.base_address, &mut data).is_err() {
+                continue;
+            }
+
+            snapshot.add_region(
+                SnapshotRegion::new(region.base_address, data, region.protection)
+                    .with_type(region.region_type),
+            )?;
+        }
+
+        for module in backend.enumerate_modules()? {
+            snapshot.add_module(module);
+        }
+
+        Ok(snapshot)
+    }
+
+    /// Save the snapshot to a dump file
+    pub fn save(&self, path: impl AsRef<Path>) -> MemoryResult<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        dump::write_dump(&mut writer, self.pid, &self.regions(), &self.modules)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Load a snapshot previously written by [`SnapshotProcess::save`]
+    pub fn load(path: impl AsRef<Path>) -> MemoryResult<Self> {
+        let mut reader = BufReader::new(File::open(path)?);
+        let (pid, regions, modules) = dump::read_dump(&mut reader)?;
+
+        let mut snapshot = SnapshotProcess::new(pid);
+        for region in regions {
+            snapshot.add_region(region)?;
+        }
+        for module in modules {
+            snapshot.add_module(module);
+        }
+
+        Ok(snapshot)
+    }
+}
+
+/// Split `[address, address + len)` into per-region spans, failing on gaps
+/// or on regions rejected by `allowed`
+fn spans(
+    regions: &[SnapshotRegion],
+    address: Address,
+    len: usize,
+    allowed: impl Fn(&RegionInfo) -> bool,
+) -> Option<Vec<(usize, usize, usize)>> {
+    let mut spans = Vec::new();
+    let mut cursor = address.as_usize();
+    let end = cursor.checked_add(len)?;
+    let mut index = regions
+        .partition_point(|r| r.base_address.as_usize() <= cursor)
+        .checked_sub(1)?;
+
+    while cursor < end {
+        let region = regions.get(index)?;
+        let info = region.info();
+        if !info.contains(Address::new(cursor)) || !allowed(&info) {
+            return None;
+        }
+
+        let offset = cursor - region.base_address.as_usize();
+        let count = (region.size() - offset).min(end - cursor);
+        spans.push((index, offset, count));
+
+        cursor += count;
+        index += 1;
+    }
+
+    Some(spans)
+}
+
+impl MemoryBackend for SnapshotProcess {
+    fn pid(&self) -> u32 {
+        self.pid
+    }
+
+    fn read(&self, address: Address, buffer: &mut [u8]) -> MemoryResult<usize> {
+        let regions = self.regions.read().unwrap();
+        let spans = spans(&regions, address, buffer.len(), |info| {
+            info.state == RegionState::Committed && info.is_readable()
+        })
+        .ok_or_else(|| MemoryError::read_failed(address, "Address range is not readable"))?;
+
+        let mut copied = 0;
+        for (index, offset, count) in spans {
+            buffer[copied..copied + count]
+                .copy_from_slice(&regions[index].data[offset..offset + count]);
+            copied += count;
+        }
+
+        Ok(copied)
+    }
+
+    fn write(&self, address: Address, data: &[u8]) -> MemoryResult<usize> {
+        let mut regions = self.regions.write().unwrap();
+        let spans = spans(&regions, address, data.len(), |info| {
+            info.state == RegionState::Committed && info.is_writable()
+        })
+        .ok_or_else(|| MemoryError::write_failed(address, "Address range is not writable"))?;
+
+        let mut copied = 0;
+        for (index, offset, count) in spans {
+            regions[index].data[offset..offset + count]
+                .copy_from_slice(&data[copied..copied + count]);
+            copied += count;
+        }
+
+        Ok(copied)
+    }
+
+    fn query_region(&self, address: Address) -> MemoryResult<RegionInfo> {
+        let regions = self.regions.read().unwrap();
+        let index = regions.partition_point(|r| r.base_address <= address);
+
+        if let Some(region) = index.checked_sub(1).map(|i| &regions[i]) {
+            if region.info().contains(address) {
+                return Ok(region.info());
+            }
+        }
+
+        // Report the gap before the next region as free, like the live backends
+        let next = regions.get(index).ok_or_else(|| {
+            MemoryError::InvalidAddress(format!(
+                "0x{:X} is beyond the last region",
+                address.as_usize()
+            ))
+        })?;
+
+        Ok(RegionInfo {
+            base_address: address,
+            size: next.base_address.as_usize() - address.as_usize(),
+            state: RegionState::Free,
+            region_type: RegionType::Private,
+            protection: PAGE_NOACCESS,
+            allocation_protection: 0,
+            allocation_base: Address::null(),
+        })
+    }
+
+    fn enumerate_modules(&self) -> MemoryResult<Vec<ModuleInfo>> {
+        Ok(self.modules.clone())
+    }
+
+    fn policy_access(&self) -> &PolicyAccess {
+        &self.policy
+    }
+
+    fn architecture(&self) -> ProcessArchitecture {
+        self.architecture
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE_READONLY: u32 = 0x02;
+    const PAGE_READWRITE: u32 = 0x04;
+
+    fn sample() -> SnapshotProcess {
+        let mut snapshot =
+            SnapshotProcess::with_region(42, 0x1000, vec![0xAA; 0x10], PAGE_READWRITE);
+        snapshot
+            .add_region(SnapshotRegion::new(
+                Address::new(0x1010),
+                vec![0xBB; 0x10],
+                PAGE_READONLY,
+            ))
+            .unwrap();
+        snapshot
+            .add_region(SnapshotRegion::new(
+                Address::new(0x3000),
+                vec![0xCC; 0x10],
+                PAGE_NOACCESS,
+            ))
+            .unwrap();
+        snapshot
+    }
+
+    #[test]
+    fn test_add_region_rejects_overlap() {
+        let mut snapshot = sample();
+
+        let overlap = SnapshotRegion::new(Address::new(0x100F), vec![0; 4], PAGE_READWRITE);
+        assert!(snapshot.add_region(overlap).is_err());
+
+        let empty = SnapshotRegion::new(Address::new(0x5000), Vec::new(), PAGE_READWRITE);
+        assert!(snapshot.add_region(empty).is_err());
+
+        let adjacent = SnapshotRegion::new(Address::new(0x1020), vec![0; 4], PAGE_READWRITE);
+        assert!(snapshot.add_region(adjacent).is_ok());
+        assert_eq!(snapshot.regions().len(), 4);
+    }
+
+    #[test]
+    fn test_read_spans_adjacent_regions() {
+        let snapshot = sample();
+        let mut buffer = [0u8; 4];
+
+        assert_eq!(snapshot.read(Address::new(0x100E), &mut buffer).unwrap(), 4);
+        assert_eq!(buffer, [0xAA, 0xAA, 0xBB, 0xBB]);
+
+        // Gaps and inaccessible regions fail like a live process
+        assert!(snapshot.read(Address::new(0x101E), &mut buffer).is_err());
+        assert!(snapshot.read(Address::new(0x3000), &mut buffer).is_err());
+        assert!(snapshot.read(Address::new(0x10), &mut buffer).is_err());
+    }
+
+    #[test]
+    fn test_write_respects_protection() {
+        let snapshot = sample();
+
+        assert_eq!(snapshot.write(Address::new(0x1004), &[1, 2]).unwrap(), 2);
+        let mut buffer = [0u8; 2];
+        snapshot.read(Address::new(0x1004), &mut buffer).unwrap();
+        assert_eq!(buffer, [1, 2]);
+
+        // A write touching a read-only region changes nothing
+        assert!(snapshot.write(Address::new(0x100E), &[0; 4]).is_err());
+        snapshot.read(Address::new(0x100E), &mut buffer).unwrap();
+        assert_eq!(buffer, [0xAA, 0xAA]);
+    }
+
+    #[test]
+    fn test_query_and_enumerate_regions() {
+        let snapshot = sample();
+
+        let region = snapshot.query_region(Address::new(0x1015)).unwrap();
+        assert_eq!(region.base_address, Address::new(0x1010));
+        assert_eq!(region.protection, PAGE_READONLY);
+
+        let gap = snapshot.query_region(Address::new(0x2000)).unwrap();
+        assert_eq!(gap.state, RegionState::Free);
+        assert_eq!(gap.end_address(), Address::new(0x3000));
+
+        assert!(snapshot.query_region(Address::new(0x4000)).is_err());
+
+        // Leading gap, two regions, gap, last region
+        let regions = snapshot.enumerate_regions().unwrap();
+        assert_eq!(regions.len(), 5);
+        assert_eq!(regions[0].state, RegionState::Free);
+        assert_eq!(regions[4].base_address, Address::new(0x3000));
+    }
+
+    #[test]
+    fn test_modules_keep_insertion_order() {
+        let mut snapshot = sample();
+        snapshot.add_module(ModuleInfo::new(
+            "main".to_string(),
+            Address::new(0x1000),
+            0x20,
+        ));
+        snapshot.add_module(ModuleInfo::new(
+            "lib".to_string(),
+            Address::new(0x3000),
+            0x10,
+        ));
+
+        let modules = snapshot.enumerate_modules().unwrap();
+        assert_eq!(modules[0].name, "main");
+        assert_eq!(modules[1].name, "lib");
+        assert_eq!(snapshot.pid(), 42);
+    }
+}
@@ -0,0 +1,199 @@
+//! Windows backend built on kernel32 and psapi
+
+use super::MemoryBackend;
+use crate::core::types::{Address, MemoryError, MemoryResult, ModuleInfo, ProcessArchitecture};
+use crate::memory::regions::{RegionInfo, RegionState, RegionType};
+use crate::process::policy::PolicyAccess;
+use crate::process::ProcessHandle;
+use crate::windows::bindings::{kernel32, ntdll};
+use crate::windows::utils::string_conv::wide_to_string;
+use std::mem;
+use winapi::shared::minwindef::{DWORD, FALSE, HMODULE, MAX_PATH};
+use winapi::um::psapi::{
+    EnumProcessModules, GetModuleBaseNameW, GetModuleFileNameExW, GetModuleInformation, MODULEINFO,
+};
+use winapi::um::winnt::MEMORY_BASIC_INFORMATION;
+
+impl MemoryBackend for ProcessHandle {
+    fn pid(&self) -> u32 {
+        ProcessHandle::pid(self)
+    }
+
+    fn read(&self, address: Address, buffer: &mut [u8]) -> MemoryResult<usize> {
+        ensure_valid(self)?;
+        unsafe { kernel32::read_process_memory(self.raw(), address.as_usize(), buffer) }
+    }
+
+    fn write(&self, address: Address, data: &[u8]) -> MemoryResult<usize> {
+        ensure_valid(self)?;
+        unsafe { kernel32::write_process_memory(self.raw(), address.as_usize(), data) }
+    }
+
+    fn query_region(&self, address: Address) -> MemoryResult<RegionInfo> {
+        ensure_valid(self)?;
+        let mbi = unsafe { kernel32::virtual_query_ex(self.raw(), address.as_usize())? };
+        Ok(parse_memory_info(&mbi))
+    }
+
+    fn enumerate_modules(&self) -> MemoryResult<Vec<ModuleInfo>> {
+        ensure_valid(self)?;
+
+        // First, get the count of modules
+        let mut modules: Vec<HMODULE> = Vec::with_capacity(1024);
+        let mut cb_needed: DWORD = 0;
+
+        unsafe {
+            // Initial call to get the required buffer size
+            let result = EnumProcessModules(
+                self.raw(),
+                modules.as_mut_ptr(),
+                (modules.capacity() * mem::size_of::<HMODULE>()) as DWORD,
+                &mut cb_needed,
+            );
+
+            if result == FALSE {
+                return Err(MemoryError::WindowsApi(
+                    "Failed to enumerate process modules".to_string(),
+                ));
+            }
+
+            // Calculate the actual number of modules
+            let module_count =
+                (cb_needed as usize / mem::size_of::<HMODULE>()).min(modules.capacity());
+            modules.set_len(module_count);
+        }
+
+        // Now get information for each module
+        let mut module_infos = Vec::with_capacity(modules.len());
+        for &module in &modules {
+            if let Ok(info) = get_module_info(self, module) {
+                module_infos.push(info);
+            }
+        }
+
+        Ok(module_infos)
+    }
+
+    fn policy_access(&self) -> &PolicyAccess {
+        &self.policy
+    }
+
+    fn architecture(&self) -> ProcessArchitecture {
+        if !self.is_valid() {
+            return ProcessArchitecture::Unknown;
+        }
+        match unsafe { ntdll::is_wow64_process(self.raw()) } {
+            Ok(true) => ProcessArchitecture::X86,
+            _ => ProcessArchitecture::Unknown,
+        }
+    }
+}
+
+fn ensure_valid(handle: &ProcessHandle) -> MemoryResult<()> {
+    if handle.is_valid() {
+        Ok(())
+    } else {
+        Err(MemoryError::InvalidHandle(
+            "Process handle is null".to_string(),
+        ))
+    }
+}
+
+/// Parse MEMORY_BASIC_INFORMATION into RegionInfo
+pub(crate) fn parse_memory_info(mbi: &MEMORY_BASIC_INFORMATION) -> RegionInfo {
+    const MEM_COMMIT: u32 = 0x1000;
+    const MEM_RESERVE: u32 = 0x2000;
+    const MEM_FREE: u32 = 0x10000;
+    const MEM_PRIVATE: u32 = 0x20000;
+    const MEM_MAPPED: u32 = 0x40000;
+    const MEM_IMAGE: u32 = 0x1000000;
+
+    let state = match mbi.State {
+        MEM_COMMIT => RegionState::Committed,
+        MEM_RESERVE => RegionState::Reserved,
+        MEM_FREE => RegionState::Free,
+        _ => RegionState::Free,
+    };
+
+    let region_type = match mbi.Type {
+        MEM_PRIVATE => RegionType::Private,
+        MEM_MAPPED => RegionType::Mapped,
+        MEM_IMAGE => RegionType::Image,
+        _ => RegionType::Private,
+    };
+
+    RegionInfo {
+        base_address: Address::new(mbi.BaseAddress as usize),
+        size: mbi.RegionSize,
+        state,
+        region_type,
+        protection: mbi.Protect,
+        allocation_protection: mbi.AllocationProtect,
+        allocation_base: Address::new(mbi.AllocationBase as usize),
+    }
+}
+
+/// Get information about a specific module
+fn get_module_info(handle: &ProcessHandle, module: HMODULE) -> MemoryResult<ModuleInfo> {
+    unsafe {
+        // Get module base name
+        let mut base_name: [u16; MAX_PATH] = [0; MAX_PATH];
+        let name_len = GetModuleBaseNameW(
+            handle.raw(),
+            module,
+            base_name.as_mut_ptr(),
+            MAX_PATH as DWORD,
+        );
+
+        if name_len == 0 {
+            return Err(MemoryError::WindowsApi(
+                "Failed to get module base name".to_string(),
+            ));
+        }
+
+        let name = wide_to_string(&base_name[..name_len as usize]);
+
+        // Get module file path
+        let mut file_path: [u16; MAX_PATH] = [0; MAX_PATH];
+        let path_len = GetModuleFileNameExW(
+            handle.raw(),
+            module,
+            file_path.as_mut_ptr(),
+            MAX_PATH as DWORD,
+        );
+
+        let path = if path_len > 0 {
+            Some(wide_to_string(&file_path[..path_len as usize]))
+        } else {
+            None
+        };
+
+        // Get module information (base address and size)
+        let mut mod_info: MODULEINFO = mem::zeroed();
+        let result = GetModuleInformation(
+            handle.raw(),
+            module,
+            &mut mod_info,
+            mem::size_of::<MODULEINFO>() as DWORD,
+        );
+
+        if result == FALSE {
+            return Err(MemoryError::WindowsApi(
+                "Failed to get module information".to_string(),
+            ));
+        }
+
+        let mut module_info = ModuleInfo::new(
+            name,
+            Address::from(mod_info.lpBaseOfDll as usize),
+            mod_info.SizeOfImage as usize,
+        );
+
+        // Set the path if available
+        if let Some(path_str) = path {
+            module_info.path = std::path::PathBuf::from(path_str);
+        }
+
+        Ok(module_info)
+    }
+}
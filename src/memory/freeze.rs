@@ -0,0 +1,550 @@
+//! Background freezing of values at fixed addresses or pointer chains
+//!
+//! Each entry is rewritten on its own tokio interval through a
+//! [`SafeMemoryWriter`] enforcing the manager's [`MemoryPolicy`]; values
+//! already in place are not written again, so backups only record real
+//! changes. The manager only holds a weak reference to the process, so every
+//! entry stops once the owning
+//! [`AttachmentGuard`](crate::process::AttachmentGuard) is dropped.
+
+use crate::core::types::{Address, MemoryError, MemoryResult, MemoryValue};
+use crate::memory::backend::MemoryBackend;
+use crate::memory::pointer::{PointerChain, PointerResolver};
+use crate::memory::policy::MemoryPolicy;
+use crate::memory::reader::SafeMemoryReader;
+use crate::memory::writer::{stored_bytes, MemoryWrite, SafeMemoryWriter, WriteJournal};
+use crate::process::{AttachmentGuard, ProcessHandle};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering as AtomicOrdering};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tokio::time::MissedTickBehavior;
+
+/// Interval used when an entry does not set one
+pub const DEFAULT_FREEZE_INTERVAL_MS: u64 = 100;
+
+/// Identifier of a freeze entry within its manager
+pub type FreezeId = u64;
+
+/// How a frozen value is enforced
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FreezeMode {
+    /// Always write the frozen value
+    #[default]
+    Exact,
+    /// Treat the frozen value as a ceiling: lower values are left alone,
+    /// higher ones are replaced by it
+    NeverIncrease,
+    /// Treat the frozen value as a floor: higher values are left alone,
+    /// lower ones are replaced by it
+    NeverDecrease,
+}
+
+/// Where a frozen value lives
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FreezeTarget {
+    Address(Address),
+    /// Resolved again on every tick
+    Chain(PointerChain),
+}
+
+/// A value to keep in place
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FreezeEntry {
+    pub target: FreezeTarget,
+    pub value: MemoryValue,
+    #[serde(default = "default_interval_ms")]
+    pub interval_ms: u64,
+    #[serde(default)]
+    pub mode: FreezeMode,
+}
+
+fn default_interval_ms() -> u64 {
+    DEFAULT_FREEZE_INTERVAL_MS
+}
+
+impl FreezeEntry {
+    /// Freeze `value` exactly at `target` every [`DEFAULT_FREEZE_INTERVAL_MS`]
+    pub fn new(target: FreezeTarget, value: MemoryValue) -> Self {
+        FreezeEntry {
+            target,
+            value,
+            interval_ms: DEFAULT_FREEZE_INTERVAL_MS,
+            mode: FreezeMode::Exact,
+        }
+    }
+
+    /// Set the rewrite interval; zero is treated as one millisecond
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval_ms = interval.as_millis() as u64;
+        self
+    }
+
+    /// Set the freeze mode
+    pub fn with_mode(mut self, mode: FreezeMode) -> Self {
+        self.mode = mode;
+        self
+    }
+}
+
+/// State of one entry as reported by [`FreezeManager::list`]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FreezeStatus {
+    pub id: FreezeId,
+    pub entry: FreezeEntry,
+    pub paused: bool,
+    /// The process detached and the entry no longer runs
+    pub stopped: bool,
+    pub writes: u64,
+    pub failures: u64,
+    /// Error from the latest tick, cleared by the next successful one
+    pub last_error: Option<String>,
+}
+
+struct Slot {
+    status: Arc<Mutex<FreezeStatus>>,
+    task: JoinHandle<()>,
+}
+
+/// Owns the freeze entries of one process
+pub struct FreezeManager<B: MemoryBackend + 'static = ProcessHandle> {
+    handle: Weak<B>,
+    policy: MemoryPolicy,
+    journal: Arc<WriteJournal>,
+    entries: Mutex<BTreeMap<FreezeId, Slot>>,
+    next_id: AtomicU64,
+}
+
+impl FreezeManager {
+    /// Freeze values in the process attached by `guard`, backing up into its journal
+    pub fn for_guard(guard: &AttachmentGuard) -> Self {
+        let manager = Self::new(guard.share());
+        match guard.journal() {
+            Some(journal) => manager.with_journal(journal.clone()),
+            None => manager,
+        }
+    }
+}
+
+impl<B: MemoryBackend + 'static> FreezeManager<B> {
+    /// Freeze values through `handle` for as long as it can be upgraded
+    pub fn new(handle: Weak<B>) -> Self {
+        FreezeManager {
+            handle,
+            policy: MemoryPolicy::default(),
+            journal: Arc::new(WriteJournal::new()),
+            entries: Mutex::new(BTreeMap::new()),
+            next_id: AtomicU64::new(1),
+        }
+    }
+
+    /// Replace the policy entries are written under
+    pub fn with_policy(mut self, policy: MemoryPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Record backups in a shared journal, such as one an attachment reverts on detach
+    pub fn with_journal(mut self, journal: Arc<WriteJournal>) -> Self {
+        self.journal = journal;
+        self
+    }
+
+    /// Whether the process is still attached
+    pub fn is_active(&self) -> bool {
+        self.handle.strong_count() > 0
+    }
+
+    /// Start freezing an entry; must be called within a tokio runtime
+    pub fn add(&self, entry: FreezeEntry) -> MemoryResult<FreezeId> {
+        if entry.mode != FreezeMode::Exact && !entry.value.value_type().is_numeric() {
+            return Err(MemoryError::InvalidValueType(format!(
+                "{:?} freezing needs a numeric value, not {}",
+                entry.mode, entry.value
+            )));
+        }
+        let Some(handle) = self.handle.upgrade() else {
+            return Err(MemoryError::InvalidHandle(
+                "process is no longer attached".to_string(),
+            ));
+        };
+        handle.policy_access().check_write(handle.pid())?;
+        drop(handle);
+        let runtime = tokio::runtime::Handle::try_current().map_err(|e| {
+            MemoryError::UnsupportedOperation(format!("freezing needs a tokio runtime: {}", e))
+        })?;
+
+        let id = self.next_id.fetch_add(1, AtomicOrdering::Relaxed);
+        let interval = Duration::from_millis(entry.interval_ms.max(1));
+        let status = Arc::new(Mutex::new(FreezeStatus {
+            id,
+            entry,
+            paused: false,
+            stopped: false,
+            writes: 0,
+            failures: 0,
+            last_error: None,
+        }));
+        let task = runtime.spawn(run(
+            self.handle.clone(),
+            Writes {
+                policy: self.policy.clone(),
+                journal: self.journal.clone(),
+            },
+            status.clone(),
+            interval,
+        ));
+        self.entries
+            .lock()
+            .unwrap()
+            .insert(id, Slot { status, task });
+        Ok(id)
+    }
+
+    /// Stop and forget an entry, returning whether it existed
+    pub fn remove(&self, id: FreezeId) -> bool {
+        match self.entries.lock().unwrap().remove(&id) {
+            Some(slot) => {
+                slot.task.abort();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Pause or resume an entry
+    pub fn pause(&self, id: FreezeId, paused: bool) -> MemoryResult<()> {
+        let entries = self.entries.lock().unwrap();
+        let slot = entries.get(&id).ok_or_else(|| {
+            MemoryError::InvalidArgument(format!("no freeze entry with id {}", id))
+        })?;
+        slot.status.lock().unwrap().paused = paused;
+        Ok(())
+    }
+
+    /// Status of every entry, in the order they were added
+    pub fn list(&self) -> Vec<FreezeStatus> {
+        self.entries
+            .lock()
+            .unwrap()
+            .values()
+            .map(|slot| slot.status.lock().unwrap().clone())
+            .collect()
+    }
+}
+
+impl<B: MemoryBackend + 'static> Drop for FreezeManager<B> {
+    fn drop(&mut self) {
+        for slot in self.entries.get_mut().unwrap().values() {
+            slot.task.abort();
+        }
+    }
+}
+
+/// Policy and journal an entry's task writes with
+struct Writes {
+    policy: MemoryPolicy,
+    journal: Arc<WriteJournal>,
+}
+
+async fn run<B: MemoryBackend>(
+    handle: Weak<B>,
+    writes: Writes,
+    status: Arc<Mutex<FreezeStatus>>,
+    interval: Duration,
+) {
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    loop {
+        ticker.tick().await;
+        let Some(handle) = handle.upgrade() else {
+            status.lock().unwrap().stopped = true;
+            return;
+        };
+        // Copy the entry out so `list` and `pause` never wait on process memory
+        let entry = {
+            let status = status.lock().unwrap();
+            if status.paused {
+                continue;
+            }
+            status.entry.clone()
+        };
+        let writer = SafeMemoryWriter::with_policy(&*handle, &writes.policy, &writes.journal);
+        let result = apply(&*handle, &writer, &entry);
+
+        let mut status = status.lock().unwrap();
+        match result {
+            Ok(wrote) => {
+                status.writes += wrote as u64;
+                status.last_error = None;
+            }
+            Err(e) => {
+                status.failures += 1;
+                status.last_error = Some(e.to_string());
+            }
+        }
+    }
+}
+
+/// Enforce `entry` once, returning whether a write was needed
+fn apply<B: MemoryBackend>(
+    handle: &B,
+    writer: &SafeMemoryWriter<'_, B>,
+    entry: &FreezeEntry,
+) -> MemoryResult<bool> {
+    let address = match &entry.target {
+        FreezeTarget::Address(address) => *address,
+        FreezeTarget::Chain(chain) => {
+            PointerResolver::new(handle, handle.architecture())
+                .resolve(chain)?
+                .address
+        }
+    };
+    let allowed = match entry.mode {
+        FreezeMode::Exact => {
+            let frozen = stored_bytes(&entry.value, handle.architecture());
+            let mut current = vec![0u8; frozen.len()];
+            handle.read(address, &mut current)?;
+            if current == frozen {
+                return Ok(false);
+            }
+            writer.write_value(address, &entry.value)?;
+            return Ok(true);
+        }
+        FreezeMode::NeverIncrease => Ordering::Less,
+        FreezeMode::NeverDecrease => Ordering::Greater,
+    };
+    let current = SafeMemoryReader::new(handle).read_value(address, entry.value.value_type())?;
+    match compare(&current, &entry.value) {
+        Some(Ordering::Equal) => Ok(false),
+        Some(order) if order == allowed => Ok(false),
+        _ => {
+            writer.write_value(address, &entry.value)?;
+            Ok(true)
+        }
+    }
+}
+
+fn compare(a: &MemoryValue, b: &MemoryValue) -> Option<Ordering> {
+    match (a, b) {
+        (MemoryValue::I8(a), MemoryValue::I8(b)) => a.partial_cmp(b),
+        (MemoryValue::I16(a), MemoryValue::I16(b)) => a.partial_cmp(b),
+        (MemoryValue::I32(a), MemoryValue::I32(b)) => a.partial_cmp(b),
+        (MemoryValue::I64(a), MemoryValue::I64(b)) => a.partial_cmp(b),
+        (MemoryValue::I128(a), MemoryValue::I128(b)) => a.partial_cmp(b),
+        (MemoryValue::U8(a), MemoryValue::U8(b)) => a.partial_cmp(b),
+        (MemoryValue::U16(a), MemoryValue::U16(b)) => a.partial_cmp(b),
+        (MemoryValue::U32(a), MemoryValue::U32(b)) => a.partial_cmp(b),
+        (MemoryValue::U64(a), MemoryValue::U64(b)) => a.partial_cmp(b),
+        (MemoryValue::U128(a), MemoryValue::U128(b)) => a.partial_cmp(b),
+        (MemoryValue::F32(a), MemoryValue::F32(b)) => a.partial_cmp(b),
+        (MemoryValue::F64(a), MemoryValue::F64(b)) => a.partial_cmp(b),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::backend::{SnapshotProcess, SnapshotRegion};
+    use crate::process::PolicyAccess;
+
+    const BASE: usize = 0x10000;
+
+    fn process() -> Arc<SnapshotProcess> {
+        Arc::new(SnapshotProcess::with_region(1, BASE, vec![0; 0x1000], 0x04))
+    }
+
+    fn read_i32(process: &SnapshotProcess, offset: usize) -> i32 {
+        SafeMemoryReader::new(process)
+            .read(Address::new(BASE + offset))
+            .unwrap()
+    }
+
+    fn write_i32(process: &SnapshotProcess, offset: usize, value: i32) {
+        SafeMemoryWriter::new(process)
+            .write(Address::new(BASE + offset), value)
+            .unwrap();
+    }
+
+    async fn ticks() {
+        tokio::time::sleep(Duration::from_millis(60)).await;
+    }
+
+    fn entry(offset: usize, value: i32, mode: FreezeMode) -> FreezeEntry {
+        FreezeEntry::new(
+            FreezeTarget::Address(Address::new(BASE + offset)),
+            MemoryValue::I32(value),
+        )
+        .with_interval(Duration::from_millis(5))
+        .with_mode(mode)
+    }
+
+    #[tokio::test]
+    async fn test_modes() {
+        let process = process();
+        let manager = FreezeManager::new(Arc::downgrade(&process));
+        manager.add(entry(0, 100, FreezeMode::Exact)).unwrap();
+        manager
+            .add(entry(4, 50, FreezeMode::NeverIncrease))
+            .unwrap();
+        let floor = manager
+            .add(entry(8, 10, FreezeMode::NeverDecrease))
+            .unwrap();
+        SafeMemoryWriter::new(&*process)
+            .write(Address::new(BASE + 0x100), (BASE + 0x200) as u64)
+            .unwrap();
+        let chain = PointerChain::from_address(Address::new(BASE + 0x100), vec![0x10]);
+        manager
+            .add(FreezeEntry::new(
+                FreezeTarget::Chain(chain),
+                MemoryValue::I32(3),
+            ))
+            .unwrap();
+        ticks().await;
+        assert_eq!(read_i32(&process, 0x210), 3);
+        assert_eq!(read_i32(&process, 0), 100);
+        // Below the ceiling, so left alone; raised to the floor
+        assert_eq!(read_i32(&process, 4), 0);
+        assert_eq!(read_i32(&process, 8), 10);
+
+        // Allowed moves are kept, disallowed ones undone
+        write_i32(&process, 4, 40);
+        write_i32(&process, 8, 20);
+        ticks().await;
+        assert_eq!(read_i32(&process, 4), 40);
+        assert_eq!(read_i32(&process, 8), 20);
+        write_i32(&process, 4, 90);
+        write_i32(&process, 8, 5);
+        write_i32(&process, 0, 1);
+        ticks().await;
+        assert_eq!(read_i32(&process, 4), 50);
+        assert_eq!(read_i32(&process, 8), 10);
+        assert_eq!(read_i32(&process, 0), 100);
+
+        // The ceiling and floor stay where they were set
+        let status = manager.list();
+        assert_eq!(status.len(), 4);
+        assert_eq!(status[2].id, floor);
+        assert_eq!(status[2].entry.value, MemoryValue::I32(10));
+        assert!(status.iter().all(|s| s.writes > 0 && s.failures == 0));
+    }
+
+    #[tokio::test]
+    async fn test_pause_remove_and_failures() {
+        let process = process();
+        let manager = FreezeManager::new(Arc::downgrade(&process));
+        let id = manager.add(entry(0, 7, FreezeMode::Exact)).unwrap();
+        let bad = manager
+            .add(FreezeEntry::new(
+                FreezeTarget::Address(Address::new(0x9000_0000)),
+                MemoryValue::U8(1),
+            ))
+            .unwrap();
+        ticks().await;
+
+        manager.pause(id, true).unwrap();
+        write_i32(&process, 0, 8);
+        ticks().await;
+        assert_eq!(read_i32(&process, 0), 8);
+        manager.pause(id, false).unwrap();
+        ticks().await;
+        assert_eq!(read_i32(&process, 0), 7);
+
+        let failed = manager.list().into_iter().find(|s| s.id == bad).unwrap();
+        assert!(failed.failures > 0 && failed.last_error.is_some());
+
+        assert!(manager.remove(id));
+        assert!(!manager.remove(id));
+        assert!(matches!(
+            manager.pause(id, true),
+            Err(MemoryError::InvalidArgument(_))
+        ));
+        write_i32(&process, 0, 9);
+        ticks().await;
+        assert_eq!(read_i32(&process, 0), 9);
+    }
+
+    #[tokio::test]
+    async fn test_stops_when_process_detaches() {
+        let process = process();
+        let manager = FreezeManager::new(Arc::downgrade(&process));
+        manager.add(entry(0, 1, FreezeMode::Exact)).unwrap();
+        assert!(manager
+            .add(
+                FreezeEntry::new(
+                    FreezeTarget::Address(Address::new(BASE)),
+                    MemoryValue::String("x".to_string())
+                )
+                .with_mode(FreezeMode::NeverIncrease)
+            )
+            .is_err());
+
+        drop(process);
+        ticks().await;
+        assert!(!manager.is_active());
+        assert!(manager.list().iter().all(|s| s.stopped));
+        assert!(manager.add(entry(0, 1, FreezeMode::Exact)).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_writes_follow_policy() {
+        let mut snapshot = SnapshotProcess::with_region(1, BASE, vec![0; 0x1000], 0x04);
+        snapshot
+            .add_region(SnapshotRegion::new(
+                Address::new(BASE + 0x1000),
+                vec![0; 0x1000],
+                0x40,
+            ))
+            .unwrap();
+        let process = Arc::new(snapshot);
+        let journal = Arc::new(WriteJournal::new());
+        let manager = FreezeManager::new(Arc::downgrade(&process))
+            .with_policy(MemoryPolicy::default().with_backups(true))
+            .with_journal(journal.clone());
+        manager.add(entry(0, 7, FreezeMode::Exact)).unwrap();
+        let code = manager.add(entry(0x1000, 7, FreezeMode::Exact)).unwrap();
+        ticks().await;
+
+        // Only writes that changed something are backed up
+        assert_eq!(journal.patches().len(), 1);
+        write_i32(&process, 0, 8);
+        ticks().await;
+        assert_eq!(read_i32(&process, 0), 7);
+        assert_eq!(journal.patches().len(), 2);
+
+        let refused = manager.list().into_iter().find(|s| s.id == code).unwrap();
+        assert_eq!(refused.writes, 0);
+        assert!(refused.last_error.unwrap().contains("executable"));
+        assert_eq!(read_i32(&process, 0x1000), 0);
+    }
+
+    #[test]
+    fn test_add_refuses_read_only_process() {
+        let snapshot = SnapshotProcess::with_region(1, BASE, vec![0; 0x1000], 0x04)
+            .with_policy_access(PolicyAccess::ReadOnly {
+                rule: "game.exe".to_string(),
+            });
+        let process = Arc::new(snapshot);
+        let manager = FreezeManager::new(Arc::downgrade(&process));
+        assert!(matches!(
+            manager.add(entry(0, 1, FreezeMode::Exact)),
+            Err(MemoryError::PermissionDenied(_))
+        ));
+    }
+
+    #[test]
+    fn test_add_needs_runtime() {
+        let process = process();
+        let manager = FreezeManager::new(Arc::downgrade(&process));
+        assert!(matches!(
+            manager.add(entry(0, 1, FreezeMode::Exact)),
+            Err(MemoryError::UnsupportedOperation(_))
+        ));
+    }
+}
@@ -5,55 +5,129 @@
 //! - Batch operations for performance
 //! - Memory region validation
 //! - Basic pattern scanning
+//! - Typed first-scan/next-scan value searches
+//! - Multi-level pointer chain resolution and pointer scanning
+//! - Structure definitions read as JSON and written field by field
+//! - Background freezing of values
+//! - Watch lists reporting value changes
+//! - A write journal for rolling back and reverting changes
+//! - Read size limits and write protection from the configuration
+//!
+//! All OS access goes through the [`MemoryBackend`] trait.
 
+pub mod backend;
+pub(crate) mod binary;
+pub mod freeze;
+pub mod pointer;
+pub mod policy;
 pub mod reader;
 pub mod regions;
+pub mod scan;
 pub mod scanner;
+pub mod structs;
+pub mod watch;
 pub mod writer;
 
+pub use backend::{MemoryBackend, SnapshotProcess, SnapshotRegion};
+pub use freeze::{FreezeEntry, FreezeManager, FreezeMode, FreezeStatus, FreezeTarget};
+pub use pointer::{
+    PointerBase, PointerChain, PointerMap, PointerResolver, PointerScanConfig, PointerScanFile,
+    PointerScanner, ResolvedChain,
+};
+pub use policy::MemoryPolicy;
 pub use reader::{BasicMemoryReader, MemoryReader, ReadCache, Reader, SafeMemoryReader};
 pub use regions::{
-    enumerate_regions, query_region, FilterCriteria, MappedRegion, MappingOptions, MemoryMapper,
-    ProtectionFlags, ProtectionManager, RegionEnumerator, RegionFilter, RegionInfo, RegionState,
-    RegionType,
+    enumerate_regions, query_region, FilterCriteria, ProtectionFlags, RegionEnumerator,
+    RegionFilter, RegionInfo, RegionState, RegionType,
+};
+#[cfg(windows)]
+pub use regions::{MappedRegion, MappingOptions, MemoryMapper, ProtectionManager};
+pub use scan::{
+    CandidateStore, ScanCriteria, ScanPredicate, SessionManager, StoreConfig, ValueScanner,
+};
+pub use scanner::{
+    ComparisonType, MemoryScanner, PatternMatch, RipRelative, ScanOptions, ScanPattern, Signature,
+};
+pub use structs::{FieldDef, StructAccessor, StructDef, StructRegistry};
+pub use watch::{ChangeEvent, WatchSpec, Watcher};
+pub use writer::{
+    create_safe_writer, create_writer, BasicMemoryWriter, Patch, SafeMemoryWriter, Transaction,
+    WriteJournal,
 };
-pub use scanner::{ComparisonType, MemoryScanner, ScanOptions, ScanPattern};
-pub use writer::{create_safe_writer, create_writer, BasicMemoryWriter, SafeMemoryWriter};
 
+use crate::config::Config;
 use crate::core::types::{Address, MemoryError, MemoryResult, MemoryValue};
 use crate::process::ProcessHandle;
-use crate::windows::bindings::kernel32;
 use std::collections::HashMap;
+use std::sync::Arc;
 
 /// Memory operation context that holds process handle and provides unified interface
+///
+/// Readers and safe writers it hands out enforce its [`MemoryPolicy`]. With
+/// `backup_before_write` set, safe writes are recorded in its journal.
 pub struct MemoryOperations {
     handle: ProcessHandle,
+    policy: MemoryPolicy,
+    journal: Arc<WriteJournal>,
 }
 
 impl MemoryOperations {
     /// Create new memory operations context for a process
     pub fn new(handle: ProcessHandle) -> Self {
-        MemoryOperations { handle }
+        MemoryOperations {
+            handle,
+            policy: MemoryPolicy::default(),
+            journal: Arc::new(WriteJournal::new()),
+        }
+    }
+
+    /// Create a context enforcing the `[memory]` settings of `config`
+    pub fn from_config(handle: ProcessHandle, config: &Config) -> Self {
+        Self::new(handle).with_policy(MemoryPolicy::from_config(&config.memory))
+    }
+
+    /// Replace the policy readers and writers enforce
+    pub fn with_policy(mut self, policy: MemoryPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Record backups in a shared journal, such as one an attachment reverts on detach
+    pub fn with_journal(mut self, journal: Arc<WriteJournal>) -> Self {
+        self.journal = journal;
+        self
+    }
+
+    /// Get the policy in force
+    pub fn policy(&self) -> &MemoryPolicy {
+        &self.policy
+    }
+
+    /// Get the journal backups are recorded in
+    pub fn journal(&self) -> &Arc<WriteJournal> {
+        &self.journal
     }
 
     /// Get a reference to the memory reader
     pub fn reader(&self) -> MemoryReader<'_> {
-        MemoryReader::new(&self.handle)
+        MemoryReader::new(&self.handle).with_policy(self.policy.clone())
     }
 
     /// Get a mutable reference to the memory reader
     pub fn reader_mut(&mut self) -> MemoryReader<'_> {
-        MemoryReader::new(&self.handle)
+        self.reader()
     }
 
-    /// Get a reference to the memory writer
-    pub fn writer(&self) -> BasicMemoryWriter<'_> {
-        BasicMemoryWriter::new(&self.handle)
+    /// Get a memory writer enforcing the policy
+    ///
+    /// The same writer as [`safe_writer`](Self::safe_writer).
+    pub fn writer(&self) -> SafeMemoryWriter<'_> {
+        self.safe_writer()
     }
 
     /// Get a safe memory writer with validation
     pub fn safe_writer(&self) -> SafeMemoryWriter<'_> {
-        SafeMemoryWriter::new(&self.handle)
+        SafeMemoryWriter::with_policy(&self.handle, &self.policy, &self.journal)
     }
 
     /// Get a reference to the memory scanner
@@ -63,15 +137,18 @@ impl MemoryOperations {
 
     /// Read a value from memory
     pub fn read<T: Copy>(&mut self, address: Address) -> MemoryResult<T> {
-        let reader = MemoryReader::new(&self.handle);
-        reader.read(address)
+        self.reader().read(address)
     }
 
     /// Write a value to memory
     pub fn write<T: Copy>(&self, address: Address, value: T) -> MemoryResult<()> {
         use writer::MemoryWrite;
-        let writer = BasicMemoryWriter::new(&self.handle);
-        writer.write(address, value)
+        self.safe_writer().write(address, value)
+    }
+
+    /// Restore everything backed up for this process, newest first
+    pub fn revert_all(&self) -> MemoryResult<usize> {
+        self.journal.revert_all(&self.handle)
     }
 
     /// Scan for a pattern in memory
@@ -82,32 +159,33 @@ impl MemoryOperations {
 }
 
 /// Validate that a memory region is accessible
-pub fn validate_region(handle: &ProcessHandle, address: Address, size: usize) -> MemoryResult<()> {
+pub fn validate_region<B: MemoryBackend>(
+    backend: &B,
+    address: Address,
+    size: usize,
+) -> MemoryResult<()> {
     // Query the memory region to check if it's valid
-    unsafe {
-        let mbi = kernel32::virtual_query_ex(handle.raw(), address.as_usize())?;
-
-        // Check if the region is committed
-        const MEM_COMMIT: u32 = 0x1000;
-        if mbi.State != MEM_COMMIT {
-            return Err(MemoryError::InvalidAddress(format!(
-                "Memory at 0x{:X} is not committed",
-                address.as_usize()
-            )));
-        }
-
-        // Check if the region is large enough
-        if mbi.RegionSize < size {
-            return Err(MemoryError::InvalidAddress(format!(
-                "Memory region at 0x{:X} is too small (requested: {}, available: {})",
-                address.as_usize(),
-                size,
-                mbi.RegionSize
-            )));
-        }
+    let region = backend.query_region(address)?;
+
+    // Check if the region is committed
+    if region.state != RegionState::Committed {
+        return Err(MemoryError::InvalidAddress(format!(
+            "Memory at 0x{:X} is not committed",
+            address.as_usize()
+        )));
+    }
 
-        Ok(())
+    // Check if the region is large enough
+    if region.size < size {
+        return Err(MemoryError::InvalidAddress(format!(
+            "Memory region at 0x{:X} is too small (requested: {}, available: {})",
+            address.as_usize(),
+            size,
+            region.size
+        )));
     }
+
+    Ok(())
 }
 
 #[cfg(test)]
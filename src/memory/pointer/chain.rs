@@ -0,0 +1,379 @@
+//! Multi-level pointer chains and their resolution
+
+use crate::core::types::{Address, MemoryError, MemoryResult, ProcessArchitecture};
+use crate::memory::backend::MemoryBackend;
+use crate::memory::reader::MemoryReader;
+use crate::process::ProcessHandle;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Where a pointer chain starts
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PointerBase {
+    /// Offset from the base of a loaded module, e.g. `game.exe+0x1234`
+    Module { name: String, offset: usize },
+    /// Fixed address
+    Address(Address),
+}
+
+/// A base plus the offsets applied after each dereference
+///
+/// The base is dereferenced, the first offset added, the result dereferenced
+/// again, and so on; the last offset is added without a final dereference.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PointerChain {
+    pub base: PointerBase,
+    pub offsets: Vec<isize>,
+}
+
+/// Result of walking a pointer chain
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedChain {
+    /// Final address the chain points at
+    pub address: Address,
+    /// Address dereferenced at each level, starting with the base
+    pub levels: Vec<Address>,
+}
+
+impl PointerChain {
+    /// Create a chain from a base and offsets
+    pub fn new(base: PointerBase, offsets: Vec<isize>) -> Self {
+        PointerChain { base, offsets }
+    }
+
+    /// Create a chain starting at `module+offset`
+    pub fn from_module(name: impl Into<String>, offset: usize, offsets: Vec<isize>) -> Self {
+        Self::new(
+            PointerBase::Module {
+                name: name.into(),
+                offset,
+            },
+            offsets,
+        )
+    }
+
+    /// Create a chain starting at a fixed address
+    pub fn from_address(address: Address, offsets: Vec<isize>) -> Self {
+        Self::new(PointerBase::Address(address), offsets)
+    }
+
+    /// Number of dereferences performed when resolving
+    pub fn depth(&self) -> usize {
+        self.offsets.len()
+    }
+}
+
+impl FromStr for PointerChain {
+    type Err = MemoryError;
+
+    /// Parse Cheat Engine style chains such as `"game.exe"+0x10F4F4 -> 0x374 -> 0x14`
+    ///
+    /// Numbers are hexadecimal with an optional `0x` prefix. A base that is not
+    /// quoted and not a hex number is taken as a module name.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split("->").map(str::trim);
+        let base = parse_base(parts.next().unwrap_or_default())?;
+        let offsets = parts
+            .map(|part| {
+                parse_signed_hex(part).ok_or_else(|| {
+                    MemoryError::InvalidAddress(format!("Invalid pointer offset '{}'", part))
+                })
+            })
+            .collect::<MemoryResult<_>>()?;
+
+        Ok(PointerChain { base, offsets })
+    }
+}
+
+impl fmt::Display for PointerChain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.base {
+            PointerBase::Module { name, offset } => write!(f, "\"{}\"+0x{:X}", name, offset)?,
+            PointerBase::Address(address) => write!(f, "0x{:X}", address.as_usize())?,
+        }
+        for &offset in &self.offsets {
+            if offset < 0 {
+                write!(f, " -> -0x{:X}", offset.unsigned_abs())?;
+            } else {
+                write!(f, " -> 0x{:X}", offset)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn parse_base(text: &str) -> MemoryResult<PointerBase> {
+    let invalid = || MemoryError::InvalidAddress(format!("Invalid pointer base '{}'", text));
+
+    let (name, offset) = match text.rsplit_once('+') {
+        Some((name, offset)) => (name.trim(), parse_hex(offset.trim()).ok_or_else(invalid)?),
+        None => (text, 0),
+    };
+
+    if let Some(quoted) = name.strip_prefix('"').and_then(|n| n.strip_suffix('"')) {
+        if quoted.is_empty() {
+            return Err(invalid());
+        }
+        return Ok(PointerBase::Module {
+            name: quoted.to_string(),
+            offset,
+        });
+    }
+
+    match parse_hex(name) {
+        Some(address) => address
+            .checked_add(offset)
+            .map(|a| PointerBase::Address(Address::new(a)))
+            .ok_or_else(invalid),
+        None if name.is_empty() || name.contains('"') => Err(invalid()),
+        None => Ok(PointerBase::Module {
+            name: name.to_string(),
+            offset,
+        }),
+    }
+}
+
+fn parse_hex(text: &str) -> Option<usize> {
+    let digits = text
+        .strip_prefix("0x")
+        .or_else(|| text.strip_prefix("0X"))
+        .unwrap_or(text);
+    usize::from_str_radix(digits, 16).ok()
+}
+
+fn parse_signed_hex(text: &str) -> Option<isize> {
+    match text.strip_prefix('-') {
+        Some(rest) => parse_hex(rest.trim()).map(|v| (v as isize).wrapping_neg()),
+        None => parse_hex(text.strip_prefix('+').unwrap_or(text).trim()).map(|v| v as isize),
+    }
+}
+
+/// Walks pointer chains through a process's memory
+pub struct PointerResolver<'a, B: MemoryBackend = ProcessHandle> {
+    handle: &'a B,
+    reader: MemoryReader<'a, B>,
+    pointer_size: usize,
+}
+
+impl<'a, B: MemoryBackend> PointerResolver<'a, B> {
+    /// Create a resolver reading pointers of `architecture`'s size
+    pub fn new(handle: &'a B, architecture: ProcessArchitecture) -> Self {
+        PointerResolver {
+            handle,
+            reader: MemoryReader::new(handle),
+            pointer_size: architecture.pointer_size(),
+        }
+    }
+
+    /// Address a chain base refers to
+    pub fn base_address(&self, base: &PointerBase) -> MemoryResult<Address> {
+        match base {
+            PointerBase::Address(address) => Ok(*address),
+            PointerBase::Module { name, offset } => {
+                let module = self
+                    .handle
+                    .enumerate_modules()?
+                    .into_iter()
+                    .find(|m| m.name.eq_ignore_ascii_case(name))
+                    .ok_or_else(|| {
+                        MemoryError::pointer_chain_broken(0, format!("module '{}' not found", name))
+                    })?;
+                module
+                    .base_address
+                    .as_usize()
+                    .checked_add(*offset)
+                    .map(Address::new)
+                    .ok_or_else(|| {
+                        MemoryError::pointer_chain_broken(
+                            0,
+                            format!(
+                                "'{}'+0x{:X} overflows the address space",
+                                module.name, offset
+                            ),
+                        )
+                    })
+            }
+        }
+    }
+
+    /// Read one pointer-sized value
+    pub fn read_pointer(&self, address: Address) -> MemoryResult<Address> {
+        let value = if self.pointer_size == 4 {
+            u32::from_le_bytes(self.reader.read::<[u8; 4]>(address)?) as usize
+        } else {
+            u64::from_le_bytes(self.reader.read::<[u8; 8]>(address)?) as usize
+        };
+        Ok(Address::new(value))
+    }
+
+    /// Walk `chain`, returning the final address and each dereferenced address
+    ///
+    /// Fails with [`MemoryError::PointerChainBroken`] naming the level whose
+    /// pointer could not be read or was null.
+    pub fn resolve(&self, chain: &PointerChain) -> MemoryResult<ResolvedChain> {
+        let mut address = self.base_address(&chain.base)?;
+        let mut levels = Vec::with_capacity(chain.offsets.len());
+
+        for (level, &offset) in chain.offsets.iter().enumerate() {
+            levels.push(address);
+            let pointer = self.read_pointer(address).map_err(|e| {
+                MemoryError::pointer_chain_broken(
+                    level,
+                    format!("failed to read pointer at {}: {}", address, e),
+                )
+            })?;
+            if pointer.is_null() {
+                return Err(MemoryError::pointer_chain_broken(
+                    level,
+                    format!("null pointer at {}", address),
+                ));
+            }
+
+            address = pointer
+                .as_usize()
+                .checked_add_signed(offset)
+                .map(Address::new)
+                .ok_or_else(|| {
+                    MemoryError::pointer_chain_broken(level, "offset overflows the address space")
+                })?;
+        }
+
+        Ok(ResolvedChain { address, levels })
+    }
+
+    /// Walk `chain` and check that it ends at `expected`
+    ///
+    /// A chain ending elsewhere is reported as broken at its last level.
+    pub fn verify(&self, chain: &PointerChain, expected: Address) -> MemoryResult<ResolvedChain> {
+        let resolved = self.resolve(chain)?;
+        if resolved.address != expected {
+            return Err(MemoryError::pointer_chain_broken(
+                chain.depth(),
+                format!("resolves to {}, expected {}", resolved.address, expected),
+            ));
+        }
+        Ok(resolved)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::types::ModuleInfo;
+    use crate::memory::backend::{SnapshotProcess, SnapshotRegion};
+
+    const MODULE: usize = 0x400000;
+    const HEAP: usize = 0x800000;
+
+    /// game.exe+0x10 -> heap+0x100, heap+0x100+0x8 -> heap+0x200
+    fn process() -> SnapshotProcess {
+        let mut module = vec![0u8; 0x100];
+        module[0x10..0x18].copy_from_slice(&(HEAP as u64 + 0x100).to_le_bytes());
+        let mut heap = vec![0u8; 0x400];
+        heap[0x108..0x110].copy_from_slice(&(HEAP as u64 + 0x200).to_le_bytes());
+
+        let mut process = SnapshotProcess::with_region(1, MODULE, module, 0x02);
+        process
+            .add_region(SnapshotRegion::new(Address::new(HEAP), heap, 0x04))
+            .unwrap();
+        process.add_module(ModuleInfo::new(
+            "Game.exe".to_string(),
+            Address::new(MODULE),
+            0x100,
+        ));
+        process
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        let chain: PointerChain = "\"game.exe\"+0x10F4F4 -> 0x374 -> 0x14".parse().unwrap();
+        assert_eq!(
+            chain,
+            PointerChain::from_module("game.exe", 0x10F4F4, vec![0x374, 0x14])
+        );
+        assert_eq!(chain.to_string(), "\"game.exe\"+0x10F4F4 -> 0x374 -> 0x14");
+
+        let chain: PointerChain = "client.dll+10 -> -8".parse().unwrap();
+        assert_eq!(
+            chain,
+            PointerChain::from_module("client.dll", 0x10, vec![-8])
+        );
+        assert_eq!(chain.to_string(), "\"client.dll\"+0x10 -> -0x8");
+
+        let chain: PointerChain = "0x7FF61000 -> 0x20".parse().unwrap();
+        assert_eq!(
+            chain,
+            PointerChain::from_address(Address::new(0x7FF61000), vec![0x20])
+        );
+
+        assert!("\"game.exe\"+0xZZ".parse::<PointerChain>().is_err());
+        assert!("game.exe -> 0x1G".parse::<PointerChain>().is_err());
+        assert!("".parse::<PointerChain>().is_err());
+    }
+
+    #[test]
+    fn test_resolve_module_chain() {
+        let process = process();
+        let resolver = PointerResolver::new(&process, ProcessArchitecture::X64);
+        let chain: PointerChain = "\"game.exe\"+0x10 -> 0x8 -> 0x14".parse().unwrap();
+
+        let resolved = resolver.resolve(&chain).unwrap();
+        assert_eq!(resolved.address, Address::new(HEAP + 0x214));
+        assert_eq!(
+            resolved.levels,
+            vec![Address::new(MODULE + 0x10), Address::new(HEAP + 0x108)]
+        );
+    }
+
+    #[test]
+    fn test_resolve_32bit_pointers() {
+        let mut data = vec![0u8; 0x20];
+        data[0..4].copy_from_slice(&0x1010u32.to_le_bytes());
+        let process = SnapshotProcess::with_region(1, 0x1000, data, 0x04);
+
+        let resolver = PointerResolver::new(&process, ProcessArchitecture::X86);
+        let chain = PointerChain::from_address(Address::new(0x1000), vec![4]);
+        assert_eq!(
+            resolver.resolve(&chain).unwrap().address,
+            Address::new(0x1014)
+        );
+    }
+
+    #[test]
+    fn test_resolve_reports_broken_level() {
+        let process = process();
+        let resolver = PointerResolver::new(&process, ProcessArchitecture::X64);
+
+        // heap+0x108+0x10 holds a null pointer
+        let null = PointerChain::from_module("game.exe", 0x10, vec![0x10, 0x0]);
+        assert!(matches!(
+            resolver.resolve(&null),
+            Err(MemoryError::PointerChainBroken { level: 1, .. })
+        ));
+
+        let unreadable = PointerChain::from_address(Address::new(0x10), vec![0]);
+        assert!(matches!(
+            resolver.resolve(&unreadable),
+            Err(MemoryError::PointerChainBroken { level: 0, .. })
+        ));
+
+        let wrong = PointerChain::from_module("game.exe", 0x10, vec![0x8, 0x10]);
+        assert!(matches!(
+            resolver.verify(&wrong, Address::new(HEAP + 0x214)),
+            Err(MemoryError::PointerChainBroken { level: 2, .. })
+        ));
+
+        let missing = PointerChain::from_module("other.dll", 0, vec![0]);
+        assert!(matches!(
+            resolver.resolve(&missing),
+            Err(MemoryError::PointerChainBroken { level: 0, .. })
+        ));
+
+        let overflow: PointerChain = "\"game.exe\"+0xFFFFFFFFFFFFFFFF -> 0x0".parse().unwrap();
+        assert!(matches!(
+            resolver.resolve(&overflow),
+            Err(MemoryError::PointerChainBroken { level: 0, .. })
+        ));
+    }
+}
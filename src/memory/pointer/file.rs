@@ -0,0 +1,379 @@
+//! Pointer scan files and cross-run path intersection
+//!
+//! Addresses inside modules are stored as module name plus offset, never as
+//! absolute addresses, so a file saved in one run can be applied to the next
+//! even if modules load at different bases.
+//!
+//! Layout (all integers little-endian):
+//! - magic `MMCPPTRS`, format version (u32), pointer size (u32)
+//! - module count (u32), then per module: name (length-prefixed UTF-8), size (u64)
+//! - entry count (u64), then per entry: value and location, each a tag (u8)
+//!   followed by module index (u32) and offset (u64) for tag 0, or an
+//!   absolute address (u64) for tag 1
+//! - path count (u32), then per path: module name, offset (u64), offset
+//!   count (u32) and offsets (i64 each)
+
+use super::chain::{PointerBase, PointerChain, PointerResolver};
+use super::scanner::{PointerEntry, PointerMap};
+use crate::core::types::{Address, MemoryError, MemoryResult, ModuleInfo};
+use crate::memory::backend::MemoryBackend;
+use crate::memory::binary::{
+    invalid, read_array, read_str, read_u32, read_u64, read_u8, write_str, write_u32, write_u64,
+};
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+const MAGIC: &[u8; 8] = b"MMCPPTRS";
+const VERSION: u32 = 1;
+
+/// An address stored relative to a module where possible
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SavedLocation {
+    /// Offset into the module at this index of [`PointerScanFile::modules`]
+    Module { index: usize, offset: usize },
+    /// Address outside every module, only meaningful in the run that saved it
+    Absolute(Address),
+}
+
+/// A module recorded by name and size
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SavedModule {
+    pub name: String,
+    pub size: usize,
+}
+
+/// Saved pointer map and candidate paths
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PointerScanFile {
+    pub pointer_size: usize,
+    pub modules: Vec<SavedModule>,
+    /// Pointers as (value, location)
+    pub entries: Vec<(SavedLocation, SavedLocation)>,
+    pub paths: Vec<PointerChain>,
+}
+
+impl PointerScanFile {
+    /// Capture a pointer map and the paths found in it
+    pub fn new(map: &PointerMap, paths: Vec<PointerChain>) -> Self {
+        let modules = map.modules();
+        let relative = |address: Address| {
+            modules
+                .iter()
+                .position(|m| m.contains_address(address))
+                .map_or(SavedLocation::Absolute(address), |index| {
+                    SavedLocation::Module {
+                        index,
+                        offset: address.as_usize() - modules[index].base_address.as_usize(),
+                    }
+                })
+        };
+
+        PointerScanFile {
+            pointer_size: map.pointer_size(),
+            modules: modules
+                .iter()
+                .map(|m| SavedModule {
+                    name: m.name.clone(),
+                    size: m.size,
+                })
+                .collect(),
+            entries: map
+                .entries()
+                .iter()
+                .map(|e| (relative(e.value), relative(e.location)))
+                .collect(),
+            paths,
+        }
+    }
+
+    /// Rebuild the pointer map against the modules of the current run
+    ///
+    /// Entries in modules that are no longer loaded are dropped.
+    pub fn to_map(&self, modules: Vec<ModuleInfo>) -> PointerMap {
+        let bases: Vec<Option<usize>> = self
+            .modules
+            .iter()
+            .map(|saved| {
+                modules
+                    .iter()
+                    .find(|m| m.name.eq_ignore_ascii_case(&saved.name))
+                    .map(|m| m.base_address.as_usize())
+            })
+            .collect();
+        let absolute = |location: SavedLocation| match location {
+            SavedLocation::Module { index, offset } => {
+                bases[index].map(|b| Address::new(b + offset))
+            }
+            SavedLocation::Absolute(address) => Some(address),
+        };
+
+        let entries = self
+            .entries
+            .iter()
+            .filter_map(|&(value, location)| {
+                Some(PointerEntry {
+                    value: absolute(value)?,
+                    location: absolute(location)?,
+                })
+            })
+            .collect();
+        PointerMap::from_entries(self.pointer_size, modules, entries)
+    }
+
+    /// Keep only the paths that still resolve to `target`
+    ///
+    /// Returns the dropped paths with the [`MemoryError::PointerChainBroken`]
+    /// explaining why each no longer holds.
+    pub fn intersect<B: MemoryBackend>(
+        &mut self,
+        resolver: &PointerResolver<'_, B>,
+        target: Address,
+    ) -> Vec<(PointerChain, MemoryError)> {
+        let mut dropped = Vec::new();
+        self.paths
+            .retain(|path| match resolver.verify(path, target) {
+                Ok(_) => true,
+                Err(e) => {
+                    dropped.push((path.clone(), e));
+                    false
+                }
+            });
+        dropped
+    }
+
+    /// Write the file to `path`
+    pub fn save(&self, path: impl AsRef<Path>) -> MemoryResult<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write(&mut writer)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Read a file written by [`PointerScanFile::save`]
+    pub fn load(path: impl AsRef<Path>) -> MemoryResult<Self> {
+        let mut reader = BufReader::new(File::open(path)?);
+        Ok(Self::read(&mut reader)?)
+    }
+
+    fn write(&self, writer: &mut impl Write) -> io::Result<()> {
+        writer.write_all(MAGIC)?;
+        write_u32(writer, VERSION)?;
+        write_u32(writer, self.pointer_size as u32)?;
+
+        write_u32(writer, self.modules.len() as u32)?;
+        for module in &self.modules {
+            write_str(writer, &module.name)?;
+            write_u64(writer, module.size as u64)?;
+        }
+
+        write_u64(writer, self.entries.len() as u64)?;
+        for &(value, location) in &self.entries {
+            write_location(writer, value)?;
+            write_location(writer, location)?;
+        }
+
+        write_u32(writer, self.paths.len() as u32)?;
+        for path in &self.paths {
+            let PointerBase::Module { name, offset } = &path.base else {
+                return Err(invalid("Only module-based paths can be saved"));
+            };
+            write_str(writer, name)?;
+            write_u64(writer, *offset as u64)?;
+            write_u32(writer, path.offsets.len() as u32)?;
+            for &offset in &path.offsets {
+                writer.write_all(&(offset as i64).to_le_bytes())?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn read(reader: &mut impl Read) -> io::Result<Self> {
+        if &read_array::<8>(reader)? != MAGIC {
+            return Err(invalid("Not a pointer scan file"));
+        }
+        let version = read_u32(reader)?;
+        if version != VERSION {
+            return Err(invalid(format!(
+                "Unsupported pointer scan version {}",
+                version
+            )));
+        }
+        let pointer_size = read_u32(reader)? as usize;
+        if pointer_size != 4 && pointer_size != 8 {
+            return Err(invalid(format!("Invalid pointer size {}", pointer_size)));
+        }
+
+        let mut modules = Vec::new();
+        for _ in 0..read_u32(reader)? {
+            let name = read_str(reader)?;
+            let size = read_u64(reader)? as usize;
+            modules.push(SavedModule { name, size });
+        }
+
+        let mut entries = Vec::new();
+        for _ in 0..read_u64(reader)? {
+            let value = read_location(reader, modules.len())?;
+            let location = read_location(reader, modules.len())?;
+            entries.push((value, location));
+        }
+
+        let mut paths = Vec::new();
+        for _ in 0..read_u32(reader)? {
+            let name = read_str(reader)?;
+            let offset = read_u64(reader)? as usize;
+            let mut offsets = Vec::new();
+            for _ in 0..read_u32(reader)? {
+                offsets.push(i64::from_le_bytes(read_array(reader)?) as isize);
+            }
+            paths.push(PointerChain::from_module(name, offset, offsets));
+        }
+
+        Ok(PointerScanFile {
+            pointer_size,
+            modules,
+            entries,
+            paths,
+        })
+    }
+}
+
+fn write_location(writer: &mut impl Write, location: SavedLocation) -> io::Result<()> {
+    match location {
+        SavedLocation::Module { index, offset } => {
+            writer.write_all(&[0])?;
+            write_u32(writer, index as u32)?;
+            write_u64(writer, offset as u64)
+        }
+        SavedLocation::Absolute(address) => {
+            writer.write_all(&[1])?;
+            write_u64(writer, address.as_usize() as u64)
+        }
+    }
+}
+
+fn read_location(reader: &mut impl Read, module_count: usize) -> io::Result<SavedLocation> {
+    match read_u8(reader)? {
+        0 => {
+            let index = read_u32(reader)? as usize;
+            if index >= module_count {
+                return Err(invalid(format!("Module index {} out of range", index)));
+            }
+            let offset = read_u64(reader)? as usize;
+            Ok(SavedLocation::Module { index, offset })
+        }
+        1 => Ok(SavedLocation::Absolute(Address::new(
+            read_u64(reader)? as usize
+        ))),
+        tag => Err(invalid(format!("Unknown location tag {}", tag))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::types::ProcessArchitecture;
+    use crate::memory::backend::{SnapshotProcess, SnapshotRegion};
+    use crate::memory::pointer::{PointerScanConfig, PointerScanner};
+
+    const HEAP: usize = 0x800000;
+
+    /// game.exe+0x20 -> +0x18 -> +0x30 and game.exe+0x40 -> +0x30 both reach
+    /// `heap + 0x30`; `stale` breaks the second path
+    fn process(module_base: usize, heap: usize, stale: bool) -> SnapshotProcess {
+        let pointer = |value: usize| (value as u64).to_le_bytes();
+        let mut module = vec![0u8; 0x1000];
+        module[0x20..0x28].copy_from_slice(&pointer(heap + 0x100));
+        if !stale {
+            module[0x40..0x48].copy_from_slice(&pointer(heap));
+        }
+        let mut heap_data = vec![0u8; 0x1000];
+        heap_data[0x118..0x120].copy_from_slice(&pointer(heap));
+
+        let mut process = SnapshotProcess::with_region(1, module_base, module, 0x02);
+        process
+            .add_region(SnapshotRegion::new(Address::new(heap), heap_data, 0x04))
+            .unwrap();
+        process.add_module(ModuleInfo::new(
+            "game.exe".to_string(),
+            Address::new(module_base),
+            0x1000,
+        ));
+        process
+    }
+
+    fn scan(process: &SnapshotProcess) -> PointerScanFile {
+        let map = PointerMap::build(process, ProcessArchitecture::X64).unwrap();
+        let config = PointerScanConfig {
+            max_offset: 0x40,
+            ..PointerScanConfig::default()
+        };
+        let paths = PointerScanner::new(&map, config).scan(Address::new(HEAP + 0x30));
+        PointerScanFile::new(&map, paths)
+    }
+
+    #[test]
+    fn test_file_is_module_relative() {
+        let file = scan(&process(0x400000, HEAP, false));
+        assert_eq!(file.paths.len(), 2);
+        assert!(file.entries.contains(&(
+            SavedLocation::Absolute(Address::new(HEAP + 0x100)),
+            SavedLocation::Module {
+                index: 0,
+                offset: 0x20
+            },
+        )));
+
+        // The same map rebuilt against a relocated module
+        let moved = ModuleInfo::new("GAME.EXE".to_string(), Address::new(0x500000), 0x1000);
+        let map = file.to_map(vec![moved]);
+        assert_eq!(map.len(), 3);
+        assert!(map
+            .entries()
+            .iter()
+            .any(|e| e.location == Address::new(0x500020)));
+        // Without the module only the heap-to-heap pointer survives
+        assert_eq!(file.to_map(Vec::new()).len(), 1);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "File system operations not supported under Miri")]
+    fn test_save_load_and_intersect_across_runs() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("scan.ptr");
+        scan(&process(0x400000, HEAP, false)).save(&path).unwrap();
+
+        // Next run: new module base and heap, and the direct pointer is gone
+        let heap = 0x900000;
+        let next = process(0x600000, heap, true);
+        let mut file = PointerScanFile::load(&path).unwrap();
+        let resolver = PointerResolver::new(&next, ProcessArchitecture::X64);
+
+        let dropped = file.intersect(&resolver, Address::new(heap + 0x30));
+        assert_eq!(file.paths.len(), 1);
+        assert_eq!(
+            file.paths[0].to_string(),
+            "\"game.exe\"+0x20 -> 0x18 -> 0x30"
+        );
+        assert_eq!(dropped.len(), 1);
+        assert!(matches!(
+            dropped[0].1,
+            MemoryError::PointerChainBroken { level: 0, .. }
+        ));
+    }
+
+    #[test]
+    fn test_read_rejects_bad_input() {
+        assert!(PointerScanFile::read(&mut &b"NOTAFILE"[..]).is_err());
+
+        let mut bytes = Vec::new();
+        scan(&process(0x400000, HEAP, false))
+            .write(&mut bytes)
+            .unwrap();
+        assert!(PointerScanFile::read(&mut &bytes[..bytes.len() - 3]).is_err());
+
+        bytes[8] = 2;
+        assert!(PointerScanFile::read(&mut bytes.as_slice()).is_err());
+    }
+}
@@ -0,0 +1,9 @@
+//! Pointer chains: parsing, resolution and discovery
+
+pub mod chain;
+pub mod file;
+pub mod scanner;
+
+pub use chain::{PointerBase, PointerChain, PointerResolver, ResolvedChain};
+pub use file::{PointerScanFile, SavedLocation, SavedModule};
+pub use scanner::{PointerEntry, PointerMap, PointerScanConfig, PointerScanner};
@@ -0,0 +1,384 @@
+//! Pointer scanning: finding static pointer paths to a dynamic address
+//!
+//! A [`PointerMap`] records every pointer-aligned value in readable memory
+//! that itself points into readable memory, sorted by value so that "who
+//! points near X" is a binary search. [`PointerScanner`] then walks backward
+//! from the target, one dereference per level, until it reaches a location
+//! inside a module image.
+
+use super::chain::PointerChain;
+use crate::core::types::{Address, MemoryResult, ModuleInfo, ProcessArchitecture};
+use crate::memory::backend::MemoryBackend;
+use crate::memory::regions::RegionState;
+use std::collections::{HashMap, HashSet};
+
+/// Bytes read per request while building a pointer map
+const READ_CHUNK_SIZE: usize = 1024 * 1024;
+
+/// Limits for a pointer scan
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PointerScanConfig {
+    /// Maximum number of dereferences in a path
+    pub max_depth: usize,
+    /// Largest offset added after a dereference
+    pub max_offset: usize,
+    /// Stop after this many paths
+    pub max_results: usize,
+    /// Most addresses expanded at each level; those with the smallest offsets are kept
+    pub max_frontier: usize,
+}
+
+impl Default for PointerScanConfig {
+    fn default() -> Self {
+        PointerScanConfig {
+            max_depth: 4,
+            max_offset: 0x1000,
+            max_results: 1000,
+            max_frontier: 100_000,
+        }
+    }
+}
+
+/// A pointer found in memory
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct PointerEntry {
+    /// Address the pointer refers to
+    pub value: Address,
+    /// Address the pointer is stored at
+    pub location: Address,
+}
+
+/// Reverse pointer map of a process
+#[derive(Debug, Clone)]
+pub struct PointerMap {
+    pointer_size: usize,
+    modules: Vec<ModuleInfo>,
+    /// Sorted by value, then location
+    entries: Vec<PointerEntry>,
+}
+
+impl PointerMap {
+    /// Build a map from pre-collected entries
+    pub fn from_entries(
+        pointer_size: usize,
+        modules: Vec<ModuleInfo>,
+        mut entries: Vec<PointerEntry>,
+    ) -> Self {
+        entries.sort_unstable();
+        PointerMap {
+            pointer_size,
+            modules,
+            entries,
+        }
+    }
+
+    /// Snapshot every aligned pointer into readable memory
+    ///
+    /// Regions that fail to read are skipped.
+    pub fn build<B: MemoryBackend>(
+        backend: &B,
+        architecture: ProcessArchitecture,
+    ) -> MemoryResult<Self> {
+        let pointer_size = architecture.pointer_size();
+        let regions: Vec<(usize, usize)> = backend
+            .enumerate_regions()?
+            .into_iter()
+            .filter(|r| r.state == RegionState::Committed && r.is_readable() && !r.is_guarded())
+            .map(|r| (r.base_address.as_usize(), r.end_address().as_usize()))
+            .collect();
+        let is_valid = |value: usize| {
+            let i = regions.partition_point(|&(start, _)| start <= value);
+            i > 0 && value < regions[i - 1].1
+        };
+
+        let mut entries = Vec::new();
+        for &(start, end) in &regions {
+            let mut chunk = start;
+            while chunk < end {
+                let mut buffer = vec![0u8; READ_CHUNK_SIZE.min(end - chunk)];
+                if backend.read(Address::new(chunk), &mut buffer).is_ok() {
+                    // Regions are page aligned, so chunk-relative alignment is absolute
+                    for (i, bytes) in buffer.chunks_exact(pointer_size).enumerate() {
+                        let value = decode_pointer(bytes);
+                        if is_valid(value) {
+                            entries.push(PointerEntry {
+                                value: Address::new(value),
+                                location: Address::new(chunk + i * pointer_size),
+                            });
+                        }
+                    }
+                }
+                chunk += buffer.len();
+            }
+        }
+
+        Ok(Self::from_entries(
+            pointer_size,
+            backend.enumerate_modules()?,
+            entries,
+        ))
+    }
+
+    /// Size of each pointer in bytes
+    pub fn pointer_size(&self) -> usize {
+        self.pointer_size
+    }
+
+    /// Modules loaded when the map was built
+    pub fn modules(&self) -> &[ModuleInfo] {
+        &self.modules
+    }
+
+    /// All recorded pointers, sorted by value
+    pub fn entries(&self) -> &[PointerEntry] {
+        &self.entries
+    }
+
+    /// Number of recorded pointers
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether no pointers were recorded
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Pointers whose value lies in `low..=high`
+    pub fn pointers_to(&self, low: Address, high: Address) -> &[PointerEntry] {
+        let start = self.entries.partition_point(|e| e.value < low);
+        let end = self.entries.partition_point(|e| e.value <= high);
+        &self.entries[start..end.max(start)]
+    }
+
+    /// Module whose image contains `address`
+    pub fn module_containing(&self, address: Address) -> Option<&ModuleInfo> {
+        self.modules.iter().find(|m| m.contains_address(address))
+    }
+}
+
+fn decode_pointer(bytes: &[u8]) -> usize {
+    match bytes.len() {
+        4 => u32::from_le_bytes(bytes.try_into().unwrap()) as usize,
+        _ => u64::from_le_bytes(bytes.try_into().unwrap()) as usize,
+    }
+}
+
+/// Searches a [`PointerMap`] for static paths to an address
+pub struct PointerScanner<'m> {
+    map: &'m PointerMap,
+    config: PointerScanConfig,
+}
+
+impl<'m> PointerScanner<'m> {
+    /// Create a scanner over `map`
+    pub fn new(map: &'m PointerMap, config: PointerScanConfig) -> Self {
+        PointerScanner { map, config }
+    }
+
+    /// Find paths from module-static memory to `target`
+    ///
+    /// Paths are ranked by depth, then by the sum of their offsets, then by
+    /// module name and offset. Each path ends at its first static location.
+    ///
+    /// Every address is expanded at most once, at the shallowest depth it is
+    /// reached with the smallest offsets, so pointer cycles end there. Each
+    /// level keeps at most `max_frontier` addresses.
+    pub fn scan(&self, target: Address) -> Vec<PointerChain> {
+        let mut results = Vec::new();
+        // Each node is an address to reach plus the offsets from it to the target
+        let mut frontier = vec![(target, Vec::<isize>::new())];
+        let mut visited = HashSet::from([target]);
+
+        for _ in 0..self.config.max_depth {
+            let mut found = Vec::new();
+            let mut next: HashMap<Address, Vec<isize>> = HashMap::new();
+
+            for (address, offsets) in &frontier {
+                let low = Address::new(address.as_usize().saturating_sub(self.config.max_offset));
+                for entry in self.map.pointers_to(low, *address) {
+                    let mut path = Vec::with_capacity(offsets.len() + 1);
+                    path.push((address.as_usize() - entry.value.as_usize()) as isize);
+                    path.extend_from_slice(offsets);
+
+                    match self.map.module_containing(entry.location) {
+                        Some(module) => found.push(PointerChain::from_module(
+                            module.name.clone(),
+                            entry.location.as_usize() - module.base_address.as_usize(),
+                            path,
+                        )),
+                        None if visited.contains(&entry.location) => {}
+                        None => match next.get(&entry.location) {
+                            Some(known) if offset_total(known) <= offset_total(&path) => {}
+                            _ => {
+                                next.insert(entry.location, path);
+                            }
+                        },
+                    }
+                }
+            }
+
+            found.sort_by_cached_key(rank);
+            results.extend(found);
+            if results.len() >= self.config.max_results || next.is_empty() {
+                break;
+            }
+
+            let mut next: Vec<(Address, Vec<isize>)> = next.into_iter().collect();
+            next.sort_by_cached_key(|(address, path)| (offset_total(path), *address));
+            next.truncate(self.config.max_frontier);
+            visited.extend(next.iter().map(|(address, _)| *address));
+            frontier = next;
+        }
+
+        results.truncate(self.config.max_results);
+        results
+    }
+}
+
+fn rank(chain: &PointerChain) -> (usize, usize, String) {
+    (
+        chain.depth(),
+        offset_total(&chain.offsets),
+        chain.to_string(),
+    )
+}
+
+fn offset_total(offsets: &[isize]) -> usize {
+    offsets.iter().map(|o| o.unsigned_abs()).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::backend::{SnapshotProcess, SnapshotRegion};
+    use crate::memory::pointer::PointerResolver;
+
+    const MODULE: usize = 0x400000;
+    const HEAP: usize = 0x800000;
+
+    fn write_pointer(data: &mut [u8], offset: usize, value: usize) {
+        data[offset..offset + 8].copy_from_slice(&(value as u64).to_le_bytes());
+    }
+
+    /// game.exe+0x20 -> +0x18 -> +0x30 reaches the target at HEAP+0x530,
+    /// and game.exe+0x40 points at the first heap object directly
+    fn process() -> SnapshotProcess {
+        let mut module = vec![0u8; 0x1000];
+        write_pointer(&mut module, 0x20, HEAP + 0x100);
+        write_pointer(&mut module, 0x40, HEAP + 0x500);
+        let mut heap = vec![0u8; 0x1000];
+        write_pointer(&mut heap, 0x118, HEAP + 0x500);
+
+        let mut process = SnapshotProcess::with_region(1, MODULE, module, 0x02);
+        process
+            .add_region(SnapshotRegion::new(Address::new(HEAP), heap, 0x04))
+            .unwrap();
+        process.add_module(ModuleInfo::new(
+            "game.exe".to_string(),
+            Address::new(MODULE),
+            0x1000,
+        ));
+        process
+    }
+
+    #[test]
+    fn test_pointer_map_records_valid_pointers() {
+        let map = PointerMap::build(&process(), ProcessArchitecture::X64).unwrap();
+        assert_eq!(map.len(), 3);
+        assert_eq!(
+            map.pointers_to(Address::new(HEAP + 0x500), Address::new(HEAP + 0x500))
+                .iter()
+                .map(|e| e.location.as_usize())
+                .collect::<Vec<_>>(),
+            vec![MODULE + 0x40, HEAP + 0x118]
+        );
+    }
+
+    #[test]
+    fn test_scan_finds_ranked_paths() {
+        let process = process();
+        let map = PointerMap::build(&process, ProcessArchitecture::X64).unwrap();
+        let target = Address::new(HEAP + 0x530);
+
+        let config = PointerScanConfig {
+            max_offset: 0x100,
+            ..PointerScanConfig::default()
+        };
+        let paths = PointerScanner::new(&map, config).scan(target);
+        let listed: Vec<String> = paths.iter().map(|p| p.to_string()).collect();
+        assert_eq!(
+            listed,
+            vec![
+                "\"game.exe\"+0x40 -> 0x30",
+                "\"game.exe\"+0x20 -> 0x18 -> 0x30",
+            ]
+        );
+
+        let resolver = PointerResolver::new(&process, ProcessArchitecture::X64);
+        for path in &paths {
+            assert_eq!(resolver.resolve(path).unwrap().address, target);
+        }
+    }
+
+    #[test]
+    fn test_scan_honors_limits() {
+        let map = PointerMap::build(&process(), ProcessArchitecture::X64).unwrap();
+        let target = Address::new(HEAP + 0x530);
+
+        // Within 0x1000 the first heap object also reaches the target directly
+        let wide = PointerScanner::new(&map, PointerScanConfig::default()).scan(target);
+        assert_eq!(wide[1].to_string(), "\"game.exe\"+0x20 -> 0x430");
+
+        let shallow = PointerScanConfig {
+            max_depth: 1,
+            max_offset: 0x100,
+            ..PointerScanConfig::default()
+        };
+        assert_eq!(PointerScanner::new(&map, shallow).scan(target).len(), 1);
+
+        let near = PointerScanConfig {
+            max_offset: 0x20,
+            ..PointerScanConfig::default()
+        };
+        assert!(PointerScanner::new(&map, near).scan(target).is_empty());
+    }
+
+    #[test]
+    fn test_scan_expands_each_address_once() {
+        // Two heap objects pointing at each other and at themselves, one of
+        // them pointing near the target; nothing static reaches them
+        let mut heap = vec![0u8; 0x1000];
+        write_pointer(&mut heap, 0x100, HEAP + 0x100);
+        write_pointer(&mut heap, 0x108, HEAP + 0x200);
+        write_pointer(&mut heap, 0x200, HEAP + 0x100);
+        write_pointer(&mut heap, 0x208, HEAP + 0x500);
+        let process = SnapshotProcess::with_region(1, HEAP, heap, 0x04);
+        let map = PointerMap::build(&process, ProcessArchitecture::X64).unwrap();
+
+        let config = PointerScanConfig {
+            max_depth: 64,
+            max_offset: 0x10,
+            ..PointerScanConfig::default()
+        };
+        assert!(PointerScanner::new(&map, config)
+            .scan(Address::new(HEAP + 0x508))
+            .is_empty());
+    }
+
+    #[test]
+    fn test_scan_bounds_frontier() {
+        let map = PointerMap::build(&process(), ProcessArchitecture::X64).unwrap();
+        let target = Address::new(HEAP + 0x530);
+
+        // The only heap node on the way to the second path is dropped
+        let narrow = PointerScanConfig {
+            max_offset: 0x100,
+            max_frontier: 0,
+            ..PointerScanConfig::default()
+        };
+        let paths = PointerScanner::new(&map, narrow).scan(target);
+        assert_eq!(paths.len(), 1);
+        assert_eq!(paths[0].to_string(), "\"game.exe\"+0x40 -> 0x30");
+    }
+}
@@ -0,0 +1,193 @@
+//! Runtime limits applied to reads and writes, taken from [`MemoryConfig`]
+
+use crate::config::{default_config, MemoryConfig};
+use crate::core::types::{Address, MemoryError, MemoryResult};
+use crate::memory::backend::MemoryBackend;
+use crate::memory::regions::{RegionInfo, RegionType};
+
+/// Limits readers and writers enforce before touching process memory
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemoryPolicy {
+    /// Largest single read in bytes
+    pub max_read_size: usize,
+    /// Refuse writes to executable and image regions
+    pub enable_write_protection: bool,
+    /// Journal the original bytes of every write
+    pub backup_before_write: bool,
+    /// Let writes through to regions write protection would refuse
+    pub allow_protected_writes: bool,
+}
+
+impl Default for MemoryPolicy {
+    fn default() -> Self {
+        let memory = default_config().memory;
+        MemoryPolicy {
+            max_read_size: memory.max_read_size,
+            enable_write_protection: memory.enable_write_protection,
+            backup_before_write: memory.backup_before_write,
+            allow_protected_writes: false,
+        }
+    }
+}
+
+impl MemoryPolicy {
+    /// Policy described by the `[memory]` section of the configuration
+    pub fn from_config(config: &MemoryConfig) -> Self {
+        MemoryPolicy {
+            max_read_size: config.max_read_size,
+            enable_write_protection: config.enable_write_protection,
+            backup_before_write: config.backup_before_write,
+            allow_protected_writes: false,
+        }
+    }
+
+    /// Set the largest single read in bytes
+    pub fn with_max_read_size(mut self, max_read_size: usize) -> Self {
+        self.max_read_size = max_read_size;
+        self
+    }
+
+    /// Enable or disable write protection
+    pub fn with_write_protection(mut self, enable: bool) -> Self {
+        self.enable_write_protection = enable;
+        self
+    }
+
+    /// Enable or disable automatic backups
+    pub fn with_backups(mut self, enable: bool) -> Self {
+        self.backup_before_write = enable;
+        self
+    }
+
+    /// Explicitly allow writes to executable and image regions
+    pub fn with_protected_writes(mut self, allow: bool) -> Self {
+        self.allow_protected_writes = allow;
+        self
+    }
+
+    /// Reject reads larger than `max_read_size`
+    pub fn check_read(&self, address: Address, size: usize) -> MemoryResult<()> {
+        if size > self.max_read_size {
+            return Err(MemoryError::read_limit_exceeded(
+                address,
+                size,
+                self.max_read_size,
+            ));
+        }
+        Ok(())
+    }
+
+    /// Reject writes into executable or image regions unless explicitly allowed
+    pub fn check_write<B: MemoryBackend>(
+        &self,
+        backend: &B,
+        address: Address,
+        size: usize,
+    ) -> MemoryResult<()> {
+        if !self.enable_write_protection || self.allow_protected_writes || size == 0 {
+            return Ok(());
+        }
+        let end = address.as_usize().saturating_add(size);
+        let mut current = address;
+        while current.as_usize() < end {
+            let region = backend.query_region(current)?;
+            if let Some(reason) = protected_reason(&region) {
+                return Err(MemoryError::write_protected(address, reason));
+            }
+            if region.end_address() <= current {
+                break;
+            }
+            current = region.end_address();
+        }
+        Ok(())
+    }
+}
+
+/// Why write protection covers a region, if it does
+fn protected_reason(region: &RegionInfo) -> Option<String> {
+    if region.is_executable() {
+        Some(format!(
+            "region at {} is executable (protection 0x{:X})",
+            region.base_address, region.protection
+        ))
+    } else if region.region_type == RegionType::Image {
+        Some(format!(
+            "region at {} is a module image",
+            region.base_address
+        ))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::backend::{SnapshotProcess, SnapshotRegion};
+
+    fn process() -> SnapshotProcess {
+        let mut snapshot = SnapshotProcess::with_region(1, 0x1000, vec![0; 0x1000], 0x04);
+        snapshot
+            .add_region(SnapshotRegion::new(
+                Address::new(0x2000),
+                vec![0; 0x1000],
+                0x20,
+            ))
+            .unwrap();
+        snapshot
+    }
+
+    #[test]
+    fn test_from_config() {
+        let mut config = crate::config::Config::default().memory;
+        config.max_read_size = 64;
+        config.backup_before_write = false;
+        let policy = MemoryPolicy::from_config(&config);
+        assert_eq!(policy.max_read_size, 64);
+        assert!(policy.enable_write_protection);
+        assert!(!policy.backup_before_write);
+        assert!(!policy.allow_protected_writes);
+    }
+
+    #[test]
+    fn test_check_read() {
+        let policy = MemoryPolicy::default().with_max_read_size(16);
+        assert!(policy.check_read(Address::new(0x1000), 16).is_ok());
+        assert!(matches!(
+            policy.check_read(Address::new(0x1000), 17),
+            Err(MemoryError::ReadLimitExceeded {
+                size: 17,
+                limit: 16,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_check_write() {
+        let process = process();
+        let policy = MemoryPolicy::default();
+        assert!(policy
+            .check_write(&process, Address::new(0x1000), 0x10)
+            .is_ok());
+        assert!(matches!(
+            policy.check_write(&process, Address::new(0x2000), 4),
+            Err(MemoryError::WriteProtected { .. })
+        ));
+        // A write running into the executable region is refused too
+        assert!(matches!(
+            policy.check_write(&process, Address::new(0x1FFE), 4),
+            Err(MemoryError::WriteProtected { .. })
+        ));
+
+        assert!(policy
+            .clone()
+            .with_protected_writes(true)
+            .check_write(&process, Address::new(0x2000), 4)
+            .is_ok());
+        assert!(policy
+            .with_write_protection(false)
+            .check_write(&process, Address::new(0x2000), 4)
+            .is_ok());
+    }
+}
@@ -1,17 +1,18 @@
 //! Basic memory reading operations without safety checks
 
 use crate::core::types::{Address, MemoryError, MemoryResult};
+use crate::memory::backend::MemoryBackend;
 use crate::process::ProcessHandle;
 use std::mem;
 
 /// Basic memory reader for raw memory operations
-pub struct BasicMemoryReader<'a> {
-    handle: &'a ProcessHandle,
+pub struct BasicMemoryReader<'a, B: MemoryBackend = ProcessHandle> {
+    handle: &'a B,
 }
 
-impl<'a> BasicMemoryReader<'a> {
+impl<'a, B: MemoryBackend> BasicMemoryReader<'a, B> {
     /// Create a new basic memory reader
-    pub fn new(handle: &'a ProcessHandle) -> Self {
+    pub fn new(handle: &'a B) -> Self {
         BasicMemoryReader { handle }
     }
 
@@ -25,7 +26,7 @@ impl<'a> BasicMemoryReader<'a> {
             )));
         }
         let mut buffer = vec![0u8; size];
-        self.handle.read_memory(address.as_usize(), &mut buffer)?;
+        self.handle.read(address, &mut buffer)?;
         Ok(buffer)
     }
 
@@ -37,7 +38,7 @@ impl<'a> BasicMemoryReader<'a> {
         let size = mem::size_of::<T>();
         let mut buffer = vec![0u8; size];
 
-        self.handle.read_memory(address.as_usize(), &mut buffer)?;
+        self.handle.read(address, &mut buffer)?;
 
         // Safety: We're reading exactly size_of::<T>() bytes
         unsafe {
@@ -55,7 +56,7 @@ impl<'a> BasicMemoryReader<'a> {
         let total_size = element_size * count;
         let mut buffer = vec![0u8; total_size];
 
-        self.handle.read_memory(address.as_usize(), &mut buffer)?;
+        self.handle.read(address, &mut buffer)?;
 
         let mut result = Vec::with_capacity(count);
         for i in 0..count {
@@ -1,8 +1,9 @@
 //! Type-safe memory reading with caching support
 
 use crate::core::types::{Address, MemoryError, MemoryResult, MemoryValue};
+use crate::memory::backend::MemoryBackend;
+use crate::memory::policy::MemoryPolicy;
 use crate::process::ProcessHandle;
-use crate::windows::bindings::kernel32;
 use std::collections::HashMap;
 use std::mem;
 
@@ -82,20 +83,38 @@ impl ReadCache {
 }
 
 /// Memory reader with type-safe operations
-pub struct MemoryReader<'a> {
-    handle: &'a ProcessHandle,
+pub struct MemoryReader<'a, B: MemoryBackend = ProcessHandle> {
+    handle: &'a B,
     cache: ReadCache,
+    policy: Option<MemoryPolicy>,
 }
 
-impl<'a> MemoryReader<'a> {
+impl<'a, B: MemoryBackend> MemoryReader<'a, B> {
     /// Create a new memory reader
-    pub fn new(handle: &'a ProcessHandle) -> Self {
+    pub fn new(handle: &'a B) -> Self {
         MemoryReader {
             handle,
             cache: ReadCache::new(100, 1000), // 100 entries, 1 second max age
+            policy: None,
         }
     }
 
+    /// Reject reads larger than the policy allows
+    pub fn with_policy(mut self, policy: MemoryPolicy) -> Self {
+        self.policy = Some(policy);
+        self
+    }
+
+    /// Read `size` bytes straight from the process, within the policy limit
+    fn read_raw(&self, address: Address, size: usize) -> MemoryResult<Vec<u8>> {
+        if let Some(policy) = &self.policy {
+            policy.check_read(address, size)?;
+        }
+        let mut buffer = vec![0u8; size];
+        self.handle.read(address, &mut buffer)?;
+        Ok(buffer)
+    }
+
     /// Read raw bytes from memory
     pub fn read_bytes(&mut self, address: Address, size: usize) -> MemoryResult<Vec<u8>> {
         // Check cache first
@@ -104,8 +123,7 @@ impl<'a> MemoryReader<'a> {
         }
 
         // Read from process
-        let mut buffer = vec![0u8; size];
-        self.handle.read_memory(address.as_usize(), &mut buffer)?;
+        let buffer = self.read_raw(address, size)?;
 
         // Store in cache
         self.cache.put(address, buffer.clone());
@@ -117,7 +135,7 @@ impl<'a> MemoryReader<'a> {
         let size = mem::size_of::<T>();
         let mut buffer = vec![0u8; size];
 
-        self.handle.read_memory(address.as_usize(), &mut buffer)?;
+        self.handle.read(address, &mut buffer)?;
 
         // Safety: We're reading exactly size_of::<T>() bytes
         unsafe { Ok(*(buffer.as_ptr() as *const T)) }
@@ -125,9 +143,7 @@ impl<'a> MemoryReader<'a> {
 
     /// Read a null-terminated string from memory
     pub fn read_string(&self, address: Address, max_len: usize) -> MemoryResult<String> {
-        let mut buffer = vec![0u8; max_len];
-
-        self.handle.read_memory(address.as_usize(), &mut buffer)?;
+        let buffer = self.read_raw(address, max_len)?;
 
         // Find null terminator
         let len = buffer.iter().position(|&b| b == 0).unwrap_or(max_len);
@@ -137,12 +153,8 @@ impl<'a> MemoryReader<'a> {
 
     /// Read a wide string (UTF-16) from memory
     pub fn read_wide_string(&self, address: Address, max_len: usize) -> MemoryResult<String> {
+        let byte_buffer = self.read_raw(address, max_len * 2)?;
         let mut buffer = vec![0u16; max_len];
-        let byte_size = max_len * 2;
-        let mut byte_buffer = vec![0u8; byte_size];
-
-        self.handle
-            .read_memory(address.as_usize(), &mut byte_buffer)?;
 
         // Convert bytes to u16 array
         for i in 0..max_len {
@@ -162,32 +174,45 @@ impl<'a> MemoryReader<'a> {
     }
 
     /// Read a MemoryValue from memory
+    ///
+    /// `Bytes` values need a length; use [`MemoryReader::read_value_len`].
     pub fn read_value(
         &self,
         address: Address,
         value_type: crate::core::types::ValueType,
     ) -> MemoryResult<MemoryValue> {
-        use crate::core::types::ValueType;
-
-        match value_type {
-            ValueType::U8 => Ok(MemoryValue::U8(self.read::<u8>(address)?)),
-            ValueType::U16 => Ok(MemoryValue::U16(self.read::<u16>(address)?)),
-            ValueType::U32 => Ok(MemoryValue::U32(self.read::<u32>(address)?)),
-            ValueType::U64 => Ok(MemoryValue::U64(self.read::<u64>(address)?)),
-            ValueType::I8 => Ok(MemoryValue::I8(self.read::<i8>(address)?)),
-            ValueType::I16 => Ok(MemoryValue::I16(self.read::<i16>(address)?)),
-            ValueType::I32 => Ok(MemoryValue::I32(self.read::<i32>(address)?)),
-            ValueType::I64 => Ok(MemoryValue::I64(self.read::<i64>(address)?)),
-            ValueType::F32 => Ok(MemoryValue::F32(self.read::<f32>(address)?)),
-            ValueType::F64 => Ok(MemoryValue::F64(self.read::<f64>(address)?)),
-            ValueType::String => Ok(MemoryValue::String(self.read_string(address, 256)?)),
-            ValueType::Bytes => {
-                // For bytes, read a default size
-                let mut buffer = vec![0u8; 256];
-                self.handle.read_memory(address.as_usize(), &mut buffer)?;
-                Ok(MemoryValue::Bytes(buffer))
-            }
+        self.read_value_len(address, value_type, None)
+    }
+
+    /// Read a MemoryValue; `len` sizes `Bytes` and caps strings, in characters
+    pub fn read_value_len(
+        &self,
+        address: Address,
+        value_type: crate::core::types::ValueType,
+        len: Option<usize>,
+    ) -> MemoryResult<MemoryValue> {
+        super::read_typed(
+            |address, size| self.read_raw(address, size),
+            self.policy.as_ref(),
+            address,
+            value_type,
+            len,
+            self.handle.architecture(),
+        )
+    }
+
+    /// Read a MemoryValue stored with `encoding`
+    pub fn read_encoded(
+        &self,
+        address: Address,
+        value_type: crate::core::types::ValueType,
+        encoding: &crate::core::types::ValueEncoding,
+    ) -> MemoryResult<MemoryValue> {
+        if encoding.is_identity() {
+            return self.read_value(address, value_type);
         }
+        let bytes = self.read_raw(address, super::encoded_size(value_type)?)?;
+        super::decode_encoded(&bytes, value_type, encoding)
     }
 
     /// Clear the read cache
@@ -8,19 +8,29 @@ pub use basic::BasicMemoryReader;
 pub use cache::{MemoryReader, ReadCache};
 pub use safe::SafeMemoryReader;
 
-use crate::core::types::{Address, MemoryResult, MemoryValue, ValueType};
+use crate::core::types::{
+    Address, MemoryError, MemoryResult, MemoryValue, ProcessArchitecture, ValueEncoding, ValueType,
+};
+use crate::memory::backend::MemoryBackend;
+use crate::memory::policy::MemoryPolicy;
 use crate::process::ProcessHandle;
 
+/// Characters read for a string value when no length is given
+pub const DEFAULT_MAX_STRING_LEN: usize = 1024;
+
+/// Strings are read a page at a time so one ending just before unreadable memory still reads
+const STRING_READ_PAGE: usize = 4096;
+
 /// Unified memory reader interface
-pub struct Reader<'a> {
-    handle: &'a ProcessHandle,
-    cached: MemoryReader<'a>,
-    safe: SafeMemoryReader<'a>,
+pub struct Reader<'a, B: MemoryBackend = ProcessHandle> {
+    handle: &'a B,
+    cached: MemoryReader<'a, B>,
+    safe: SafeMemoryReader<'a, B>,
 }
 
-impl<'a> Reader<'a> {
+impl<'a, B: MemoryBackend> Reader<'a, B> {
     /// Create a new reader
-    pub fn new(handle: &'a ProcessHandle) -> Self {
+    pub fn new(handle: &'a B) -> Self {
         Reader {
             handle,
             cached: MemoryReader::new(handle),
@@ -28,6 +38,13 @@ impl<'a> Reader<'a> {
         }
     }
 
+    /// Reject reads larger than the policy allows
+    pub fn with_policy(mut self, policy: MemoryPolicy) -> Self {
+        self.cached = self.cached.with_policy(policy.clone());
+        self.safe = self.safe.with_policy(policy);
+        self
+    }
+
     /// Read with caching
     pub fn read_cached<T>(&mut self, address: Address) -> MemoryResult<T>
     where
@@ -54,12 +71,141 @@ impl<'a> Reader<'a> {
         self.safe.read_value(address, value_type)
     }
 
+    /// Read a value by type; `len` sizes `Bytes` and caps strings, in characters
+    pub fn read_value_len(
+        &self,
+        address: Address,
+        value_type: ValueType,
+        len: Option<usize>,
+    ) -> MemoryResult<MemoryValue> {
+        self.safe.read_value_len(address, value_type, len)
+    }
+
+    /// Read a value stored with a non-default encoding
+    pub fn read_encoded(
+        &self,
+        address: Address,
+        value_type: ValueType,
+        encoding: &ValueEncoding,
+    ) -> MemoryResult<MemoryValue> {
+        self.safe.read_encoded(address, value_type, encoding)
+    }
+
     /// Clear cache
     pub fn clear_cache(&mut self) {
         self.cached.clear_cache();
     }
 }
 
+/// Read a value through `read_raw`, for readers' `read_value_len`
+///
+/// Fixed-size types read exactly their size in a process of `architecture`.
+/// `Bytes` reads exactly `len` bytes; strings read up to their terminator,
+/// stopping after `len` (default [`DEFAULT_MAX_STRING_LEN`]) characters.
+/// Strings are read in pieces, so their full length is checked against
+/// `policy` up front.
+pub(crate) fn read_typed(
+    read_raw: impl Fn(Address, usize) -> MemoryResult<Vec<u8>>,
+    policy: Option<&MemoryPolicy>,
+    address: Address,
+    value_type: ValueType,
+    len: Option<usize>,
+    architecture: ProcessArchitecture,
+) -> MemoryResult<MemoryValue> {
+    let max_chars = len.unwrap_or(DEFAULT_MAX_STRING_LEN);
+    match value_type {
+        ValueType::Bytes => {
+            let len = len.ok_or_else(|| {
+                MemoryError::InvalidValueType("Bytes values need a length to read".to_string())
+            })?;
+            Ok(MemoryValue::Bytes(read_raw(address, len)?))
+        }
+        ValueType::String => {
+            let bytes = read_terminated(&read_raw, policy, address, 1, max_chars)?;
+            String::from_utf8(bytes)
+                .map(MemoryValue::String)
+                .map_err(MemoryError::Utf8Error)
+        }
+        ValueType::WString => {
+            let bytes = read_terminated(&read_raw, policy, address, 2, max_chars)?;
+            MemoryValue::from_bytes(&bytes, ValueType::WString)
+                .ok_or_else(|| MemoryError::InvalidValueType("Invalid UTF-16 string".to_string()))
+        }
+        _ => {
+            let size = value_type.size_for(architecture).ok_or_else(|| {
+                MemoryError::InvalidValueType(format!("{:?} has no fixed size", value_type))
+            })?;
+            let bytes = read_raw(address, size)?;
+            MemoryValue::from_bytes_for(&bytes, value_type, architecture).ok_or_else(|| {
+                MemoryError::InvalidValueType(format!("Invalid {:?} value", value_type))
+            })
+        }
+    }
+}
+
+/// Read `unit`-byte characters up to a zero character or `max_chars`
+fn read_terminated(
+    read_raw: &impl Fn(Address, usize) -> MemoryResult<Vec<u8>>,
+    policy: Option<&MemoryPolicy>,
+    address: Address,
+    unit: usize,
+    max_chars: usize,
+) -> MemoryResult<Vec<u8>> {
+    let limit = max_chars.saturating_mul(unit);
+    if let Some(policy) = policy {
+        policy.check_read(address, limit)?;
+    }
+    let mut data = Vec::new();
+
+    while data.len() < limit {
+        let at = address.as_usize().checked_add(data.len()).ok_or_else(|| {
+            MemoryError::InvalidAddress("String overflows the address space".to_string())
+        })?;
+        let page_left = STRING_READ_PAGE - at % STRING_READ_PAGE;
+        data.extend(read_raw(
+            Address::new(at),
+            page_left.min(limit - data.len()),
+        )?);
+
+        if let Some(end) = data
+            .chunks_exact(unit)
+            .position(|c| c.iter().all(|&b| b == 0))
+        {
+            data.truncate(end * unit);
+            return Ok(data);
+        }
+    }
+
+    Ok(data)
+}
+
+/// Decode a value read as raw bytes, for readers' `read_encoded`
+pub(crate) fn decode_encoded(
+    bytes: &[u8],
+    value_type: ValueType,
+    encoding: &ValueEncoding,
+) -> MemoryResult<MemoryValue> {
+    encoding.decode(bytes, value_type).ok_or_else(|| {
+        MemoryError::InvalidValueType(format!(
+            "{:?} values cannot be read with encoding {:?}",
+            value_type, encoding
+        ))
+    })
+}
+
+/// Bytes to read for an encoded value of `value_type`
+pub(crate) fn encoded_size(value_type: ValueType) -> MemoryResult<usize> {
+    value_type
+        .size()
+        .filter(|_| value_type.is_numeric())
+        .ok_or_else(|| {
+            MemoryError::InvalidValueType(format!(
+                "Encodings only apply to numeric values, got {:?}",
+                value_type
+            ))
+        })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -76,4 +222,41 @@ mod tests {
         reader.clear_cache();
         assert_eq!(reader.cached.cache_size(), 0);
     }
+
+    #[test]
+    fn test_string_length_checked_against_policy() {
+        let reads = std::cell::Cell::new(0);
+        let read_raw = |_: Address, size: usize| {
+            reads.set(reads.get() + 1);
+            Ok(vec![b'a'; size])
+        };
+        let policy = MemoryPolicy::default().with_max_read_size(64);
+        let address = Address::new(0x10000);
+
+        for (value_type, len) in [(ValueType::String, 65), (ValueType::WString, 33)] {
+            assert!(matches!(
+                read_typed(
+                    read_raw,
+                    Some(&policy),
+                    address,
+                    value_type,
+                    Some(len),
+                    ProcessArchitecture::Unknown
+                ),
+                Err(MemoryError::ReadLimitExceeded { .. })
+            ));
+        }
+        assert_eq!(reads.get(), 0);
+
+        let value = read_typed(
+            read_raw,
+            Some(&policy),
+            address,
+            ValueType::String,
+            Some(64),
+            ProcessArchitecture::Unknown,
+        )
+        .unwrap();
+        assert_eq!(value, MemoryValue::String("a".repeat(64)));
+    }
 }
@@ -1,62 +1,76 @@
 //! Safe memory reading with validation and error handling
 
-use crate::core::types::{Address, MemoryError, MemoryResult, MemoryValue, ValueType};
+use crate::core::types::{
+    Address, MemoryError, MemoryResult, MemoryValue, ValueEncoding, ValueType,
+};
+use crate::memory::backend::MemoryBackend;
+use crate::memory::policy::MemoryPolicy;
 use crate::memory::reader::basic::BasicMemoryReader;
+use crate::memory::regions::RegionState;
 use crate::process::ProcessHandle;
-use crate::windows::bindings::kernel32;
 
 /// Safe memory reader with validation
-pub struct SafeMemoryReader<'a> {
-    handle: &'a ProcessHandle,
-    basic_reader: BasicMemoryReader<'a>,
+pub struct SafeMemoryReader<'a, B: MemoryBackend = ProcessHandle> {
+    handle: &'a B,
+    basic_reader: BasicMemoryReader<'a, B>,
+    policy: Option<MemoryPolicy>,
 }
 
-impl<'a> SafeMemoryReader<'a> {
+impl<'a, B: MemoryBackend> SafeMemoryReader<'a, B> {
     /// Create a new safe memory reader
-    pub fn new(handle: &'a ProcessHandle) -> Self {
+    pub fn new(handle: &'a B) -> Self {
         SafeMemoryReader {
             handle,
             basic_reader: BasicMemoryReader::new(handle),
+            policy: None,
+        }
+    }
+
+    /// Reject reads larger than the policy allows
+    pub fn with_policy(mut self, policy: MemoryPolicy) -> Self {
+        self.policy = Some(policy);
+        self
+    }
+
+    fn check_read(&self, address: Address, size: usize) -> MemoryResult<()> {
+        match &self.policy {
+            Some(policy) => policy.check_read(address, size),
+            None => Ok(()),
         }
     }
 
     /// Validate memory region before reading
     pub fn validate_region(&self, address: Address, size: usize) -> MemoryResult<()> {
-        unsafe {
-            let mbi = kernel32::virtual_query_ex(self.handle.raw(), address.as_usize())?;
-
-            // Check if memory is committed
-            const MEM_COMMIT: u32 = 0x1000;
-            if mbi.State != MEM_COMMIT {
-                return Err(MemoryError::InvalidAddress(format!(
-                    "Memory at 0x{:X} is not committed",
-                    address.as_usize()
-                )));
-            }
-
-            // Check if region is large enough
-            if mbi.RegionSize < size {
-                return Err(MemoryError::InvalidAddress(format!(
-                    "Memory region at 0x{:X} is too small (requested: {}, available: {})",
-                    address.as_usize(),
-                    size,
-                    mbi.RegionSize
-                )));
-            }
-
-            // Check read permissions
-            const PAGE_NOACCESS: u32 = 0x01;
-            const PAGE_GUARD: u32 = 0x100;
-            if mbi.Protect & PAGE_NOACCESS != 0 || mbi.Protect & PAGE_GUARD != 0 {
-                return Err(MemoryError::InvalidAddress(format!(
-                    "Memory at 0x{:X} is not readable (protection: 0x{:X})",
-                    address.as_usize(),
-                    mbi.Protect
-                )));
-            }
-
-            Ok(())
+        let region = self.handle.query_region(address)?;
+
+        // Check if memory is committed
+        if region.state != RegionState::Committed {
+            return Err(MemoryError::InvalidAddress(format!(
+                "Memory at 0x{:X} is not committed",
+                address.as_usize()
+            )));
+        }
+
+        // Check if region is large enough
+        if region.size < size {
+            return Err(MemoryError::InvalidAddress(format!(
+                "Memory region at 0x{:X} is too small (requested: {}, available: {})",
+                address.as_usize(),
+                size,
+                region.size
+            )));
         }
+
+        // Check read permissions
+        if !region.is_readable() {
+            return Err(MemoryError::InvalidAddress(format!(
+                "Memory at 0x{:X} is not readable (protection: 0x{:X})",
+                address.as_usize(),
+                region.protection
+            )));
+        }
+
+        Ok(())
     }
 
     /// Read with validation
@@ -70,6 +84,7 @@ impl<'a> SafeMemoryReader<'a> {
 
     /// Read raw bytes with validation
     pub fn read_raw(&self, address: Address, size: usize) -> MemoryResult<Vec<u8>> {
+        self.check_read(address, size)?;
         self.validate_region(address, size)?;
         self.basic_reader.read_raw(address, size)
     }
@@ -79,13 +94,15 @@ impl<'a> SafeMemoryReader<'a> {
     where
         T: Copy + Default,
     {
-        let total_size = std::mem::size_of::<T>() * count;
+        let total_size = std::mem::size_of::<T>().saturating_mul(count);
+        self.check_read(address, total_size)?;
         self.validate_region(address, total_size)?;
         self.basic_reader.read_array(address, count)
     }
 
     /// Read string with validation
     pub fn read_string(&self, address: Address, max_len: usize) -> MemoryResult<String> {
+        self.check_read(address, max_len)?;
         // Validate at least first byte
         self.validate_region(address, 1)?;
         self.basic_reader.read_string(address, max_len)
@@ -93,30 +110,48 @@ impl<'a> SafeMemoryReader<'a> {
 
     /// Read wide string with validation
     pub fn read_wide_string(&self, address: Address, max_len: usize) -> MemoryResult<String> {
+        self.check_read(address, max_len.saturating_mul(2))?;
         // Validate at least first 2 bytes
         self.validate_region(address, 2)?;
         self.basic_reader.read_wide_string(address, max_len)
     }
 
     /// Read a MemoryValue with type information
+    ///
+    /// `Bytes` values need a length; use [`SafeMemoryReader::read_value_len`].
     pub fn read_value(&self, address: Address, value_type: ValueType) -> MemoryResult<MemoryValue> {
-        match value_type {
-            ValueType::U8 => Ok(MemoryValue::U8(self.read::<u8>(address)?)),
-            ValueType::U16 => Ok(MemoryValue::U16(self.read::<u16>(address)?)),
-            ValueType::U32 => Ok(MemoryValue::U32(self.read::<u32>(address)?)),
-            ValueType::U64 => Ok(MemoryValue::U64(self.read::<u64>(address)?)),
-            ValueType::I8 => Ok(MemoryValue::I8(self.read::<i8>(address)?)),
-            ValueType::I16 => Ok(MemoryValue::I16(self.read::<i16>(address)?)),
-            ValueType::I32 => Ok(MemoryValue::I32(self.read::<i32>(address)?)),
-            ValueType::I64 => Ok(MemoryValue::I64(self.read::<i64>(address)?)),
-            ValueType::F32 => Ok(MemoryValue::F32(self.read::<f32>(address)?)),
-            ValueType::F64 => Ok(MemoryValue::F64(self.read::<f64>(address)?)),
-            ValueType::String => Ok(MemoryValue::String(self.read_string(address, 256)?)),
-            ValueType::Bytes => {
-                let buffer = self.read_raw(address, 256)?;
-                Ok(MemoryValue::Bytes(buffer))
-            }
+        self.read_value_len(address, value_type, None)
+    }
+
+    /// Read a MemoryValue; `len` sizes `Bytes` and caps strings, in characters
+    pub fn read_value_len(
+        &self,
+        address: Address,
+        value_type: ValueType,
+        len: Option<usize>,
+    ) -> MemoryResult<MemoryValue> {
+        super::read_typed(
+            |address, size| self.read_raw(address, size),
+            self.policy.as_ref(),
+            address,
+            value_type,
+            len,
+            self.handle.architecture(),
+        )
+    }
+
+    /// Read a MemoryValue stored with `encoding`
+    pub fn read_encoded(
+        &self,
+        address: Address,
+        value_type: ValueType,
+        encoding: &ValueEncoding,
+    ) -> MemoryResult<MemoryValue> {
+        if encoding.is_identity() {
+            return self.read_value(address, value_type);
         }
+        let bytes = self.read_raw(address, super::encoded_size(value_type)?)?;
+        super::decode_encoded(&bytes, value_type, encoding)
     }
 
     /// Batch read with validation
@@ -1,10 +1,9 @@
 //! Memory region enumeration functionality
 
-use crate::core::types::{Address, MemoryError, MemoryResult};
+use crate::core::types::{Address, MemoryResult};
+use crate::memory::backend::MemoryBackend;
 use crate::memory::regions::{RegionState, RegionType};
 use crate::process::ProcessHandle;
-use crate::windows::bindings::kernel32;
-use winapi::um::winnt::MEMORY_BASIC_INFORMATION;
 
 /// Information about a memory region
 #[derive(Debug, Clone)]
@@ -76,17 +75,17 @@ impl RegionInfo {
 }
 
 /// Enumerates memory regions for a process
-pub struct RegionEnumerator {
-    handle: ProcessHandle,
+pub struct RegionEnumerator<B: MemoryBackend = ProcessHandle> {
+    backend: B,
     current_address: Address,
     max_address: Address,
 }
 
-impl RegionEnumerator {
+impl<B: MemoryBackend> RegionEnumerator<B> {
     /// Create a new region enumerator for a process
-    pub fn new(handle: ProcessHandle) -> Self {
+    pub fn new(backend: B) -> Self {
         RegionEnumerator {
-            handle,
+            backend,
             current_address: Address::new(0),
             max_address: Address::new(usize::MAX),
         }
@@ -105,14 +104,18 @@ impl RegionEnumerator {
     /// Get the next memory region
     pub fn next_region(&mut self) -> Option<RegionInfo> {
         while self.current_address < self.max_address {
-            match unsafe {
-                kernel32::virtual_query_ex(self.handle.raw(), self.current_address.as_usize())
-            } {
-                Ok(mbi) => {
-                    let region = self.parse_memory_info(&mbi);
-
-                    // Move to next region
-                    self.current_address = Address::new(mbi.BaseAddress as usize + mbi.RegionSize);
+            match self.backend.query_region(self.current_address) {
+                Ok(region) => {
+                    // Move to next region, stopping at the top of the address space
+                    self.current_address = match region
+                        .base_address
+                        .as_usize()
+                        .checked_add(region.size)
+                        .filter(|&next| next > self.current_address.as_usize())
+                    {
+                        Some(next) => Address::new(next),
+                        None => self.max_address,
+                    };
 
                     return Some(region);
                 }
@@ -120,7 +123,7 @@ impl RegionEnumerator {
                     // Error querying memory, try next page
                     const PAGE_SIZE: usize = 4096;
                     self.current_address =
-                        Address::new(self.current_address.as_usize() + PAGE_SIZE);
+                        Address::new(self.current_address.as_usize().saturating_add(PAGE_SIZE));
 
                     // Stop if we've gone too far
                     if self.current_address >= self.max_address {
@@ -132,43 +135,9 @@ impl RegionEnumerator {
 
         None
     }
-
-    /// Parse MEMORY_BASIC_INFORMATION into RegionInfo
-    fn parse_memory_info(&self, mbi: &MEMORY_BASIC_INFORMATION) -> RegionInfo {
-        const MEM_COMMIT: u32 = 0x1000;
-        const MEM_RESERVE: u32 = 0x2000;
-        const MEM_FREE: u32 = 0x10000;
-        const MEM_PRIVATE: u32 = 0x20000;
-        const MEM_MAPPED: u32 = 0x40000;
-        const MEM_IMAGE: u32 = 0x1000000;
-
-        let state = match mbi.State {
-            MEM_COMMIT => RegionState::Committed,
-            MEM_RESERVE => RegionState::Reserved,
-            MEM_FREE => RegionState::Free,
-            _ => RegionState::Free,
-        };
-
-        let region_type = match mbi.Type {
-            MEM_PRIVATE => RegionType::Private,
-            MEM_MAPPED => RegionType::Mapped,
-            MEM_IMAGE => RegionType::Image,
-            _ => RegionType::Private,
-        };
-
-        RegionInfo {
-            base_address: Address::new(mbi.BaseAddress as usize),
-            size: mbi.RegionSize,
-            state,
-            region_type,
-            protection: mbi.Protect,
-            allocation_protection: mbi.AllocationProtect,
-            allocation_base: Address::new(mbi.AllocationBase as usize),
-        }
-    }
 }
 
-impl Iterator for RegionEnumerator {
+impl<B: MemoryBackend> Iterator for RegionEnumerator<B> {
     type Item = RegionInfo;
 
     fn next(&mut self) -> Option<Self::Item> {
@@ -179,21 +148,11 @@ impl Iterator for RegionEnumerator {
 /// Enumerate all memory regions for the current process
 pub fn enumerate_regions() -> MemoryResult<Vec<RegionInfo>> {
     let handle = ProcessHandle::open_for_read(std::process::id())?;
-    let enumerator = RegionEnumerator::new(handle);
-    let mut regions = Vec::new();
+    let regions = handle.enumerate_regions()?;
 
     // In test mode, limit enumeration to prevent CI timeouts
     #[cfg(test)]
-    let max_regions = 100;
-    #[cfg(not(test))]
-    let max_regions = usize::MAX;
-
-    for (i, region) in enumerator.enumerate() {
-        if i >= max_regions {
-            break;
-        }
-        regions.push(region);
-    }
+    let regions = regions.into_iter().take(100).collect();
 
     Ok(regions)
 }
@@ -201,17 +160,7 @@ pub fn enumerate_regions() -> MemoryResult<Vec<RegionInfo>> {
 /// Query information about a specific memory region
 pub fn query_region_at(address: Address) -> MemoryResult<RegionInfo> {
     let handle = ProcessHandle::open_for_read(std::process::id())?;
-
-    match unsafe { kernel32::virtual_query_ex(handle.raw(), address.as_usize()) } {
-        Ok(mbi) => {
-            let enumerator = RegionEnumerator::new(handle);
-            Ok(enumerator.parse_memory_info(&mbi))
-        }
-        Err(e) => Err(MemoryError::WindowsApi(format!(
-            "Failed to query region: {}",
-            e
-        ))),
-    }
+    handle.query_region(address)
 }
 
 #[cfg(test)]
@@ -1,18 +1,23 @@
-//! Memory region management for Windows processes
+//! Memory region management for process address spaces
 //!
 //! This module provides functionality for enumerating, filtering, and managing
-//! memory regions within a Windows process. It supports querying region properties,
-//! modifying protection flags, and mapping memory regions.
+//! memory regions within a process. It supports querying region properties,
+//! modifying protection flags, and mapping memory regions (the latter two on
+//! Windows only).
 
 pub mod enumerator;
 pub mod filter;
+#[cfg(windows)]
 pub mod mapper;
 pub mod protection;
 
 pub use enumerator::{enumerate_regions, query_region_at, RegionEnumerator, RegionInfo};
 pub use filter::{FilterCriteria, RegionFilter};
+#[cfg(windows)]
 pub use mapper::{MappedRegion, MappingOptions, MemoryMapper};
-pub use protection::{change_protection, ProtectionFlags, ProtectionManager};
+pub use protection::ProtectionFlags;
+#[cfg(windows)]
+pub use protection::{change_protection, ProtectionManager};
 
 use crate::core::types::{Address, MemoryResult};
 
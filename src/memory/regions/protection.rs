@@ -2,7 +2,9 @@
 
 use crate::core::types::{Address, MemoryError, MemoryResult};
 use crate::process::ProcessHandle;
+#[cfg(windows)]
 use winapi::shared::minwindef::{DWORD, FALSE};
+#[cfg(windows)]
 use winapi::um::memoryapi::VirtualProtectEx;
 
 /// Memory protection flags
@@ -165,10 +167,12 @@ pub struct ProtectionChange {
 }
 
 /// Manages memory protection for a process
+#[cfg(windows)]
 pub struct ProtectionManager {
     handle: ProcessHandle,
 }
 
+#[cfg(windows)]
 impl ProtectionManager {
     /// Create a new protection manager
     pub fn new(handle: ProcessHandle) -> Self {
@@ -302,6 +306,7 @@ impl ProtectionManager {
 }
 
 /// Change protection for a memory region in the current process
+#[cfg(windows)]
 pub fn change_protection(
     address: Address,
     size: usize,
@@ -345,6 +350,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(windows)]
     #[cfg_attr(miri, ignore = "FFI not supported in Miri")]
     fn test_change_protection() {
         use std::ptr;
@@ -382,6 +388,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(windows)]
     #[cfg_attr(miri, ignore = "FFI not supported in Miri")]
     fn test_unprotect_for_operation() {
         use std::ptr;
@@ -416,6 +423,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(windows)]
     #[cfg_attr(miri, ignore = "FFI not supported in Miri")]
     fn test_make_executable() {
         use std::ptr;
@@ -450,6 +458,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(windows)]
     #[cfg_attr(miri, ignore = "FFI not supported in Miri")]
     fn test_make_non_executable() {
         use std::ptr;
@@ -510,6 +519,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(windows)]
     #[cfg_attr(miri, ignore = "FFI not supported in Miri")]
     fn test_protection_change_invalid_size() {
         let handle = ProcessHandle::open_for_read_write(std::process::id()).unwrap();
@@ -0,0 +1,82 @@
+//! Fixed-size bit set tracking surviving scan candidates
+
+/// One bit per candidate slot
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Bitmap {
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl Bitmap {
+    /// Create a bitmap of `len` bits, all cleared
+    pub fn new(len: usize) -> Self {
+        Bitmap {
+            words: vec![0; (len + 63) / 64],
+            len,
+        }
+    }
+
+    /// Number of bits
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Bytes used by the bit storage
+    pub fn memory_usage(&self) -> usize {
+        self.words.len() * 8
+    }
+
+    /// Whether the bit at `index` is set
+    pub fn get(&self, index: usize) -> bool {
+        self.words[index / 64] & (1 << (index % 64)) != 0
+    }
+
+    pub fn set(&mut self, index: usize) {
+        self.words[index / 64] |= 1 << (index % 64);
+    }
+
+    pub fn clear(&mut self, index: usize) {
+        self.words[index / 64] &= !(1 << (index % 64));
+    }
+
+    /// Number of set bits
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    /// Indices of set bits in ascending order
+    pub fn iter_ones(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(i, &word)| {
+            let mut remaining = word;
+            std::iter::from_fn(move || {
+                if remaining == 0 {
+                    return None;
+                }
+                let bit = remaining.trailing_zeros() as usize;
+                remaining &= remaining - 1;
+                Some(i * 64 + bit)
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bitmap_set_clear_iterate() {
+        let mut bitmap = Bitmap::new(130);
+        assert_eq!(bitmap.len(), 130);
+        assert_eq!(bitmap.memory_usage(), 24);
+
+        for index in [0, 63, 64, 129] {
+            bitmap.set(index);
+        }
+        bitmap.clear(63);
+
+        assert_eq!(bitmap.count_ones(), 3);
+        assert!(bitmap.get(64) && !bitmap.get(63));
+        assert_eq!(bitmap.iter_ones().collect::<Vec<_>>(), vec![0, 64, 129]);
+    }
+}
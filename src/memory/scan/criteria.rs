@@ -0,0 +1,510 @@
+//! Scan criteria and numeric comparison semantics
+//!
+//! Values are compared as numbers, never as raw bytes: integers are widened
+//! to `i128` so every signed and unsigned width orders correctly, and floats
+//! are compared with a tolerance scaled to the magnitude of the operands.
+
+use crate::core::types::{MemoryError, MemoryResult, MemoryValue, ScanType, ValueType};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+/// What a first or next scan should keep
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScanCriteria {
+    /// Comparison to perform
+    pub scan_type: ScanType,
+    /// Target value, delta for `IncreasedBy`/`DecreasedBy`, lower bound for
+    /// `Between` and `ChangedByPercent`
+    #[serde(default)]
+    pub value: Option<MemoryValue>,
+    /// Upper bound for `Between` and `ChangedByPercent` (inclusive)
+    #[serde(default)]
+    pub upper: Option<MemoryValue>,
+    /// Absolute tolerance for comparisons; floats default to a few ULPs and
+    /// integers to exact equality
+    #[serde(default)]
+    pub epsilon: Option<f64>,
+}
+
+impl ScanCriteria {
+    /// Create criteria for a scan type without a value
+    pub fn new(scan_type: ScanType) -> Self {
+        ScanCriteria {
+            scan_type,
+            value: None,
+            upper: None,
+            epsilon: None,
+        }
+    }
+
+    /// Create criteria for a scan type with a value
+    pub fn with_value(scan_type: ScanType, value: MemoryValue) -> Self {
+        ScanCriteria {
+            value: Some(value),
+            ..ScanCriteria::new(scan_type)
+        }
+    }
+
+    /// Create `Between` criteria with inclusive bounds
+    pub fn between(lower: MemoryValue, upper: MemoryValue) -> Self {
+        ScanCriteria {
+            upper: Some(upper),
+            ..ScanCriteria::with_value(ScanType::Between, lower)
+        }
+    }
+
+    /// Set the float tolerance
+    pub fn epsilon(mut self, epsilon: f64) -> Self {
+        self.epsilon = Some(epsilon);
+        self
+    }
+
+    /// Check that the criteria can be evaluated for `value_type`
+    ///
+    /// `has_previous` is false for a first scan, where comparisons against
+    /// the previous value are impossible.
+    pub fn validate(&self, value_type: ValueType, has_previous: bool) -> MemoryResult<()> {
+        if self.scan_type.requires_previous() && !has_previous {
+            return Err(MemoryError::UnsupportedOperation(format!(
+                "{:?} scan requires a previous scan",
+                self.scan_type
+            )));
+        }
+
+        if self.scan_type.requires_value() && self.value.is_none() {
+            return Err(MemoryError::InvalidValueType(format!(
+                "{:?} scan requires a value",
+                self.scan_type
+            )));
+        }
+
+        let bounded = matches!(
+            self.scan_type,
+            ScanType::Between | ScanType::ChangedByPercent
+        );
+        if bounded && self.upper.is_none() {
+            return Err(MemoryError::InvalidValueType(format!(
+                "{:?} scan requires an upper bound",
+                self.scan_type
+            )));
+        }
+
+        if self.epsilon.is_some_and(|e| e.is_nan() || e < 0.0) {
+            return Err(MemoryError::InvalidValueType(
+                "Tolerance must be a non-negative number".to_string(),
+            ));
+        }
+
+        let numeric = ordered(value_type);
+        let operands = [self.value.as_ref(), self.upper.as_ref()];
+
+        if numeric {
+            if let Some(operand) = operands.into_iter().flatten().find(|v| number(v).is_none()) {
+                return Err(MemoryError::InvalidValueType(format!(
+                    "{} is not a numeric value for a {:?} scan",
+                    operand, value_type
+                )));
+            }
+        } else if !matches!(
+            self.scan_type,
+            ScanType::Exact | ScanType::Unknown | ScanType::Changed | ScanType::Unchanged
+        ) {
+            return Err(MemoryError::InvalidValueType(format!(
+                "{:?} scan is not supported for {:?} values",
+                self.scan_type, value_type
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Evaluate the criteria for a value read from memory
+    ///
+    /// Assumes [`ScanCriteria::validate`] succeeded; anything it would have
+    /// rejected evaluates to `false`.
+    pub fn matches(
+        &self,
+        value_type: ValueType,
+        current: &MemoryValue,
+        previous: Option<&MemoryValue>,
+    ) -> bool {
+        if !ordered(value_type) {
+            return self.matches_bytes(current, previous);
+        }
+
+        let Some(now) = number(current) else {
+            return false;
+        };
+        let compare = |a: Number, b: Number| compare(value_type, a, b, self.epsilon);
+        let target = self.value.as_ref().and_then(number);
+        let old = previous.and_then(number);
+
+        match self.scan_type {
+            ScanType::Unknown => true,
+            ScanType::Exact => target.is_some_and(|t| compare(now, t) == Some(Ordering::Equal)),
+            ScanType::BiggerThan => {
+                target.is_some_and(|t| compare(now, t) == Some(Ordering::Greater))
+            }
+            ScanType::SmallerThan => {
+                target.is_some_and(|t| compare(now, t) == Some(Ordering::Less))
+            }
+            ScanType::Between => {
+                let upper = self.upper.as_ref().and_then(number);
+                match (target, upper) {
+                    (Some(low), Some(high)) => {
+                        matches!(compare(now, low), Some(Ordering::Greater | Ordering::Equal))
+                            && matches!(compare(now, high), Some(Ordering::Less | Ordering::Equal))
+                    }
+                    _ => false,
+                }
+            }
+            ScanType::Increased => old.is_some_and(|o| compare(now, o) == Some(Ordering::Greater)),
+            ScanType::Decreased => old.is_some_and(|o| compare(now, o) == Some(Ordering::Less)),
+            ScanType::Changed => old.is_some_and(|o| !same(value_type, now, o, self.epsilon)),
+            ScanType::Unchanged => old.is_some_and(|o| same(value_type, now, o, self.epsilon)),
+            ScanType::IncreasedBy => match (old, target) {
+                (Some(o), Some(delta)) => compare(now.sub(o), delta) == Some(Ordering::Equal),
+                _ => false,
+            },
+            ScanType::DecreasedBy => match (old, target) {
+                (Some(o), Some(delta)) => compare(o.sub(now), delta) == Some(Ordering::Equal),
+                _ => false,
+            },
+            ScanType::ChangedByPercent => {
+                let upper = self.upper.as_ref().and_then(number);
+                match (old, target, upper) {
+                    (Some(o), Some(low), Some(high)) => percent_change(now, o)
+                        .is_some_and(|p| p >= low.as_f64() && p <= high.as_f64()),
+                    _ => false,
+                }
+            }
+        }
+    }
+
+    fn matches_bytes(&self, current: &MemoryValue, previous: Option<&MemoryValue>) -> bool {
+        let bytes = current.to_bytes();
+        match self.scan_type {
+            ScanType::Unknown => true,
+            ScanType::Exact => self.value.as_ref().is_some_and(|v| v.to_bytes() == bytes),
+            ScanType::Changed => previous.is_some_and(|p| p.to_bytes() != bytes),
+            ScanType::Unchanged => previous.is_some_and(|p| p.to_bytes() == bytes),
+            _ => false,
+        }
+    }
+}
+
+/// A numeric value widened for comparison
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Number {
+    Int(i128),
+    Float(f64),
+}
+
+impl Number {
+    fn as_f64(self) -> f64 {
+        match self {
+            Number::Int(v) => v as f64,
+            Number::Float(v) => v,
+        }
+    }
+
+    fn sub(self, other: Number) -> Number {
+        match (self, other) {
+            (Number::Int(a), Number::Int(b)) => a
+                .checked_sub(b)
+                .map_or(Number::Float(a as f64 - b as f64), Number::Int),
+            (a, b) => Number::Float(a.as_f64() - b.as_f64()),
+        }
+    }
+}
+
+/// Whether values of `value_type` compare as numbers rather than as bytes
+fn ordered(value_type: ValueType) -> bool {
+    value_type.is_numeric() || value_type == ValueType::Pointer
+}
+
+fn number(value: &MemoryValue) -> Option<Number> {
+    Some(match *value {
+        MemoryValue::I8(v) => Number::Int(v as i128),
+        MemoryValue::I16(v) => Number::Int(v as i128),
+        MemoryValue::I32(v) => Number::Int(v as i128),
+        MemoryValue::I64(v) => Number::Int(v as i128),
+        MemoryValue::U8(v) => Number::Int(v as i128),
+        MemoryValue::U16(v) => Number::Int(v as i128),
+        MemoryValue::U32(v) => Number::Int(v as i128),
+        MemoryValue::U64(v) => Number::Int(v as i128),
+        MemoryValue::I128(v) => Number::Int(v),
+        // Only values above i128::MAX lose precision
+        MemoryValue::U128(v) => i128::try_from(v).map_or(Number::Float(v as f64), Number::Int),
+        MemoryValue::Pointer(a) => Number::Int(a.as_usize() as i128),
+        MemoryValue::F32(v) => Number::Float(v as f64),
+        MemoryValue::F64(v) => Number::Float(v),
+        _ => return None,
+    })
+}
+
+/// Change from `old` to `now` as a percentage of `old`; `None` if `old` is zero
+fn percent_change(now: Number, old: Number) -> Option<f64> {
+    let (now, old) = (now.as_f64(), old.as_f64());
+    let percent = (now - old) / old.abs() * 100.0;
+    percent.is_finite().then_some(percent)
+}
+
+/// Tolerance for comparing `a` and `b` when memory holds `value_type`
+fn tolerance(value_type: ValueType, a: f64, b: f64, epsilon: Option<f64>) -> f64 {
+    let machine = match value_type {
+        ValueType::F32 => f32::EPSILON as f64,
+        ValueType::F64 => f64::EPSILON,
+        _ => return 0.0,
+    };
+
+    // A few ULPs relative to the larger operand, but at least that much absolutely
+    epsilon.unwrap_or(machine * 4.0 * a.abs().max(b.abs()).max(1.0))
+}
+
+/// Order `a` relative to `b`; `None` if either is NaN
+fn compare(value_type: ValueType, a: Number, b: Number, epsilon: Option<f64>) -> Option<Ordering> {
+    if let (Number::Int(a), Number::Int(b)) = (a, b) {
+        if epsilon.is_some_and(|e| a.abs_diff(b) as f64 <= e) {
+            return Some(Ordering::Equal);
+        }
+        return Some(a.cmp(&b));
+    }
+
+    let (a, b) = (a.as_f64(), b.as_f64());
+    if (a - b).abs() <= tolerance(value_type, a, b, epsilon) {
+        return Some(Ordering::Equal);
+    }
+    a.partial_cmp(&b)
+}
+
+/// Whether two readings of the same address hold the same value
+fn same(value_type: ValueType, a: Number, b: Number, epsilon: Option<f64>) -> bool {
+    match (a, b) {
+        // A NaN that stays NaN has not changed
+        (Number::Float(x), Number::Float(y)) if x.is_nan() || y.is_nan() => {
+            x.is_nan() && y.is_nan()
+        }
+        _ => compare(value_type, a, b, epsilon) == Some(Ordering::Equal),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(criteria: &ScanCriteria, ty: ValueType, now: MemoryValue, old: MemoryValue) -> bool {
+        criteria.matches(ty, &now, Some(&old))
+    }
+
+    #[test]
+    fn test_signed_and_unsigned_ordering() {
+        let bigger = ScanCriteria::with_value(ScanType::BiggerThan, MemoryValue::I32(0));
+
+        // -1 is 0xFFFFFFFF in memory but must never compare as large
+        assert!(!bigger.matches(ValueType::I32, &MemoryValue::I32(-1), None));
+        assert!(bigger.matches(ValueType::U32, &MemoryValue::U32(u32::MAX), None));
+
+        // Little-endian 256 is [0x00, 0x01]; lexicographic byte order would say 256 < 1
+        let increased = ScanCriteria::new(ScanType::Increased);
+        assert!(check(
+            &increased,
+            ValueType::U16,
+            MemoryValue::U16(256),
+            MemoryValue::U16(1)
+        ));
+        assert!(!check(
+            &increased,
+            ValueType::I8,
+            MemoryValue::I8(-5),
+            MemoryValue::I8(3)
+        ));
+    }
+
+    #[test]
+    fn test_exact_and_between() {
+        let exact = ScanCriteria::with_value(ScanType::Exact, MemoryValue::I32(100));
+        assert!(exact.matches(ValueType::U8, &MemoryValue::U8(100), None));
+        assert!(!exact.matches(ValueType::U8, &MemoryValue::U8(99), None));
+
+        let between = ScanCriteria::between(MemoryValue::I64(-10), MemoryValue::I64(10));
+        assert!(between.matches(ValueType::I16, &MemoryValue::I16(-10), None));
+        assert!(between.matches(ValueType::I16, &MemoryValue::I16(10), None));
+        assert!(!between.matches(ValueType::I16, &MemoryValue::I16(11), None));
+        assert!(!between.matches(ValueType::U64, &MemoryValue::U64(u64::MAX), None));
+    }
+
+    #[test]
+    fn test_deltas() {
+        let up = ScanCriteria::with_value(ScanType::IncreasedBy, MemoryValue::I32(5));
+        assert!(check(
+            &up,
+            ValueType::I32,
+            MemoryValue::I32(3),
+            MemoryValue::I32(-2)
+        ));
+        assert!(!check(
+            &up,
+            ValueType::I32,
+            MemoryValue::I32(4),
+            MemoryValue::I32(-2)
+        ));
+
+        let down = ScanCriteria::with_value(ScanType::DecreasedBy, MemoryValue::F32(0.1));
+        assert!(check(
+            &down,
+            ValueType::F32,
+            MemoryValue::F32(0.2),
+            MemoryValue::F32(0.3)
+        ));
+    }
+
+    #[test]
+    fn test_percent_change_and_integer_tolerance() {
+        let grew = ScanCriteria {
+            upper: Some(MemoryValue::F64(50.0)),
+            ..ScanCriteria::with_value(ScanType::ChangedByPercent, MemoryValue::F64(10.0))
+        };
+        let u32s = |now, old| {
+            check(
+                &grew,
+                ValueType::U32,
+                MemoryValue::U32(now),
+                MemoryValue::U32(old),
+            )
+        };
+        assert!(u32s(110, 100));
+        assert!(u32s(150, 100));
+        assert!(!u32s(151, 100));
+        assert!(!u32s(100, 100));
+        assert!(!u32s(5, 0));
+
+        // A drop of a quarter is -25%, whatever the sign of the old value
+        let quarter = ScanCriteria {
+            upper: Some(MemoryValue::I32(-20)),
+            ..ScanCriteria::with_value(ScanType::ChangedByPercent, MemoryValue::I32(-30))
+        };
+        let i16s = |now, old| {
+            check(
+                &quarter,
+                ValueType::I16,
+                MemoryValue::I16(now),
+                MemoryValue::I16(old),
+            )
+        };
+        assert!(i16s(45, 60));
+        assert!(i16s(-75, -60));
+        assert!(!i16s(30, 60));
+
+        let about =
+            ScanCriteria::with_value(ScanType::IncreasedBy, MemoryValue::I32(10)).epsilon(1.0);
+        assert!(check(
+            &about,
+            ValueType::I32,
+            MemoryValue::I32(19),
+            MemoryValue::I32(10)
+        ));
+        assert!(!check(
+            &about,
+            ValueType::I32,
+            MemoryValue::I32(22),
+            MemoryValue::I32(10)
+        ));
+    }
+
+    #[test]
+    fn test_float_epsilon() {
+        // 1.1f32 widened to f64 is not exactly 1.1
+        let exact = ScanCriteria::with_value(ScanType::Exact, MemoryValue::F64(1.1));
+        assert!(exact.matches(ValueType::F32, &MemoryValue::F32(1.1), None));
+        assert!(!exact.matches(ValueType::F32, &MemoryValue::F32(1.1001), None));
+
+        let loose = exact.clone().epsilon(0.01);
+        assert!(loose.matches(ValueType::F32, &MemoryValue::F32(1.105), None));
+
+        // Tiny float noise is not a change
+        let unchanged = ScanCriteria::new(ScanType::Unchanged);
+        let noisy = f64::from_bits(1.0f64.to_bits() + 1);
+        assert!(check(
+            &unchanged,
+            ValueType::F64,
+            MemoryValue::F64(noisy),
+            MemoryValue::F64(1.0)
+        ));
+
+        let nan = MemoryValue::F32(f32::NAN);
+        assert!(check(&unchanged, ValueType::F32, nan.clone(), nan.clone()));
+        assert!(!exact.matches(ValueType::F32, &nan, None));
+    }
+
+    #[test]
+    fn test_bytes_criteria() {
+        let exact = ScanCriteria::with_value(ScanType::Exact, MemoryValue::String("hp".into()));
+        assert!(exact.matches(ValueType::String, &MemoryValue::String("hp".into()), None));
+
+        let changed = ScanCriteria::new(ScanType::Changed);
+        assert!(check(
+            &changed,
+            ValueType::Bytes,
+            MemoryValue::Bytes(vec![1, 2]),
+            MemoryValue::Bytes(vec![1, 3])
+        ));
+    }
+
+    #[test]
+    fn test_validate() {
+        let increased = ScanCriteria::new(ScanType::Increased);
+        assert!(increased.validate(ValueType::I32, false).is_err());
+        assert!(increased.validate(ValueType::I32, true).is_ok());
+        assert!(increased.validate(ValueType::Bytes, true).is_err());
+
+        assert!(ScanCriteria::new(ScanType::Exact)
+            .validate(ValueType::I32, false)
+            .is_err());
+        assert!(
+            ScanCriteria::with_value(ScanType::Between, MemoryValue::I32(1))
+                .validate(ValueType::I32, false)
+                .is_err()
+        );
+        assert!(
+            ScanCriteria::with_value(ScanType::Exact, MemoryValue::String("1".into()))
+                .validate(ValueType::I32, false)
+                .is_err()
+        );
+        assert!(ScanCriteria::new(ScanType::Unknown)
+            .validate(ValueType::F64, false)
+            .is_ok());
+        assert!(
+            ScanCriteria::with_value(ScanType::ChangedByPercent, MemoryValue::F64(1.0))
+                .validate(ValueType::F64, true)
+                .is_err()
+        );
+        assert!(ScanCriteria::new(ScanType::Unknown)
+            .epsilon(f64::NAN)
+            .validate(ValueType::F64, false)
+            .is_err());
+    }
+
+    #[test]
+    fn test_wide_integers_and_bools() {
+        let big = MemoryValue::U128(u128::MAX);
+        let bigger = ScanCriteria::with_value(ScanType::BiggerThan, MemoryValue::I128(i128::MAX));
+        assert!(bigger.matches(ValueType::U128, &big, None));
+
+        // Differences that overflow i128 fall back to floats instead of panicking
+        let increased = ScanCriteria::new(ScanType::Increased);
+        let (now, old) = (MemoryValue::I128(i128::MAX), MemoryValue::I128(i128::MIN));
+        assert!(check(&increased, ValueType::I128, now.clone(), old.clone()));
+        let by = ScanCriteria::with_value(ScanType::IncreasedBy, MemoryValue::U8(1));
+        assert!(!check(&by, ValueType::I128, now, old));
+
+        let exact = ScanCriteria::with_value(ScanType::Exact, MemoryValue::Bool(true));
+        assert!(exact.matches(ValueType::Bool, &MemoryValue::Bool(true), None));
+        assert!(!exact.matches(ValueType::Bool, &MemoryValue::Bool(false), None));
+        assert!(
+            ScanCriteria::with_value(ScanType::BiggerThan, MemoryValue::Bool(false))
+                .validate(ValueType::Bool, false)
+                .is_err()
+        );
+    }
+}
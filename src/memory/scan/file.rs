@@ -0,0 +1,566 @@
+//! Scan session files
+//!
+//! Sessions are saved either as JSON, convenient for small result sets, or
+//! in a compact binary layout for large ones; files ending in `.json` use
+//! JSON. Both record, for each result inside a module, the module and the
+//! offset into it, so results can be rebased against their own module when
+//! the process is restarted and its modules load elsewhere. The scan options
+//! are saved too, so a resumed session keeps scanning the same regions.
+//!
+//! Binary layout (all integers little-endian):
+//! - magic `MMCPSESS`, format version (u32)
+//! - header, session metadata and scan options as length-prefixed JSON (u32)
+//! - result count (u64), then per result: address (u64), module (u32, the
+//!   index in the header's modules plus one, or 0 outside any module), the
+//!   offset into that module (u64) if any, value, a flag (u8) and the
+//!   previous value if the flag is 1
+//!
+//! Values of fixed-size types are stored raw; others are length-prefixed
+//! (u32). Sessions from any-type scans, whose results differ in type,
+//! precede each value with its type's index in [`ValueType::all`] (u8).
+//! Region info is not stored in the binary layout.
+
+use super::history::ScanHistory;
+use super::session::ManagedSession;
+use crate::core::types::{
+    Address, MemoryResult, MemoryValue, ModuleInfo, ScanResult, ScanSession, ValueType,
+};
+use crate::memory::backend::MemoryBackend;
+use crate::memory::binary::{
+    invalid, read_array, read_bytes, read_u32, read_u64, read_u8, write_u32, write_u64,
+};
+use crate::memory::scanner::ScanOptions;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+const MAGIC: &[u8; 8] = b"MMCPSESS";
+const VERSION: u32 = 1;
+
+/// On-disk encoding of a session file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionFormat {
+    Binary,
+    Json,
+}
+
+impl SessionFormat {
+    /// JSON for `.json` paths, binary otherwise
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension() {
+            Some(ext) if ext.eq_ignore_ascii_case("json") => SessionFormat::Json,
+            _ => SessionFormat::Binary,
+        }
+    }
+}
+
+/// A module that held results when the session was saved
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedModule {
+    pub name: String,
+    pub base_address: Address,
+    pub size: usize,
+}
+
+/// Position of a result inside one of the saved modules
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModuleOffset {
+    /// Index in [`SessionHeader::modules`]
+    pub module: usize,
+    pub offset: usize,
+}
+
+/// Process the session was taken from
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionHeader {
+    pub pid: u32,
+    /// Name of the main module
+    pub process_name: String,
+    /// Base address of the main module, if the process had any modules
+    #[serde(default)]
+    pub main_module_base: Option<Address>,
+    /// Modules containing results
+    pub modules: Vec<SavedModule>,
+    pub value_type: ValueType,
+    /// Results hold values of other types than `value_type`
+    #[serde(default)]
+    pub mixed_types: bool,
+}
+
+/// A saved scan session
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionFile {
+    pub header: SessionHeader,
+    pub session: ScanSession,
+    /// Module position of each result, `None` for results outside modules
+    #[serde(default)]
+    pub locations: Vec<Option<ModuleOffset>>,
+    /// Regions the session's first scans covered
+    #[serde(default)]
+    pub options: ScanOptions,
+}
+
+impl SessionFile {
+    /// Capture a session, locating its results in the modules of `backend`
+    pub fn capture<B: MemoryBackend>(backend: &B, session: &ManagedSession) -> MemoryResult<Self> {
+        let mut loaded = backend.enumerate_modules()?;
+        let process_name = loaded.first().map(|m| m.name.clone()).unwrap_or_default();
+        let main_module_base = loaded.first().map(|m| m.base_address);
+        loaded.sort_by_key(|m| m.base_address);
+
+        let mut modules = Vec::new();
+        // Index in `modules` of each module in `loaded` that holds a result
+        let mut saved: HashMap<usize, usize> = HashMap::new();
+        let locations = session
+            .scan
+            .results
+            .iter()
+            .map(|result| {
+                let address = result.address.as_usize();
+                let index = loaded.partition_point(|m| m.base_address <= result.address);
+                let found = &loaded[index.checked_sub(1)?];
+                let offset = address - found.base_address.as_usize();
+                if offset >= found.size {
+                    return None;
+                }
+                let module = *saved.entry(index - 1).or_insert_with(|| {
+                    modules.push(SavedModule {
+                        name: found.name.clone(),
+                        base_address: found.base_address,
+                        size: found.size,
+                    });
+                    modules.len() - 1
+                });
+                Some(ModuleOffset { module, offset })
+            })
+            .collect();
+
+        Ok(SessionFile {
+            header: SessionHeader {
+                pid: session.pid,
+                process_name,
+                main_module_base,
+                modules,
+                value_type: session.value_type(),
+                mixed_types: session
+                    .scan
+                    .results
+                    .iter()
+                    .any(|r| r.value.value_type() != session.value_type()),
+            },
+            session: session.scan.clone(),
+            locations,
+            options: session.options.clone(),
+        })
+    }
+
+    /// Move each result inside a module to that module's base in `modules`
+    ///
+    /// Returns the number of rebased results. Results outside modules, and
+    /// in modules that are not loaded or have not moved, stay where they are.
+    /// The saved module bases, the main one included, are updated to match.
+    pub fn rebase(&mut self, modules: &[ModuleInfo]) -> usize {
+        if let Some(main) = modules
+            .iter()
+            .find(|m| m.name.eq_ignore_ascii_case(&self.header.process_name))
+        {
+            self.header.main_module_base = Some(main.base_address);
+        }
+        let bases: Vec<Option<Address>> = self
+            .header
+            .modules
+            .iter()
+            .map(|saved| {
+                modules
+                    .iter()
+                    .find(|m| m.name.eq_ignore_ascii_case(&saved.name))
+                    .map(|m| m.base_address)
+            })
+            .collect();
+
+        let mut moved = 0;
+        for (result, location) in self.session.results.iter_mut().zip(&self.locations) {
+            let Some(location) = location else {
+                continue;
+            };
+            let Some(base) = bases.get(location.module).copied().flatten() else {
+                continue;
+            };
+            let address = Address::new(base.as_usize() + location.offset);
+            if address != result.address {
+                result.address = address;
+                moved += 1;
+            }
+        }
+
+        for (saved, base) in self.header.modules.iter_mut().zip(bases) {
+            if let Some(base) = base {
+                saved.base_address = base;
+            }
+        }
+        moved
+    }
+
+    /// Turn the file into a live session for the process behind `backend`
+    ///
+    /// Results are rebased against the process's current modules, and
+    /// later first scans cover the regions the saved options selected.
+    pub fn resume<B: MemoryBackend>(mut self, backend: &B) -> MemoryResult<ManagedSession> {
+        self.rebase(&backend.enumerate_modules()?);
+        Ok(ManagedSession {
+            pid: backend.pid(),
+            options: self.options,
+            scan: self.session,
+            history: ScanHistory::default(),
+        })
+    }
+
+    /// Write the file to `path` in the format its extension selects
+    pub fn save(&self, path: impl AsRef<Path>) -> MemoryResult<()> {
+        let path = path.as_ref();
+        let mut writer = BufWriter::new(File::create(path)?);
+        match SessionFormat::from_path(path) {
+            SessionFormat::Json => serde_json::to_writer(&mut writer, self)?,
+            SessionFormat::Binary => self.write(&mut writer)?,
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Read a file written by [`SessionFile::save`]
+    pub fn load(path: impl AsRef<Path>) -> MemoryResult<Self> {
+        let path = path.as_ref();
+        let mut reader = BufReader::new(File::open(path)?);
+        match SessionFormat::from_path(path) {
+            SessionFormat::Json => Ok(serde_json::from_reader(reader)?),
+            SessionFormat::Binary => Ok(Self::read(&mut reader)?),
+        }
+    }
+
+    fn write(&self, writer: &mut impl Write) -> io::Result<()> {
+        writer.write_all(MAGIC)?;
+        write_u32(writer, VERSION)?;
+
+        let metadata = SessionFile {
+            header: self.header.clone(),
+            session: ScanSession {
+                results: Vec::new(),
+                id: self.session.id.clone(),
+                encoding: self.session.encoding.clone(),
+                ..self.session
+            },
+            locations: Vec::new(),
+            options: self.options.clone(),
+        };
+        let json = serde_json::to_vec(&metadata).map_err(|e| invalid(e.to_string()))?;
+        write_u32(writer, json.len() as u32)?;
+        writer.write_all(&json)?;
+
+        let value_type = (!self.header.mixed_types).then_some(self.header.value_type);
+        write_u64(writer, self.session.results.len() as u64)?;
+        for (i, result) in self.session.results.iter().enumerate() {
+            write_u64(writer, result.address.as_usize() as u64)?;
+            match self.locations.get(i).copied().flatten() {
+                Some(location) => {
+                    write_u32(writer, location.module as u32 + 1)?;
+                    write_u64(writer, location.offset as u64)?;
+                }
+                None => write_u32(writer, 0)?,
+            }
+            write_value(writer, &result.value, value_type)?;
+            match &result.previous_value {
+                Some(previous) => {
+                    writer.write_all(&[1])?;
+                    write_value(writer, previous, value_type)?;
+                }
+                None => writer.write_all(&[0])?,
+            }
+        }
+
+        Ok(())
+    }
+
+    fn read(reader: &mut impl Read) -> io::Result<Self> {
+        if &read_array::<8>(reader)? != MAGIC {
+            return Err(invalid("Not a scan session file"));
+        }
+        let version = read_u32(reader)?;
+        if version != VERSION {
+            return Err(invalid(format!(
+                "Unsupported scan session version {}",
+                version
+            )));
+        }
+
+        let len = read_u32(reader)?;
+        let mut file: SessionFile = serde_json::from_slice(&read_bytes(reader, len as u64)?)
+            .map_err(|e| invalid(e.to_string()))?;
+
+        let value_type = (!file.header.mixed_types).then_some(file.header.value_type);
+        for _ in 0..read_u64(reader)? {
+            let address = Address::new(read_u64(reader)? as usize);
+            let location = match read_u32(reader)? {
+                0 => None,
+                module if module as usize <= file.header.modules.len() => Some(ModuleOffset {
+                    module: module as usize - 1,
+                    offset: read_u64(reader)? as usize,
+                }),
+                module => return Err(invalid(format!("Unknown module index {}", module))),
+            };
+            file.locations.push(location);
+            let value = read_value(reader, value_type)?;
+            let previous_value = match read_u8(reader)? {
+                0 => None,
+                1 => Some(read_value(reader, value_type)?),
+                flag => return Err(invalid(format!("Unknown previous value flag {}", flag))),
+            };
+            file.session.results.push(ScanResult {
+                previous_value,
+                ..ScanResult::new(address, value)
+            });
+        }
+
+        Ok(file)
+    }
+}
+
+/// Save `session` to `path`, recording the main module of `backend`
+pub fn save_session<B: MemoryBackend>(
+    backend: &B,
+    session: &ManagedSession,
+    path: impl AsRef<Path>,
+) -> MemoryResult<()> {
+    SessionFile::capture(backend, session)?.save(path)
+}
+
+/// Load a session saved by [`save_session`] and bind it to `backend`
+pub fn load_session<B: MemoryBackend>(
+    backend: &B,
+    path: impl AsRef<Path>,
+) -> MemoryResult<ManagedSession> {
+    SessionFile::load(path)?.resume(backend)
+}
+
+/// Write a value of `value_type`, or a tagged value of any type if `None`
+fn write_value(
+    writer: &mut impl Write,
+    value: &MemoryValue,
+    value_type: Option<ValueType>,
+) -> io::Result<()> {
+    let actual = value.value_type();
+    match value_type {
+        Some(expected) if expected != actual => {
+            return Err(invalid(format!(
+                "{:?} value in a {:?} session",
+                actual, expected
+            )));
+        }
+        Some(_) => {}
+        None => {
+            let tag = ValueType::all()
+                .iter()
+                .position(|&t| t == actual)
+                .unwrap_or(0);
+            writer.write_all(&[tag as u8])?;
+        }
+    }
+
+    let bytes = value.to_bytes();
+    if actual.size().is_none() {
+        write_u32(writer, bytes.len() as u32)?;
+    }
+    writer.write_all(&bytes)
+}
+
+fn read_value(reader: &mut impl Read, value_type: Option<ValueType>) -> io::Result<MemoryValue> {
+    let value_type = match value_type {
+        Some(value_type) => value_type,
+        None => {
+            let tag = read_u8(reader)?;
+            *ValueType::all()
+                .get(tag as usize)
+                .ok_or_else(|| invalid(format!("Unknown value type tag {}", tag)))?
+        }
+    };
+    let len = match value_type.size() {
+        Some(size) => size,
+        None => read_u32(reader)? as usize,
+    };
+    let bytes = read_bytes(reader, len as u64)?;
+    MemoryValue::from_bytes(&bytes, value_type)
+        .ok_or_else(|| invalid(format!("Invalid {:?} value", value_type)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::types::ScanType;
+    use crate::memory::backend::SnapshotProcess;
+
+    fn process(module_base: usize, library_base: usize) -> SnapshotProcess {
+        let mut process = SnapshotProcess::new(7);
+        process.add_module(ModuleInfo::new(
+            "game.exe".to_string(),
+            Address::new(module_base),
+            0x1000,
+        ));
+        process.add_module(ModuleInfo::new(
+            "engine.dll".to_string(),
+            Address::new(library_base),
+            0x1000,
+        ));
+        process
+    }
+
+    fn session(value_type: ValueType, values: Vec<MemoryValue>) -> ManagedSession {
+        let mut scan = ScanSession::new("session-1".to_string(), ScanType::Exact, value_type);
+        let mut results: Vec<ScanResult> = values
+            .into_iter()
+            .enumerate()
+            .map(|(i, v)| ScanResult::new(Address::new(0x400010 + i * 0x10), v))
+            .collect();
+        // One result outside the module, on the heap
+        results[0].address = Address::new(0x900000);
+        results[1].previous_value = Some(results[1].value.clone());
+        scan.add_results(results);
+        ManagedSession {
+            pid: 7,
+            options: ScanOptions {
+                writable_only: true,
+                alignment: 4,
+                ..ScanOptions::default()
+            },
+            scan,
+            history: ScanHistory::default(),
+        }
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "File system operations not supported under Miri")]
+    fn test_round_trip_in_both_formats() {
+        let dir = tempfile::tempdir().unwrap();
+        let cases = [
+            session(
+                ValueType::I32,
+                vec![
+                    MemoryValue::I32(-1),
+                    MemoryValue::I32(2),
+                    MemoryValue::I32(3),
+                ],
+            ),
+            session(
+                ValueType::String,
+                vec![
+                    MemoryValue::String("hp".to_string()),
+                    MemoryValue::String("mana".to_string()),
+                ],
+            ),
+        ];
+
+        for (i, original) in cases.iter().enumerate() {
+            for name in ["session.bin", "session.json"] {
+                let path = dir.path().join(format!("{}-{}", i, name));
+                save_session(&process(0x400000, 0x700000), original, &path).unwrap();
+
+                let file = SessionFile::load(&path).unwrap();
+                assert_eq!(file.header.process_name, "game.exe");
+                assert_eq!(file.header.main_module_base, Some(Address::new(0x400000)));
+                assert_eq!(file.header.value_type, original.value_type());
+                assert_eq!(file.session, original.scan);
+                assert_eq!(file.options, original.options);
+            }
+        }
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "File system operations not supported under Miri")]
+    fn test_load_rebases_module_addresses() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.bin");
+        let mut original = session(
+            ValueType::U8,
+            vec![
+                MemoryValue::U8(1),
+                MemoryValue::U8(2),
+                MemoryValue::U8(3),
+                MemoryValue::U8(4),
+            ],
+        );
+        // One result in a library that loads at a different offset from the main module
+        original.scan.results[3].address = Address::new(0x700800);
+        save_session(&process(0x400000, 0x700000), &original, &path).unwrap();
+
+        let file = SessionFile::load(&path).unwrap();
+        assert_eq!(file.header.modules.len(), 2);
+        assert_eq!(
+            file.locations,
+            vec![
+                None,
+                Some(ModuleOffset {
+                    module: 0,
+                    offset: 0x20
+                }),
+                Some(ModuleOffset {
+                    module: 0,
+                    offset: 0x30
+                }),
+                Some(ModuleOffset {
+                    module: 1,
+                    offset: 0x800
+                }),
+            ]
+        );
+
+        let resumed = load_session(&process(0x600000, 0x500000), &path).unwrap();
+        let addresses: Vec<usize> = resumed
+            .scan
+            .results
+            .iter()
+            .map(|r| r.address.as_usize())
+            .collect();
+        assert_eq!(addresses, vec![0x900000, 0x600020, 0x600030, 0x500800]);
+        assert_eq!(resumed.pid, 7);
+        assert_eq!(resumed.options, original.options);
+
+        let mut file = SessionFile::load(&path).unwrap();
+        file.rebase(&process(0x600000, 0x500000).enumerate_modules().unwrap());
+        assert_eq!(file.header.main_module_base, Some(Address::new(0x600000)));
+
+        let manager = crate::memory::SessionManager::default();
+        let id = manager.adopt(resumed);
+        assert_eq!(manager.get(&id).unwrap().lock().unwrap().scan.id, id);
+    }
+
+    #[test]
+    fn test_rebase_without_module_is_a_no_op() {
+        let original = session(ValueType::U8, vec![MemoryValue::U8(1), MemoryValue::U8(2)]);
+        let mut file = SessionFile::capture(&process(0x400000, 0x700000), &original).unwrap();
+        assert_eq!(file.rebase(&[]), 0);
+        assert_eq!(file.session, original.scan);
+    }
+
+    #[test]
+    fn test_read_rejects_bad_input() {
+        assert!(SessionFile::read(&mut &b"NOTAFILE"[..]).is_err());
+
+        let original = session(
+            ValueType::U16,
+            vec![MemoryValue::U16(1), MemoryValue::U16(2)],
+        );
+        let file = SessionFile::capture(&process(0x400000, 0x700000), &original).unwrap();
+        let mut bytes = Vec::new();
+        file.write(&mut bytes).unwrap();
+        assert!(SessionFile::read(&mut &bytes[..bytes.len() - 1]).is_err());
+
+        // A value that does not match the session's type needs a mixed header
+        let mut mixed = file.clone();
+        mixed.session.results[0].value = MemoryValue::U8(1);
+        assert!(mixed.write(&mut Vec::new()).is_err());
+        mixed.header.mixed_types = true;
+        let mut bytes = Vec::new();
+        mixed.write(&mut bytes).unwrap();
+        assert_eq!(SessionFile::read(&mut bytes.as_slice()).unwrap(), mixed);
+    }
+}
@@ -0,0 +1,392 @@
+//! Undo/redo history of scan refinements
+//!
+//! Each step stores only the difference between the result sets before and
+//! after a scan: results that were dropped, and results whose address, type
+//! or value changed. The previous values a refinement records on every
+//! survivor are kept apart, as one bit per survivor for the common cases of
+//! "none" and "same as the value", so a refinement that keeps most of a
+//! million candidates does not copy them.
+
+use super::bitmap::Bitmap;
+use super::ScanCriteria;
+use crate::core::types::{Address, MemoryValue, ScanResult, ScanSession, ScanType, ValueType};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Steps kept per session before the oldest is discarded
+pub const DEFAULT_HISTORY_DEPTH: usize = 16;
+
+/// Description of the scan that produced a step
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HistoryEntry {
+    /// Comparison the scan performed
+    pub scan_type: ScanType,
+    /// Value the scan compared against, if any
+    pub value: Option<MemoryValue>,
+    /// Unix time of the scan in seconds
+    pub timestamp: u64,
+    /// Results remaining after the scan
+    pub result_count: usize,
+}
+
+/// Identifies a result: an address holds one result per value type
+type ResultKey = (Address, ValueType);
+
+fn key(result: &ScanResult) -> ResultKey {
+    (result.address, result.value.value_type())
+}
+
+/// Order of a result set: by address, then in [`ValueType::all`] order
+fn order(result: &ScanResult) -> (Address, usize) {
+    let value_type = result.value.value_type();
+    let rank = ValueType::all()
+        .iter()
+        .position(|t| *t == value_type)
+        .unwrap_or(usize::MAX);
+    (result.address, rank)
+}
+
+/// Changes that rebuild one result set from another
+///
+/// Only valid for the result set it was computed against.
+#[derive(Debug, Clone)]
+struct ResultDelta {
+    /// Results to put back, replacing any with the same address and type
+    restore: Vec<ScanResult>,
+    /// Results to remove
+    remove: Vec<ResultKey>,
+    /// Positions of kept results whose previous value was `None`
+    previous_none: Bitmap,
+    /// Positions of kept results whose previous value equalled their value
+    previous_own: Bitmap,
+    /// Other previous values of kept results
+    previous: HashMap<ResultKey, MemoryValue>,
+}
+
+impl ResultDelta {
+    /// Delta that turns `to` back into `from`
+    fn between(from: &[ScanResult], to: &[ScanResult]) -> Self {
+        let earlier: HashMap<ResultKey, &ScanResult> = from.iter().map(|r| (key(r), r)).collect();
+        let current: HashSet<ResultKey> = to.iter().map(key).collect();
+
+        let mut delta = ResultDelta {
+            restore: from
+                .iter()
+                .filter(|r| !current.contains(&key(r)))
+                .cloned()
+                .collect(),
+            remove: Vec::new(),
+            previous_none: Bitmap::new(to.len()),
+            previous_own: Bitmap::new(to.len()),
+            previous: HashMap::new(),
+        };
+
+        for (index, now) in to.iter().enumerate() {
+            let Some(old) = earlier.get(&key(now)) else {
+                delta.remove.push(key(now));
+                continue;
+            };
+            if old.value != now.value || old.region_info != now.region_info {
+                delta.restore.push((*old).clone());
+                continue;
+            }
+            if old.previous_value == now.previous_value {
+                continue;
+            }
+            match &old.previous_value {
+                None => delta.previous_none.set(index),
+                Some(value) if *value == old.value => delta.previous_own.set(index),
+                Some(value) => {
+                    delta.previous.insert(key(old), value.clone());
+                }
+            }
+        }
+        delta
+    }
+
+    /// Rebuild the earlier result set from `results`, in address and type order
+    fn apply(&self, results: &[ScanResult]) -> Vec<ScanResult> {
+        let replaced: HashSet<ResultKey> = self
+            .remove
+            .iter()
+            .copied()
+            .chain(self.restore.iter().map(key))
+            .collect();
+
+        let mut rebuilt: Vec<ScanResult> = results
+            .iter()
+            .enumerate()
+            .filter(|(_, r)| !replaced.contains(&key(r)))
+            .map(|(index, r)| {
+                let mut r = r.clone();
+                if self.previous_none.get(index) {
+                    r.previous_value = None;
+                } else if self.previous_own.get(index) {
+                    r.previous_value = Some(r.value.clone());
+                } else if let Some(value) = self.previous.get(&key(&r)) {
+                    r.previous_value = Some(value.clone());
+                }
+                r
+            })
+            .chain(self.restore.iter().cloned())
+            .collect();
+        rebuilt.sort_by_key(order);
+        rebuilt
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Step {
+    entry: HistoryEntry,
+    /// Scan type of the session before this step
+    previous_type: ScanType,
+    /// Undo stack: rebuilds the results before the step; redo stack: after it
+    delta: ResultDelta,
+}
+
+/// Bounded undo/redo stacks for one session
+#[derive(Debug, Clone)]
+pub struct ScanHistory {
+    depth: usize,
+    undo: VecDeque<Step>,
+    redo: Vec<Step>,
+}
+
+impl ScanHistory {
+    /// Create a history that keeps up to `depth` steps
+    pub fn new(depth: usize) -> Self {
+        ScanHistory {
+            depth,
+            undo: VecDeque::new(),
+            redo: Vec::new(),
+        }
+    }
+
+    /// Record a scan that turned `before` into the session's current results
+    ///
+    /// Clears anything that could have been redone.
+    pub fn record(
+        &mut self,
+        before: &[ScanResult],
+        previous_type: ScanType,
+        session: &ScanSession,
+        criteria: &ScanCriteria,
+    ) {
+        self.redo.clear();
+        if self.depth == 0 {
+            return;
+        }
+        if self.undo.len() == self.depth {
+            self.undo.pop_front();
+        }
+        self.undo.push_back(Step {
+            entry: HistoryEntry {
+                scan_type: criteria.scan_type,
+                value: criteria.value.clone(),
+                timestamp: session.last_scan_at,
+                result_count: session.results.len(),
+            },
+            previous_type,
+            delta: ResultDelta::between(before, &session.results),
+        });
+    }
+
+    /// Revert the session to before its latest recorded scan
+    ///
+    /// Returns the entry that was undone, or `None` if there is nothing to undo.
+    pub fn undo(&mut self, session: &mut ScanSession) -> Option<HistoryEntry> {
+        let step = self.undo.pop_back()?;
+        let restored = step.delta.apply(&session.results);
+        let forward = ResultDelta::between(&session.results, &restored);
+
+        session.results = restored;
+        session.scan_type = step.previous_type;
+        self.redo.push(Step {
+            delta: forward,
+            ..step.clone()
+        });
+        Some(step.entry)
+    }
+
+    /// Reapply the most recently undone scan
+    pub fn redo(&mut self, session: &mut ScanSession) -> Option<HistoryEntry> {
+        let step = self.redo.pop()?;
+        let restored = step.delta.apply(&session.results);
+        let backward = ResultDelta::between(&session.results, &restored);
+
+        session.results = restored;
+        session.scan_type = step.entry.scan_type;
+        self.undo.push_back(Step {
+            delta: backward,
+            ..step.clone()
+        });
+        Some(step.entry)
+    }
+
+    /// Applied scans, oldest first
+    pub fn entries(&self) -> Vec<HistoryEntry> {
+        self.undo.iter().map(|s| s.entry.clone()).collect()
+    }
+
+    /// Undone scans that can be redone, next redo first
+    pub fn redo_entries(&self) -> Vec<HistoryEntry> {
+        self.redo.iter().rev().map(|s| s.entry.clone()).collect()
+    }
+
+    /// Whether a scan can be undone
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    /// Whether a scan can be redone
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+}
+
+impl Default for ScanHistory {
+    fn default() -> Self {
+        Self::new(DEFAULT_HISTORY_DEPTH)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::backend::{SnapshotProcess, SnapshotRegion};
+    use crate::memory::scan::{ScanOptions, ValueScanner};
+
+    fn result(address: usize, value: u32) -> ScanResult {
+        ScanResult::new(Address::new(address), MemoryValue::U32(value))
+    }
+
+    fn scan(
+        history: &mut ScanHistory,
+        session: &mut ScanSession,
+        criteria: ScanCriteria,
+        results: Vec<ScanResult>,
+    ) {
+        let before = std::mem::take(&mut session.results);
+        let previous_type = session.scan_type;
+        session.scan_type = criteria.scan_type;
+        session.add_results(results);
+        history.record(&before, previous_type, session, &criteria);
+    }
+
+    #[test]
+    fn test_delta_stores_only_differences() {
+        let before = vec![result(0x10, 1), result(0x20, 2), result(0x30, 3)];
+        let after = vec![result(0x20, 2), result(0x30, 4)];
+        let delta = ResultDelta::between(&before, &after);
+
+        assert_eq!(delta.restore, vec![result(0x10, 1), result(0x30, 3)]);
+        assert!(delta.remove.is_empty());
+        assert_eq!(delta.apply(&after), before);
+        assert_eq!(ResultDelta::between(&after, &before).apply(&before), after);
+    }
+
+    #[test]
+    fn test_undo_and_redo() {
+        let mut session = ScanSession::new("s".to_string(), ScanType::Unknown, ValueType::U32);
+        let mut history = ScanHistory::default();
+        let first = vec![result(0x10, 5), result(0x20, 5), result(0x30, 5)];
+
+        let exact = ScanCriteria::with_value(ScanType::Exact, MemoryValue::U32(5));
+        scan(&mut history, &mut session, exact, first.clone());
+        let changed = ScanCriteria::new(ScanType::Changed);
+        scan(&mut history, &mut session, changed, vec![result(0x20, 6)]);
+
+        let entries = history.entries();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].value, Some(MemoryValue::U32(5)));
+        assert_eq!(entries[1].result_count, 1);
+
+        let undone = history.undo(&mut session).unwrap();
+        assert_eq!(undone.scan_type, ScanType::Changed);
+        assert_eq!(session.results, first);
+        assert_eq!(session.scan_type, ScanType::Exact);
+        assert_eq!(history.redo_entries(), vec![undone]);
+
+        history.redo(&mut session).unwrap();
+        assert_eq!(session.results, vec![result(0x20, 6)]);
+        assert_eq!(session.scan_type, ScanType::Changed);
+        assert!(history.redo(&mut session).is_none());
+
+        history.undo(&mut session);
+        history.undo(&mut session);
+        assert!(session.results.is_empty());
+        assert_eq!(session.scan_type, ScanType::Unknown);
+        assert!(!history.can_undo());
+
+        // A new scan discards the redo stack
+        scan(
+            &mut history,
+            &mut session,
+            ScanCriteria::new(ScanType::Unknown),
+            first,
+        );
+        assert!(!history.can_redo());
+    }
+
+    #[test]
+    fn test_history_is_bounded() {
+        let mut session = ScanSession::new("s".to_string(), ScanType::Unknown, ValueType::U32);
+        let mut history = ScanHistory::new(2);
+        for i in 0..4 {
+            scan(
+                &mut history,
+                &mut session,
+                ScanCriteria::new(ScanType::Unchanged),
+                vec![result(0x10, i)],
+            );
+        }
+
+        assert_eq!(history.entries().len(), 2);
+        history.undo(&mut session);
+        history.undo(&mut session);
+        assert_eq!(session.results, vec![result(0x10, 1)]);
+        assert!(history.undo(&mut session).is_none());
+    }
+
+    #[test]
+    fn test_refinement_delta_does_not_copy_survivors() {
+        let process = SnapshotProcess::with_region(1, 0x10000, vec![5; 0x100], 0x04);
+        let scanner = ValueScanner::new(&process);
+        let mut session = ScanSession::new("s".to_string(), ScanType::Unknown, ValueType::U8);
+        let mut history = ScanHistory::default();
+
+        let five = ScanCriteria::with_value(ScanType::Exact, MemoryValue::U8(5));
+        let options = ScanOptions {
+            max_results: None,
+            ..ScanOptions::default()
+        };
+        assert_eq!(
+            scanner.first_scan(&mut session, &five, &options).unwrap(),
+            0x100
+        );
+        let found = session.results.clone();
+
+        let unchanged = ScanCriteria::new(ScanType::Unchanged);
+        let refined = scanner.refine(&session, &unchanged).unwrap();
+        scan(
+            &mut history,
+            &mut session,
+            unchanged.clone(),
+            refined.clone(),
+        );
+        let delta = &history.undo.back().unwrap().delta;
+        assert!(delta.restore.is_empty());
+        assert!(delta.remove.is_empty());
+        assert!(delta.previous.is_empty());
+        assert_eq!(delta.previous_none.count_ones(), 0x100);
+
+        history.undo(&mut session);
+        assert_eq!(session.results, found);
+        let delta = &history.redo.last().unwrap().delta;
+        assert!(delta.restore.is_empty() && delta.previous.is_empty());
+        assert_eq!(delta.previous_own.count_ones(), 0x100);
+
+        history.redo(&mut session);
+        assert_eq!(session.results, refined);
+    }
+}
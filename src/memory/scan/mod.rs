@@ -0,0 +1,588 @@
+//! Typed value scanning: first scan over readable memory, then next scans
+//!
+//! A first scan reads every region selected by [`ScanOptions`] and records
+//! each aligned address whose value satisfies the [`ScanCriteria`]. Next
+//! scans re-read only the surviving addresses of the [`ScanSession`] and
+//! compare them against their previously recorded values.
+//!
+//! Scans expected to match a large share of memory, such as an unknown
+//! initial value, should use the compact variants, which track candidates in
+//! a [`CandidateStore`] until few enough remain to list individually.
+
+mod bitmap;
+pub mod criteria;
+pub mod file;
+pub mod history;
+pub mod predicate;
+pub mod session;
+mod spill;
+pub mod store;
+
+pub use criteria::ScanCriteria;
+pub use file::{load_session, save_session, SessionFile, SessionFormat, SessionHeader};
+pub use history::{HistoryEntry, ScanHistory};
+pub use predicate::ScanPredicate;
+pub use session::{ManagedSession, SessionInfo, SessionManager, SharedSession};
+pub use store::{CandidateStore, StoreConfig};
+
+use crate::core::types::{
+    Address, MemoryResult, MemoryValue, ProcessArchitecture, ScanResult, ScanSession,
+    ValueEncoding, ValueType,
+};
+use crate::memory::backend::MemoryBackend;
+use crate::memory::scanner::{scan_regions, ScanOptions};
+use crate::process::ProcessHandle;
+
+/// Bytes read from the target per request during a first scan
+const READ_CHUNK_SIZE: usize = 1024 * 1024;
+
+/// Scanner that searches memory for typed values
+pub struct ValueScanner<'a, B: MemoryBackend = ProcessHandle> {
+    handle: &'a B,
+}
+
+impl<'a, B: MemoryBackend> ValueScanner<'a, B> {
+    /// Create a new value scanner
+    pub fn new(handle: &'a B) -> Self {
+        ValueScanner { handle }
+    }
+
+    /// Backend the scanner reads from
+    pub fn backend(&self) -> &'a B {
+        self.handle
+    }
+
+    /// Run a first scan, replacing any results already in the session
+    ///
+    /// Returns the number of matches. Regions that fail to read are skipped.
+    pub fn first_scan(
+        &self,
+        session: &mut ScanSession,
+        criteria: &ScanCriteria,
+        options: &ScanOptions,
+    ) -> MemoryResult<usize> {
+        criteria.validate(session.value_type, false)?;
+
+        let value_type = session.value_type;
+        let width = value_width(value_type, criteria, self.handle.architecture());
+        let results = if width > 0 {
+            let types = [(value_type, width)];
+            self.collect_first(criteria, options, &session.encoding, &types)?
+        } else {
+            Vec::new()
+        };
+
+        session.scan_type = criteria.scan_type;
+        session.add_results(results);
+        Ok(session.results.len())
+    }
+
+    /// Run a first scan that tries every numeric type up to 64 bits at each address
+    ///
+    /// Each result's value carries the type it matched as, and an address
+    /// may appear once per matching type. [`ValueScanner::next_scan`] then
+    /// refines every result using its own type. The session's value type is
+    /// left unchanged.
+    pub fn first_scan_any(
+        &self,
+        session: &mut ScanSession,
+        criteria: &ScanCriteria,
+        options: &ScanOptions,
+    ) -> MemoryResult<usize> {
+        let types: Vec<(ValueType, usize)> = ValueType::all()
+            .iter()
+            .filter(|t| t.is_numeric())
+            .filter_map(|&t| Some((t, t.size()?)))
+            .filter(|&(_, width)| width <= 8)
+            .collect();
+        for &(value_type, _) in &types {
+            criteria.validate(value_type, false)?;
+        }
+
+        let results = self.collect_first(criteria, options, &session.encoding, &types)?;
+        session.scan_type = criteria.scan_type;
+        session.add_results(results);
+        Ok(session.results.len())
+    }
+
+    /// Read the selected regions and keep every `(type, width)` reading that matches
+    fn collect_first(
+        &self,
+        criteria: &ScanCriteria,
+        options: &ScanOptions,
+        encoding: &ValueEncoding,
+        types: &[(ValueType, usize)],
+    ) -> MemoryResult<Vec<ScanResult>> {
+        let widest = types.iter().map(|&(_, w)| w).max().unwrap_or(0);
+        let narrowest = types.iter().map(|&(_, w)| w).min().unwrap_or(0);
+        let alignment = options.alignment.max(1);
+        let limit = options.max_results.unwrap_or(usize::MAX);
+        let architecture = self.handle.architecture();
+        let mut results = Vec::new();
+
+        'regions: for (base, size) in scan_regions(self.handle, options)? {
+            let mut offset = 0;
+
+            while offset < size {
+                // Overlap chunks by the widest width - 1 so values straddling a boundary are seen
+                let len = (READ_CHUNK_SIZE + widest - 1).min(size - offset);
+                let mut buffer = vec![0u8; len];
+                let chunk = Address::new(base.as_usize() + offset);
+                if len < narrowest || self.handle.read(chunk, &mut buffer).is_err() {
+                    break;
+                }
+
+                // Offsets past the chunk size are scanned again by the next chunk
+                let first = align_up(chunk.as_usize(), alignment) - chunk.as_usize();
+                for i in (first..len.min(READ_CHUNK_SIZE)).step_by(alignment) {
+                    for &(value_type, width) in types {
+                        let Some(bytes) = buffer.get(i..i + width) else {
+                            continue;
+                        };
+                        let Some(value) = encoding.decode_for(bytes, value_type, architecture)
+                        else {
+                            continue;
+                        };
+
+                        if criteria.matches(value_type, &value, None) {
+                            results
+                                .push(ScanResult::new(Address::new(chunk.as_usize() + i), value));
+                            if results.len() >= limit {
+                                break 'regions;
+                            }
+                        }
+                    }
+                }
+
+                offset += READ_CHUNK_SIZE;
+            }
+        }
+
+        Ok(results)
+    }
+
+    /// Refine the session's results by re-reading each address
+    ///
+    /// Each result is read and compared as the type of its recorded value.
+    /// Addresses that can no longer be read are dropped. Returns the number
+    /// of surviving results.
+    pub fn next_scan(
+        &self,
+        session: &mut ScanSession,
+        criteria: &ScanCriteria,
+    ) -> MemoryResult<usize> {
+        let results = self.refine(session, criteria)?;
+        session.scan_type = criteria.scan_type;
+        session.add_results(results);
+        Ok(session.results.len())
+    }
+
+    /// Results that `next_scan` would leave in the session, without changing it
+    pub(crate) fn refine(
+        &self,
+        session: &ScanSession,
+        criteria: &ScanCriteria,
+    ) -> MemoryResult<Vec<ScanResult>> {
+        criteria.validate(session.value_type, true)?;
+        let mut checked = vec![session.value_type];
+        for result in &session.results {
+            let value_type = result.value.value_type();
+            if !checked.contains(&value_type) {
+                criteria.validate(value_type, true)?;
+                checked.push(value_type);
+            }
+        }
+
+        let architecture = self.handle.architecture();
+        let mut buffer = Vec::new();
+
+        let results = session
+            .results
+            .iter()
+            .filter_map(|result| {
+                let value_type = result.value.value_type();
+                buffer.resize(result.value.size_for(architecture), 0);
+                self.handle.read(result.address, &mut buffer).ok()?;
+                let current = session
+                    .encoding
+                    .decode_for(&buffer, value_type, architecture)?;
+
+                criteria
+                    .matches(value_type, &current, Some(&result.value))
+                    .then(|| {
+                        ScanResult::with_previous(result.address, current, result.value.clone())
+                    })
+            })
+            .collect();
+        Ok(results)
+    }
+
+    /// Run a first scan into a compact candidate store
+    ///
+    /// If the match count is already at or below the store's materialize
+    /// threshold, the results are also written to the session.
+    pub fn first_scan_compact(
+        &self,
+        session: &mut ScanSession,
+        criteria: &ScanCriteria,
+        options: &ScanOptions,
+        config: StoreConfig,
+    ) -> MemoryResult<CandidateStore> {
+        criteria.validate(session.value_type, false)?;
+
+        let value_type = session.value_type;
+        let mut store = CandidateStore::new(value_type, options.alignment, config)?
+            .with_encoding(session.encoding.clone())
+            .with_architecture(self.handle.architecture());
+
+        for (base, size) in scan_regions(self.handle, options)? {
+            store.add_region(self.handle, base, size, |value| {
+                criteria.matches(value_type, value, None)
+            })?;
+        }
+
+        session.scan_type = criteria.scan_type;
+        let results = if store.should_materialize() {
+            store.materialize()?
+        } else {
+            Vec::new()
+        };
+        session.add_results(results);
+        Ok(store)
+    }
+
+    /// Refine a compact candidate store
+    ///
+    /// Once the candidate count drops to the materialize threshold, the
+    /// results are written to the session and later refinements should use
+    /// [`ValueScanner::next_scan`]. Returns the number of surviving candidates.
+    pub fn next_scan_compact(
+        &self,
+        store: &mut CandidateStore,
+        session: &mut ScanSession,
+        criteria: &ScanCriteria,
+    ) -> MemoryResult<usize> {
+        let count = store.refine(self.handle, criteria)?;
+
+        session.scan_type = criteria.scan_type;
+        let results = if store.should_materialize() {
+            store.materialize()?
+        } else {
+            Vec::new()
+        };
+        session.add_results(results);
+        Ok(count)
+    }
+}
+
+/// Number of bytes compared at each address
+fn value_width(
+    value_type: ValueType,
+    criteria: &ScanCriteria,
+    architecture: ProcessArchitecture,
+) -> usize {
+    value_type
+        .size_for(architecture)
+        .or_else(|| criteria.value.as_ref().map(|v| v.size_for(architecture)))
+        .unwrap_or(0)
+}
+
+pub(crate) fn align_up(value: usize, alignment: usize) -> usize {
+    (value + alignment - 1) / alignment * alignment
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::types::ScanType;
+    use crate::memory::backend::{SnapshotProcess, SnapshotRegion};
+
+    const PAGE_READWRITE: u32 = 0x04;
+    const BASE: usize = 0x10000;
+
+    fn snapshot(data: Vec<u8>) -> SnapshotProcess {
+        SnapshotProcess::with_region(1, BASE, data, PAGE_READWRITE)
+    }
+
+    fn session(value_type: ValueType) -> ScanSession {
+        ScanSession::new("test".to_string(), ScanType::Unknown, value_type)
+    }
+
+    fn addresses(session: &ScanSession) -> Vec<usize> {
+        session
+            .results
+            .iter()
+            .map(|r| r.address.as_usize())
+            .collect()
+    }
+
+    #[test]
+    fn test_first_scan_exact_aligned() {
+        let mut data = vec![0u8; 64];
+        data[8..12].copy_from_slice(&(-7i32).to_le_bytes());
+        data[21..25].copy_from_slice(&(-7i32).to_le_bytes());
+        let process = snapshot(data);
+        let scanner = ValueScanner::new(&process);
+        let criteria = ScanCriteria::with_value(ScanType::Exact, MemoryValue::I32(-7));
+
+        let mut aligned = session(ValueType::I32);
+        let options = ScanOptions {
+            alignment: 4,
+            ..ScanOptions::default()
+        };
+        assert_eq!(
+            scanner
+                .first_scan(&mut aligned, &criteria, &options)
+                .unwrap(),
+            1
+        );
+        assert_eq!(addresses(&aligned), vec![BASE + 8]);
+
+        let mut unaligned = session(ValueType::I32);
+        scanner
+            .first_scan(&mut unaligned, &criteria, &ScanOptions::default())
+            .unwrap();
+        assert_eq!(addresses(&unaligned), vec![BASE + 8, BASE + 21]);
+    }
+
+    #[test]
+    fn test_first_scan_crosses_chunk_boundary() {
+        let mut data = vec![0u8; READ_CHUNK_SIZE + 16];
+        data[READ_CHUNK_SIZE - 2..READ_CHUNK_SIZE + 2]
+            .copy_from_slice(&0xDEADBEEFu32.to_le_bytes());
+        let process = snapshot(data);
+
+        let mut session = session(ValueType::U32);
+        let criteria = ScanCriteria::with_value(ScanType::Exact, MemoryValue::U32(0xDEADBEEF));
+        ValueScanner::new(&process)
+            .first_scan(&mut session, &criteria, &ScanOptions::default())
+            .unwrap();
+        assert_eq!(addresses(&session), vec![BASE + READ_CHUNK_SIZE - 2]);
+    }
+
+    #[test]
+    fn test_next_scan_refines_results() {
+        let process = snapshot(
+            [10u16, 20, 30, 40]
+                .iter()
+                .flat_map(|v| v.to_le_bytes())
+                .collect(),
+        );
+        let scanner = ValueScanner::new(&process);
+        let options = ScanOptions {
+            alignment: 2,
+            ..ScanOptions::default()
+        };
+
+        let mut session = session(ValueType::U16);
+        let unknown = ScanCriteria::new(ScanType::Unknown);
+        assert_eq!(
+            scanner
+                .first_scan(&mut session, &unknown, &options)
+                .unwrap(),
+            4
+        );
+
+        process
+            .write(Address::new(BASE), &15u16.to_le_bytes())
+            .unwrap();
+        process
+            .write(Address::new(BASE + 4), &25u16.to_le_bytes())
+            .unwrap();
+
+        let decreased = ScanCriteria::with_value(ScanType::DecreasedBy, MemoryValue::U16(5));
+        assert_eq!(scanner.next_scan(&mut session, &decreased).unwrap(), 1);
+        assert_eq!(addresses(&session), vec![BASE + 4]);
+        assert_eq!(session.results[0].value, MemoryValue::U16(25));
+        assert_eq!(
+            session.results[0].previous_value,
+            Some(MemoryValue::U16(30))
+        );
+        assert_eq!(session.scan_count, 2);
+        assert_eq!(session.scan_type, ScanType::DecreasedBy);
+    }
+
+    #[test]
+    fn test_first_scan_rejects_relative_types() {
+        let process = snapshot(vec![0; 16]);
+        let mut session = session(ValueType::I32);
+        let result = ValueScanner::new(&process).first_scan(
+            &mut session,
+            &ScanCriteria::new(ScanType::Changed),
+            &ScanOptions::default(),
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_string_scan() {
+        let mut data = vec![0u8; 32];
+        data[5..9].copy_from_slice(b"gold");
+        let process = snapshot(data);
+
+        let mut session = session(ValueType::String);
+        let criteria =
+            ScanCriteria::with_value(ScanType::Exact, MemoryValue::String("gold".into()));
+        ValueScanner::new(&process)
+            .first_scan(&mut session, &criteria, &ScanOptions::default())
+            .unwrap();
+        assert_eq!(addresses(&session), vec![BASE + 5]);
+    }
+
+    #[test]
+    fn test_unknown_scan_compact_materializes() {
+        let process = snapshot(vec![0u8; 4096]);
+        let scanner = ValueScanner::new(&process);
+        let options = ScanOptions {
+            alignment: 4,
+            ..ScanOptions::default()
+        };
+        let config = StoreConfig {
+            materialize_threshold: 4,
+            ..StoreConfig::default()
+        };
+
+        let mut session = session(ValueType::I32);
+        let unknown = ScanCriteria::new(ScanType::Unknown);
+        let mut store = scanner
+            .first_scan_compact(&mut session, &unknown, &options, config)
+            .unwrap();
+        assert_eq!(store.candidate_count(), 1024);
+        assert!(session.results.is_empty());
+
+        process
+            .write(Address::new(BASE + 0x100), &(-3i32).to_le_bytes())
+            .unwrap();
+        let decreased = ScanCriteria::new(ScanType::Decreased);
+        assert_eq!(
+            scanner
+                .next_scan_compact(&mut store, &mut session, &decreased)
+                .unwrap(),
+            1
+        );
+        assert_eq!(addresses(&session), vec![BASE + 0x100]);
+        assert_eq!(session.results[0].value, MemoryValue::I32(-3));
+    }
+
+    #[test]
+    fn test_any_type_scan_tags_and_refines_by_type() {
+        let mut data = vec![0u8; 0x100];
+        data[0x20..0x24].copy_from_slice(&[100, 0, 0xFF, 0xFF]);
+        data[0x40..0x44].copy_from_slice(&100f32.to_le_bytes());
+        data[0x44..0x48].fill(0xFF);
+        data[0x60..0x68].copy_from_slice(&100f64.to_le_bytes());
+        let process = snapshot(data);
+        let scanner = ValueScanner::new(&process);
+        let options = ScanOptions {
+            alignment: 4,
+            ..ScanOptions::default()
+        };
+
+        let mut session = session(ValueType::I32);
+        let hundred = ScanCriteria::with_value(ScanType::Exact, MemoryValue::I32(100));
+        assert_eq!(
+            scanner
+                .first_scan_any(&mut session, &hundred, &options)
+                .unwrap(),
+            6
+        );
+        let tagged: Vec<(usize, ValueType)> = session
+            .results
+            .iter()
+            .map(|r| (r.address.as_usize() - BASE, r.value.value_type()))
+            .collect();
+        assert_eq!(
+            tagged,
+            vec![
+                (0x20, ValueType::I8),
+                (0x20, ValueType::I16),
+                (0x20, ValueType::U8),
+                (0x20, ValueType::U16),
+                (0x40, ValueType::F32),
+                (0x60, ValueType::F64),
+            ]
+        );
+
+        process
+            .write(Address::new(BASE + 0x20), &356u16.to_le_bytes())
+            .unwrap();
+        process
+            .write(Address::new(BASE + 0x40), &150f32.to_le_bytes())
+            .unwrap();
+        let increased = ScanCriteria::new(ScanType::Increased);
+        assert_eq!(scanner.next_scan(&mut session, &increased).unwrap(), 3);
+        let values: Vec<MemoryValue> = session.results.iter().map(|r| r.value.clone()).collect();
+        assert_eq!(
+            values,
+            vec![
+                MemoryValue::I16(356),
+                MemoryValue::U16(356),
+                MemoryValue::F32(150.0)
+            ]
+        );
+        assert_eq!(session.value_type, ValueType::I32);
+    }
+
+    #[test]
+    fn test_scans_decode_with_session_encoding() {
+        let mut data = vec![0u8; 64];
+        data[8..12].copy_from_slice(&(1000u32 ^ 0xABCD).to_be_bytes());
+        let process = snapshot(data);
+        let scanner = ValueScanner::new(&process);
+
+        let mut session = session(ValueType::U32);
+        session.encoding = ValueEncoding::big_endian().with_xor(0xABCD);
+        let criteria = ScanCriteria::with_value(ScanType::Exact, MemoryValue::U32(1000));
+        scanner
+            .first_scan(&mut session, &criteria, &ScanOptions::default())
+            .unwrap();
+        assert_eq!(addresses(&session), vec![BASE + 8]);
+
+        process
+            .write(Address::new(BASE + 8), &(1001u32 ^ 0xABCD).to_be_bytes())
+            .unwrap();
+        let increased = ScanCriteria::with_value(ScanType::IncreasedBy, MemoryValue::U32(1));
+        assert_eq!(scanner.next_scan(&mut session, &increased).unwrap(), 1);
+        assert_eq!(session.results[0].value, MemoryValue::U32(1001));
+    }
+
+    #[test]
+    fn test_pointer_and_array_scans() {
+        let mut data = vec![0u8; 64];
+        data[8..12].copy_from_slice(&0x0040_1000u32.to_le_bytes());
+        data[16..20].copy_from_slice(&0x0040_2000u32.to_le_bytes());
+        data[32..36].copy_from_slice(&[1, 2, 3, 4]);
+        let process = snapshot(data).with_architecture(ProcessArchitecture::X86);
+        let scanner = ValueScanner::new(&process);
+        let options = ScanOptions::default();
+
+        // 32-bit pointers are four bytes wide and compare as addresses
+        let mut pointers = session(ValueType::Pointer);
+        let range = ScanCriteria::between(
+            MemoryValue::Pointer(Address::new(0x0040_0000)),
+            MemoryValue::Pointer(Address::new(0x0040_1FFF)),
+        );
+        scanner.first_scan(&mut pointers, &range, &options).unwrap();
+        assert_eq!(addresses(&pointers), vec![BASE + 8]);
+
+        process
+            .write(Address::new(BASE + 8), &0x0040_1010u32.to_le_bytes())
+            .unwrap();
+        let increased = ScanCriteria::new(ScanType::Increased);
+        assert_eq!(scanner.next_scan(&mut pointers, &increased).unwrap(), 1);
+        assert_eq!(
+            pointers.results[0].value,
+            MemoryValue::Pointer(Address::new(0x0040_1010))
+        );
+
+        let bytes = ValueType::array(ValueType::U8, 4).unwrap();
+        let target = MemoryValue::from_bytes(&[1, 2, 3, 4], bytes).unwrap();
+        let mut arrays = session(bytes);
+        let exact = ScanCriteria::with_value(ScanType::Exact, target);
+        scanner.first_scan(&mut arrays, &exact, &options).unwrap();
+        assert_eq!(addresses(&arrays), vec![BASE + 32]);
+        assert!(ScanCriteria::new(ScanType::Increased)
+            .validate(bytes, true)
+            .is_err());
+    }
+}
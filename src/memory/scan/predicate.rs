@@ -0,0 +1,174 @@
+//! Serializable scan predicates
+//!
+//! [`ScanPredicate`] names each comparison together with exactly the
+//! parameters it needs, which makes it the natural shape for JSON tool
+//! arguments such as `{"op": "between", "low": {...}, "high": {...}}`.
+//! Predicates convert into [`ScanCriteria`], which does the evaluation;
+//! convert once before a scan rather than for every candidate.
+
+use super::criteria::ScanCriteria;
+use crate::core::types::{MemoryValue, ScanType};
+use serde::{Deserialize, Serialize};
+
+/// A comparison applied to each value during a first or next scan
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum ScanPredicate {
+    /// Every value matches
+    Unknown,
+    /// Equal to `value`, within `tolerance`
+    Exact {
+        value: MemoryValue,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        tolerance: Option<f64>,
+    },
+    /// Strictly greater than `value`
+    BiggerThan { value: MemoryValue },
+    /// Strictly less than `value`
+    SmallerThan { value: MemoryValue },
+    /// Within `low..=high`
+    Between {
+        low: MemoryValue,
+        high: MemoryValue,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        tolerance: Option<f64>,
+    },
+    /// Greater than the previous value
+    Increased,
+    /// Less than the previous value
+    Decreased,
+    /// Different from the previous value by more than `tolerance`
+    Changed {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        tolerance: Option<f64>,
+    },
+    /// Equal to the previous value within `tolerance`
+    Unchanged {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        tolerance: Option<f64>,
+    },
+    /// Grew by `delta` since the previous scan, within `tolerance`
+    IncreasedBy {
+        delta: MemoryValue,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        tolerance: Option<f64>,
+    },
+    /// Shrank by `delta` since the previous scan, within `tolerance`
+    DecreasedBy {
+        delta: MemoryValue,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        tolerance: Option<f64>,
+    },
+    /// Changed by `min..=max` percent of the previous value; negative for drops
+    ChangedByPercent { min: f64, max: f64 },
+}
+
+impl ScanPredicate {
+    /// Scan type recorded for this predicate
+    pub fn scan_type(&self) -> ScanType {
+        match self {
+            ScanPredicate::Unknown => ScanType::Unknown,
+            ScanPredicate::Exact { .. } => ScanType::Exact,
+            ScanPredicate::BiggerThan { .. } => ScanType::BiggerThan,
+            ScanPredicate::SmallerThan { .. } => ScanType::SmallerThan,
+            ScanPredicate::Between { .. } => ScanType::Between,
+            ScanPredicate::Increased => ScanType::Increased,
+            ScanPredicate::Decreased => ScanType::Decreased,
+            ScanPredicate::Changed { .. } => ScanType::Changed,
+            ScanPredicate::Unchanged { .. } => ScanType::Unchanged,
+            ScanPredicate::IncreasedBy { .. } => ScanType::IncreasedBy,
+            ScanPredicate::DecreasedBy { .. } => ScanType::DecreasedBy,
+            ScanPredicate::ChangedByPercent { .. } => ScanType::ChangedByPercent,
+        }
+    }
+}
+
+impl From<ScanPredicate> for ScanCriteria {
+    fn from(predicate: ScanPredicate) -> Self {
+        let scan_type = predicate.scan_type();
+        let (value, upper, epsilon) = match predicate {
+            ScanPredicate::Unknown | ScanPredicate::Increased | ScanPredicate::Decreased => {
+                (None, None, None)
+            }
+            ScanPredicate::BiggerThan { value } | ScanPredicate::SmallerThan { value } => {
+                (Some(value), None, None)
+            }
+            ScanPredicate::Exact { value, tolerance } => (Some(value), None, tolerance),
+            ScanPredicate::Between {
+                low,
+                high,
+                tolerance,
+            } => (Some(low), Some(high), tolerance),
+            ScanPredicate::Changed { tolerance } | ScanPredicate::Unchanged { tolerance } => {
+                (None, None, tolerance)
+            }
+            ScanPredicate::IncreasedBy { delta, tolerance }
+            | ScanPredicate::DecreasedBy { delta, tolerance } => (Some(delta), None, tolerance),
+            ScanPredicate::ChangedByPercent { min, max } => (
+                Some(MemoryValue::F64(min)),
+                Some(MemoryValue::F64(max)),
+                None,
+            ),
+        };
+
+        ScanCriteria {
+            scan_type,
+            value,
+            upper,
+            epsilon,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::types::ValueType;
+    use serde_json::json;
+
+    #[test]
+    fn test_deserialize_from_tool_arguments() {
+        let predicate: ScanPredicate = serde_json::from_value(json!({
+            "op": "between",
+            "low": { "type": "I32", "value": -10 },
+            "high": { "type": "I32", "value": 10 }
+        }))
+        .unwrap();
+        assert_eq!(predicate.scan_type(), ScanType::Between);
+        let criteria = ScanCriteria::from(predicate);
+        assert!(criteria.matches(ValueType::I8, &MemoryValue::I8(-10), None));
+        assert!(!criteria.matches(ValueType::U64, &MemoryValue::U64(u64::MAX), None));
+
+        let percent: ScanPredicate =
+            serde_json::from_value(json!({ "op": "changed_by_percent", "min": -60, "max": -40 }))
+                .unwrap();
+        let percent = ScanCriteria::from(percent);
+        let now = MemoryValue::F32(50.0);
+        assert!(percent.matches(ValueType::F32, &now, Some(&MemoryValue::F32(100.0))));
+        assert!(percent.validate(ValueType::F32, false).is_err());
+    }
+
+    #[test]
+    fn test_round_trip_and_tolerance() {
+        let predicate = ScanPredicate::IncreasedBy {
+            delta: MemoryValue::F32(1.5),
+            tolerance: Some(0.1),
+        };
+        let text = serde_json::to_string(&predicate).unwrap();
+        assert_eq!(
+            serde_json::from_str::<ScanPredicate>(&text).unwrap(),
+            predicate
+        );
+
+        let (now, old) = (MemoryValue::F32(11.55), MemoryValue::F32(10.0));
+        assert!(ScanCriteria::from(predicate).matches(ValueType::F32, &now, Some(&old)));
+        let strict = ScanCriteria::from(ScanPredicate::IncreasedBy {
+            delta: MemoryValue::F32(1.5),
+            tolerance: None,
+        });
+        assert!(!strict.matches(ValueType::F32, &now, Some(&old)));
+
+        let unchanged: ScanPredicate = serde_json::from_str(r#"{"op":"unchanged"}"#).unwrap();
+        assert_eq!(unchanged, ScanPredicate::Unchanged { tolerance: None });
+    }
+}
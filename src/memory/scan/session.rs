@@ -0,0 +1,499 @@
+//! Named scan sessions shared between concurrent clients
+//!
+//! A [`SessionManager`] owns every live scan session by id. Each session
+//! sits behind its own lock, so refining one session never waits on another
+//! even when both target the same process. Sessions left idle for longer
+//! than the configured TTL are evicted.
+
+use super::history::{HistoryEntry, ScanHistory};
+use super::{ScanCriteria, ValueScanner};
+use crate::config::{default_config, ScannerConfig};
+use crate::core::types::{MemoryError, MemoryResult, ScanSession, ScanType, ValueType};
+use crate::memory::backend::MemoryBackend;
+use crate::memory::scanner::ScanOptions;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// A scan session bound to one process and set of regions
+#[derive(Debug, Clone)]
+pub struct ManagedSession {
+    /// Process the session scans
+    pub pid: u32,
+    /// Regions covered by first scans
+    pub options: ScanOptions,
+    /// Results and scan metadata
+    pub scan: ScanSession,
+    /// Recorded scans that can be undone
+    pub history: ScanHistory,
+}
+
+impl ManagedSession {
+    /// Value type the session scans for
+    pub fn value_type(&self) -> ValueType {
+        self.scan.value_type
+    }
+
+    /// Refuse to scan a process other than the session's own
+    fn check_process<B: MemoryBackend>(&self, scanner: &ValueScanner<'_, B>) -> MemoryResult<()> {
+        let pid = scanner.backend().pid();
+        if pid != self.pid {
+            return Err(MemoryError::InvalidArgument(format!(
+                "session {} scans process {}, not {}",
+                self.scan.id, self.pid, pid
+            )));
+        }
+        Ok(())
+    }
+
+    /// Run a first scan over the session's regions and record it
+    pub fn first_scan<B: MemoryBackend>(
+        &mut self,
+        scanner: &ValueScanner<'_, B>,
+        criteria: &ScanCriteria,
+    ) -> MemoryResult<usize> {
+        self.check_process(scanner)?;
+        let before = std::mem::take(&mut self.scan.results);
+        let previous_type = self.scan.scan_type;
+        match scanner.first_scan(&mut self.scan, criteria, &self.options) {
+            Ok(count) => {
+                self.history
+                    .record(&before, previous_type, &self.scan, criteria);
+                Ok(count)
+            }
+            Err(e) => {
+                self.scan.results = before;
+                Err(e)
+            }
+        }
+    }
+
+    /// Run a first scan trying every numeric type, and record it
+    pub fn first_scan_any<B: MemoryBackend>(
+        &mut self,
+        scanner: &ValueScanner<'_, B>,
+        criteria: &ScanCriteria,
+    ) -> MemoryResult<usize> {
+        self.check_process(scanner)?;
+        let before = std::mem::take(&mut self.scan.results);
+        let previous_type = self.scan.scan_type;
+        match scanner.first_scan_any(&mut self.scan, criteria, &self.options) {
+            Ok(count) => {
+                self.history
+                    .record(&before, previous_type, &self.scan, criteria);
+                Ok(count)
+            }
+            Err(e) => {
+                self.scan.results = before;
+                Err(e)
+            }
+        }
+    }
+
+    /// Refine the session's results and record the step
+    pub fn next_scan<B: MemoryBackend>(
+        &mut self,
+        scanner: &ValueScanner<'_, B>,
+        criteria: &ScanCriteria,
+    ) -> MemoryResult<usize> {
+        self.check_process(scanner)?;
+        let results = scanner.refine(&self.scan, criteria)?;
+        let before = std::mem::take(&mut self.scan.results);
+        let previous_type = self.scan.scan_type;
+        self.scan.scan_type = criteria.scan_type;
+        self.scan.add_results(results);
+        self.history
+            .record(&before, previous_type, &self.scan, criteria);
+        Ok(self.scan.results.len())
+    }
+
+    /// Revert the latest recorded scan
+    pub fn undo(&mut self) -> Option<HistoryEntry> {
+        self.history.undo(&mut self.scan)
+    }
+
+    /// Reapply the most recently undone scan
+    pub fn redo(&mut self) -> Option<HistoryEntry> {
+        self.history.redo(&mut self.scan)
+    }
+}
+
+/// Handle to a session; lock it for the duration of a scan
+pub type SharedSession = Arc<Mutex<ManagedSession>>;
+
+/// Summary of a session as listed by [`SessionManager::list`]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SessionInfo {
+    pub id: String,
+    pub pid: u32,
+    pub value_type: ValueType,
+    pub scan_type: ScanType,
+    pub result_count: usize,
+    pub scan_count: u32,
+    /// Seconds since the session was last fetched
+    pub idle_secs: u64,
+}
+
+struct Entry {
+    /// Creation order, taken from the id counter
+    order: u64,
+    session: SharedSession,
+    last_used: Instant,
+}
+
+/// Owner of all scan sessions, keyed by id
+pub struct SessionManager {
+    sessions: Mutex<HashMap<String, Entry>>,
+    ttl: Duration,
+    next_id: AtomicU64,
+}
+
+impl SessionManager {
+    /// Create a manager that evicts sessions idle for longer than `ttl`
+    pub fn new(ttl: Duration) -> Self {
+        SessionManager {
+            sessions: Mutex::new(HashMap::new()),
+            ttl,
+            next_id: AtomicU64::new(1),
+        }
+    }
+
+    /// Create a manager using the configured session TTL
+    pub fn with_config(config: &ScannerConfig) -> Self {
+        Self::new(Duration::from_secs(config.session_ttl_secs))
+    }
+
+    /// Idle time after which sessions are evicted
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// Start a session with no results and return its id
+    pub fn create(&self, pid: u32, value_type: ValueType, options: ScanOptions) -> String {
+        let (order, id) = self.next_id();
+        let session = ManagedSession {
+            pid,
+            options,
+            scan: ScanSession::new(id.clone(), ScanType::Unknown, value_type),
+            history: ScanHistory::default(),
+        };
+        self.insert(order, id.clone(), session);
+        id
+    }
+
+    /// Take ownership of an existing session, such as one loaded from disk
+    ///
+    /// The session is given a fresh id, which is returned.
+    pub fn adopt(&self, mut session: ManagedSession) -> String {
+        let (order, id) = self.next_id();
+        session.scan.id = id.clone();
+        self.insert(order, id.clone(), session);
+        id
+    }
+
+    /// Fetch a session, marking it as used
+    pub fn get(&self, id: &str) -> MemoryResult<SharedSession> {
+        let mut sessions = self.sessions.lock().unwrap();
+        let entry = sessions
+            .get_mut(id)
+            .ok_or_else(|| MemoryError::SessionNotFound(id.to_string()))?;
+        entry.last_used = Instant::now();
+        Ok(Arc::clone(&entry.session))
+    }
+
+    /// Copy a session and its results under a new id
+    ///
+    /// Waits for any scan running on the source session to finish.
+    pub fn clone_session(&self, id: &str) -> MemoryResult<String> {
+        let source = self.get(id)?;
+        let copy = source.lock().unwrap().clone();
+        Ok(self.adopt(copy))
+    }
+
+    /// Remove a session
+    pub fn delete(&self, id: &str) -> MemoryResult<()> {
+        self.sessions
+            .lock()
+            .unwrap()
+            .remove(id)
+            .map(|_| ())
+            .ok_or_else(|| MemoryError::SessionNotFound(id.to_string()))
+    }
+
+    /// Summaries of all sessions, oldest first
+    pub fn list(&self) -> Vec<SessionInfo> {
+        // Release the map before locking sessions so a long scan only delays this call
+        let mut entries: Vec<(u64, String, SharedSession, Instant)> = self
+            .sessions
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(id, e)| (e.order, id.clone(), Arc::clone(&e.session), e.last_used))
+            .collect();
+        entries.sort_by_key(|(order, ..)| *order);
+
+        entries
+            .into_iter()
+            .map(|(_, id, session, last_used)| {
+                let session = session.lock().unwrap();
+                SessionInfo {
+                    id,
+                    pid: session.pid,
+                    value_type: session.value_type(),
+                    scan_type: session.scan.scan_type,
+                    result_count: session.scan.results.len(),
+                    scan_count: session.scan.scan_count,
+                    idle_secs: last_used.elapsed().as_secs(),
+                }
+            })
+            .collect()
+    }
+
+    /// Number of live sessions
+    pub fn len(&self) -> usize {
+        self.sessions.lock().unwrap().len()
+    }
+
+    /// Whether no sessions are live
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Remove sessions idle for longer than the TTL, returning their ids
+    ///
+    /// Sessions still held by a caller are kept.
+    pub fn evict_idle(&self) -> Vec<String> {
+        self.evict_idle_at(Instant::now())
+    }
+
+    fn evict_idle_at(&self, now: Instant) -> Vec<String> {
+        let mut evicted = Vec::new();
+        self.sessions.lock().unwrap().retain(|id, entry| {
+            let idle = now.saturating_duration_since(entry.last_used) > self.ttl;
+            let expired = idle && Arc::strong_count(&entry.session) == 1;
+            if expired {
+                evicted.push(id.clone());
+            }
+            !expired
+        });
+        evicted
+    }
+
+    fn insert(&self, order: u64, id: String, session: ManagedSession) {
+        self.evict_idle();
+        self.sessions.lock().unwrap().insert(
+            id,
+            Entry {
+                order,
+                session: Arc::new(Mutex::new(session)),
+                last_used: Instant::now(),
+            },
+        );
+    }
+
+    fn next_id(&self) -> (u64, String) {
+        let order = self.next_id.fetch_add(1, Ordering::Relaxed);
+        (order, format!("session-{}", order))
+    }
+}
+
+impl Default for SessionManager {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(
+            default_config().scanner.session_ttl_secs,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::types::{Address, MemoryValue, ScanResult};
+    use crate::memory::backend::{SnapshotProcess, SnapshotRegion};
+    use std::thread;
+
+    #[test]
+    fn test_create_get_and_delete() {
+        let manager = SessionManager::default();
+        let id = manager.create(42, ValueType::I32, ScanOptions::default());
+
+        let session = manager.get(&id).unwrap();
+        assert_eq!(session.lock().unwrap().pid, 42);
+        assert_eq!(session.lock().unwrap().value_type(), ValueType::I32);
+        assert_eq!(session.lock().unwrap().scan.id, id);
+
+        manager.delete(&id).unwrap();
+        assert!(matches!(
+            manager.get(&id),
+            Err(MemoryError::SessionNotFound(_))
+        ));
+        assert!(manager.delete(&id).is_err());
+    }
+
+    #[test]
+    fn test_clone_copies_results() {
+        let manager = SessionManager::default();
+        let id = manager.create(1, ValueType::U8, ScanOptions::default());
+        manager
+            .get(&id)
+            .unwrap()
+            .lock()
+            .unwrap()
+            .scan
+            .add_results(vec![ScanResult::new(
+                Address::new(0x1000),
+                MemoryValue::U8(7),
+            )]);
+
+        let copy = manager.clone_session(&id).unwrap();
+        assert_ne!(copy, id);
+        manager
+            .get(&id)
+            .unwrap()
+            .lock()
+            .unwrap()
+            .scan
+            .results
+            .clear();
+
+        let listed = manager.list();
+        assert_eq!(listed.len(), 2);
+        let info = listed.iter().find(|i| i.id == copy).unwrap();
+        assert_eq!(info.result_count, 1);
+        assert_eq!(info.scan_count, 1);
+        assert!(manager.clone_session("missing").is_err());
+    }
+
+    #[test]
+    fn test_sessions_lock_independently() {
+        let manager = Arc::new(SessionManager::default());
+        let first = manager.create(1, ValueType::U32, ScanOptions::default());
+        let second = manager.create(1, ValueType::U32, ScanOptions::default());
+
+        // Holding one session must not block work on the other
+        let held = manager.get(&first).unwrap();
+        let _guard = held.lock().unwrap();
+        let worker = {
+            let manager = Arc::clone(&manager);
+            thread::spawn(move || {
+                manager
+                    .get(&second)
+                    .unwrap()
+                    .lock()
+                    .unwrap()
+                    .scan
+                    .scan_count += 1;
+            })
+        };
+        worker.join().unwrap();
+    }
+
+    #[test]
+    fn test_evicts_idle_sessions() {
+        let manager = SessionManager::new(Duration::from_secs(60));
+        let idle = manager.create(1, ValueType::U32, ScanOptions::default());
+        let busy = manager.create(1, ValueType::U32, ScanOptions::default());
+        let held = manager.get(&busy).unwrap();
+
+        assert!(manager.evict_idle().is_empty());
+        let evicted = manager.evict_idle_at(Instant::now() + Duration::from_secs(120));
+        assert_eq!(evicted, vec![idle]);
+        assert_eq!(manager.len(), 1);
+
+        drop(held);
+        manager.evict_idle_at(Instant::now() + Duration::from_secs(120));
+        assert!(manager.is_empty());
+    }
+
+    #[test]
+    fn test_scans_are_recorded_for_undo() {
+        let mut data = vec![0u8; 0x100];
+        data[0x10] = 5;
+        data[0x40] = 5;
+        let process = SnapshotProcess::with_region(7, 0x10000, data, 0x04);
+        let scanner = ValueScanner::new(&process);
+
+        let manager = SessionManager::default();
+        let id = manager.create(7, ValueType::U8, ScanOptions::default());
+        let session = manager.get(&id).unwrap();
+        let mut session = session.lock().unwrap();
+
+        let five = ScanCriteria::with_value(ScanType::Exact, MemoryValue::U8(5));
+        assert_eq!(session.first_scan(&scanner, &five).unwrap(), 2);
+        let six = ScanCriteria::with_value(ScanType::Exact, MemoryValue::U8(6));
+        assert_eq!(session.next_scan(&scanner, &six).unwrap(), 0);
+
+        // The wrong criterion wiped the candidates; undo brings them back
+        assert_eq!(session.undo().unwrap().value, Some(MemoryValue::U8(6)));
+        assert_eq!(session.scan.results.len(), 2);
+        assert_eq!(session.history.entries().len(), 1);
+        assert!(session.redo().is_some());
+        assert!(session.scan.results.is_empty());
+    }
+
+    #[test]
+    fn test_undo_keeps_every_type_at_an_address() {
+        let mut data = vec![0u8; 0x10];
+        data[..8].copy_from_slice(&100u64.to_le_bytes());
+        let process = SnapshotProcess::with_region(7, 0x10000, data, 0x04);
+        let scanner = ValueScanner::new(&process);
+
+        let manager = SessionManager::default();
+        let options = ScanOptions {
+            alignment: 8,
+            ..ScanOptions::default()
+        };
+        let id = manager.create(7, ValueType::I32, options);
+        let session = manager.get(&id).unwrap();
+        let mut session = session.lock().unwrap();
+
+        let hundred = ScanCriteria::with_value(ScanType::Exact, MemoryValue::I32(100));
+        assert_eq!(session.first_scan_any(&scanner, &hundred).unwrap(), 8);
+        let found = session.scan.results.clone();
+        let unchanged = ScanCriteria::new(ScanType::Unchanged);
+        assert_eq!(session.next_scan(&scanner, &unchanged).unwrap(), 8);
+        let refined = session.scan.results.clone();
+        let changed = ScanCriteria::new(ScanType::Changed);
+        assert_eq!(session.next_scan(&scanner, &changed).unwrap(), 0);
+
+        session.undo();
+        assert_eq!(session.scan.results, refined);
+        session.undo();
+        assert_eq!(session.scan.results, found);
+        session.redo();
+        assert_eq!(session.scan.results, refined);
+    }
+
+    #[test]
+    fn test_list_is_in_creation_order() {
+        let manager = SessionManager::default();
+        let ids: Vec<String> = (0..12)
+            .map(|_| manager.create(1, ValueType::U8, ScanOptions::default()))
+            .collect();
+        let listed: Vec<String> = manager.list().into_iter().map(|i| i.id).collect();
+        assert_eq!(listed, ids);
+        assert_eq!(listed[9], "session-10");
+    }
+
+    #[test]
+    fn test_scans_refuse_other_processes() {
+        let process = SnapshotProcess::with_region(8, 0x10000, vec![5; 0x10], 0x04);
+        let scanner = ValueScanner::new(&process);
+
+        let manager = SessionManager::default();
+        let id = manager.create(7, ValueType::U8, ScanOptions::default());
+        let session = manager.get(&id).unwrap();
+        let mut session = session.lock().unwrap();
+
+        let five = ScanCriteria::with_value(ScanType::Exact, MemoryValue::U8(5));
+        assert!(matches!(
+            session.first_scan(&scanner, &five),
+            Err(MemoryError::InvalidArgument(_))
+        ));
+        assert!(session.first_scan_any(&scanner, &five).is_err());
+        assert!(session.next_scan(&scanner, &five).is_err());
+        assert!(session.history.entries().is_empty());
+    }
+}
@@ -0,0 +1,173 @@
+//! Region snapshot storage that can live in memory or in a temp file
+//!
+//! Snapshots hold raw memory of the target process, so spill files are only
+//! readable by the current user and live in a private directory.
+
+use crate::core::types::MemoryResult;
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tempfile::TempDir;
+
+/// Distinguishes spill files created by this process
+static NEXT_SPILL_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// Create a directory for spill files inside `parent` that only the current user can open
+pub(crate) fn private_dir(parent: &Path) -> MemoryResult<TempDir> {
+    let mut builder = tempfile::Builder::new();
+    builder.prefix("memory-mcp-");
+    #[cfg(unix)]
+    let permissions = {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::Permissions::from_mode(0o700)
+    };
+    #[cfg(unix)]
+    builder.permissions(permissions);
+    Ok(builder.tempdir_in(parent)?)
+}
+
+/// Contents of one region as of the last scan
+#[derive(Debug)]
+pub(crate) enum SnapshotData {
+    Memory(Vec<u8>),
+    Spilled {
+        file: File,
+        path: PathBuf,
+        len: usize,
+    },
+}
+
+impl SnapshotData {
+    /// Create a zero-filled temp file of `len` bytes in `dir`
+    pub fn spill(dir: &Path, len: usize) -> MemoryResult<Self> {
+        let id = NEXT_SPILL_ID.fetch_add(1, Ordering::Relaxed);
+        let path = dir.join(format!("memory-mcp-{}-{}.snap", std::process::id(), id));
+        let mut options = OpenOptions::new();
+        options.read(true).write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let file = options.open(&path)?;
+        file.set_len(len as u64)?;
+
+        Ok(SnapshotData::Spilled { file, path, len })
+    }
+
+    /// Whether the bytes are held in a temp file
+    pub fn is_spilled(&self) -> bool {
+        matches!(self, SnapshotData::Spilled { .. })
+    }
+
+    /// Length of the snapshot in bytes
+    pub fn len(&self) -> usize {
+        match self {
+            SnapshotData::Memory(data) => data.len(),
+            SnapshotData::Spilled { len, .. } => *len,
+        }
+    }
+
+    /// Bytes of process memory used by this snapshot
+    pub fn memory_usage(&self) -> usize {
+        match self {
+            SnapshotData::Memory(data) => data.len(),
+            SnapshotData::Spilled { .. } => 0,
+        }
+    }
+
+    /// Fill `buffer` with the bytes starting at `offset`
+    pub fn read_at(&self, offset: usize, buffer: &mut [u8]) -> MemoryResult<()> {
+        match self {
+            SnapshotData::Memory(data) => {
+                buffer.copy_from_slice(&data[offset..offset + buffer.len()]);
+            }
+            SnapshotData::Spilled { file, .. } => {
+                let mut file = file;
+                file.seek(SeekFrom::Start(offset as u64))?;
+                file.read_exact(buffer)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Replace the bytes starting at `offset` with `data`
+    pub fn write_at(&mut self, offset: usize, data: &[u8]) -> MemoryResult<()> {
+        match self {
+            SnapshotData::Memory(current) => {
+                current[offset..offset + data.len()].copy_from_slice(data);
+            }
+            SnapshotData::Spilled { file, .. } => {
+                file.seek(SeekFrom::Start(offset as u64))?;
+                file.write_all(data)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Drop for SnapshotData {
+    fn drop(&mut self) {
+        if let SnapshotData::Spilled { path, .. } = self {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg_attr(miri, ignore = "File system operations not supported under Miri")]
+    fn test_spill_round_trip_and_cleanup() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut data = SnapshotData::spill(dir.path(), 8).unwrap();
+        assert!(data.is_spilled());
+        assert_eq!(data.len(), 8);
+        assert_eq!(data.memory_usage(), 0);
+
+        data.write_at(2, &[1, 2, 3, 4]).unwrap();
+        let mut buffer = [0xFF; 8];
+        data.read_at(0, &mut buffer).unwrap();
+        assert_eq!(buffer, [0, 0, 1, 2, 3, 4, 0, 0]);
+
+        let mut middle = [0; 2];
+        data.read_at(3, &mut middle).unwrap();
+        assert_eq!(middle, [2, 3]);
+
+        drop(data);
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    #[cfg_attr(miri, ignore = "File system operations not supported under Miri")]
+    fn test_spill_files_are_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let parent = tempfile::tempdir().unwrap();
+        let dir = private_dir(parent.path()).unwrap();
+        let data = SnapshotData::spill(dir.path(), 4).unwrap();
+        let SnapshotData::Spilled { path, .. } = &data else {
+            panic!("Expected a spilled snapshot");
+        };
+
+        let mode = |path: &Path| std::fs::metadata(path).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode(dir.path()), 0o700);
+        assert_eq!(mode(path), 0o600);
+    }
+
+    #[test]
+    fn test_memory_snapshot() {
+        let mut data = SnapshotData::Memory(vec![9; 16]);
+        assert!(!data.is_spilled());
+        assert_eq!(data.memory_usage(), 16);
+
+        data.write_at(4, &[1; 4]).unwrap();
+        let mut buffer = [0; 6];
+        data.read_at(3, &mut buffer).unwrap();
+        assert_eq!(buffer, [9, 1, 1, 1, 1, 9]);
+    }
+}
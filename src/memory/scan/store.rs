@@ -0,0 +1,439 @@
+//! Compact candidate storage for scans with many matches
+//!
+//! Instead of one [`ScanResult`] per address, the store keeps a snapshot of
+//! each scanned region plus a bitmap of the aligned slots that still match.
+//! An unknown-initial-value scan over gigabytes of memory then costs about
+//! the size of the memory itself, and snapshots beyond the configured limit
+//! are spilled to temp files. Regions are read and compared one chunk at a
+//! time, so a spilled region never has to fit in memory.
+
+use super::bitmap::Bitmap;
+use super::spill::{private_dir, SnapshotData};
+use super::{align_up, ScanCriteria};
+use crate::config::{default_config, ScannerConfig};
+use crate::core::types::{
+    Address, MemoryError, MemoryResult, MemoryValue, ProcessArchitecture, ScanResult,
+    ValueEncoding, ValueType,
+};
+use crate::memory::backend::MemoryBackend;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+/// Limits for a [`CandidateStore`]
+#[derive(Debug, Clone)]
+pub struct StoreConfig {
+    /// Bytes of snapshots kept in memory before new ones are spilled
+    pub memory_limit: usize,
+    /// Candidate count at or below which results are materialized
+    pub materialize_threshold: usize,
+    /// Bytes of a region read at a time
+    pub chunk_size: usize,
+    /// Directory in which a private directory for spill files is created
+    pub spill_dir: PathBuf,
+}
+
+impl StoreConfig {
+    /// Build store limits from the scanner configuration
+    pub fn from_config(config: &ScannerConfig) -> Self {
+        StoreConfig {
+            memory_limit: config.snapshot_memory_limit,
+            materialize_threshold: config.materialize_threshold,
+            chunk_size: config.chunk_size,
+            spill_dir: std::env::temp_dir(),
+        }
+    }
+}
+
+impl Default for StoreConfig {
+    fn default() -> Self {
+        let defaults = default_config().scanner;
+        StoreConfig {
+            memory_limit: defaults.snapshot_memory_limit,
+            materialize_threshold: defaults.materialize_threshold,
+            chunk_size: defaults.chunk_size,
+            spill_dir: std::env::temp_dir(),
+        }
+    }
+}
+
+/// Snapshot and surviving slots of one region
+#[derive(Debug)]
+struct RegionCandidates {
+    base: Address,
+    data: SnapshotData,
+    bitmap: Bitmap,
+}
+
+/// Region snapshots plus bitmaps of surviving candidates
+#[derive(Debug)]
+pub struct CandidateStore {
+    value_type: ValueType,
+    encoding: ValueEncoding,
+    architecture: ProcessArchitecture,
+    width: usize,
+    alignment: usize,
+    config: StoreConfig,
+    regions: Vec<RegionCandidates>,
+    /// Created on the first spill; declared last so spill files are closed first
+    spill_dir: Option<TempDir>,
+}
+
+impl CandidateStore {
+    /// Create an empty store for a fixed-size value type
+    pub fn new(value_type: ValueType, alignment: usize, config: StoreConfig) -> MemoryResult<Self> {
+        let width = value_type.size().ok_or_else(|| {
+            MemoryError::InvalidValueType(format!(
+                "Compact scans need a fixed-size value type, got {:?}",
+                value_type
+            ))
+        })?;
+
+        Ok(CandidateStore {
+            value_type,
+            encoding: ValueEncoding::default(),
+            architecture: ProcessArchitecture::Unknown,
+            width,
+            alignment: alignment.max(1),
+            config,
+            regions: Vec::new(),
+            spill_dir: None,
+        })
+    }
+
+    /// Decode candidates with `encoding` instead of plain little-endian
+    pub fn with_encoding(mut self, encoding: ValueEncoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    /// Size pointers for a target of `architecture` instead of the host
+    pub fn with_architecture(mut self, architecture: ProcessArchitecture) -> Self {
+        if let Some(width) = self.value_type.size_for(architecture) {
+            self.width = width;
+        }
+        self.architecture = architecture;
+        self
+    }
+
+    /// Value type the candidates are decoded as
+    pub fn value_type(&self) -> ValueType {
+        self.value_type
+    }
+
+    /// Number of surviving candidates
+    pub fn candidate_count(&self) -> usize {
+        self.regions.iter().map(|r| r.bitmap.count_ones()).sum()
+    }
+
+    /// Bytes of snapshots and bitmaps held in memory
+    pub fn memory_usage(&self) -> usize {
+        self.regions
+            .iter()
+            .map(|r| r.data.memory_usage() + r.bitmap.memory_usage())
+            .sum()
+    }
+
+    /// Number of regions whose snapshot lives in a temp file
+    pub fn spilled_regions(&self) -> usize {
+        self.regions.iter().filter(|r| r.data.is_spilled()).count()
+    }
+
+    /// Whether few enough candidates remain to list them individually
+    pub fn should_materialize(&self) -> bool {
+        self.candidate_count() <= self.config.materialize_threshold
+    }
+
+    /// Offset of the first aligned slot and the number of slots in a region
+    fn slots(&self, base: Address, len: usize) -> (usize, usize) {
+        let first = align_up(base.as_usize(), self.alignment) - base.as_usize();
+        let count = match len.checked_sub(first + self.width) {
+            Some(span) => span / self.alignment + 1,
+            None => 0,
+        };
+        (first, count)
+    }
+
+    /// Slot ranges read together, each spanning about `chunk_size` bytes
+    fn chunks(&self, count: usize) -> impl Iterator<Item = Range<usize>> {
+        let per_chunk = (self.config.chunk_size / self.alignment).max(1);
+        (0..count)
+            .step_by(per_chunk)
+            .map(move |start| start..(start + per_chunk).min(count))
+    }
+
+    /// Region offsets of the bytes holding `slots`
+    fn byte_range(&self, first: usize, slots: &Range<usize>) -> Range<usize> {
+        first + slots.start * self.alignment..first + (slots.end - 1) * self.alignment + self.width
+    }
+
+    /// Decode the slot at `index` within bytes starting at a slot
+    fn decode(&self, bytes: &[u8], index: usize) -> Option<MemoryValue> {
+        let offset = index * self.alignment;
+        self.encoding.decode_for(
+            &bytes[offset..offset + self.width],
+            self.value_type,
+            self.architecture,
+        )
+    }
+
+    /// Private directory for spill files, created on first use
+    fn spill_dir(&mut self) -> MemoryResult<&Path> {
+        if self.spill_dir.is_none() {
+            self.spill_dir = Some(private_dir(&self.config.spill_dir)?);
+        }
+        Ok(self.spill_dir.as_ref().map(TempDir::path).unwrap())
+    }
+
+    /// Read a region, keeping the slots for which `keep` returns true
+    ///
+    /// Regions that cannot be read are skipped. A region that would take
+    /// snapshots past the memory limit is streamed to a spill file.
+    pub(crate) fn add_region<B: MemoryBackend>(
+        &mut self,
+        backend: &B,
+        base: Address,
+        len: usize,
+        keep: impl Fn(&MemoryValue) -> bool,
+    ) -> MemoryResult<()> {
+        let (first, count) = self.slots(base, len);
+        if count == 0 {
+            return Ok(());
+        }
+
+        let mut data = if self.memory_usage() + len > self.config.memory_limit {
+            SnapshotData::spill(self.spill_dir()?, len)?
+        } else {
+            SnapshotData::Memory(vec![0; len])
+        };
+        let mut bitmap = Bitmap::new(count);
+        let mut buffer = Vec::new();
+
+        for slots in self.chunks(count) {
+            let bytes = self.byte_range(first, &slots);
+            buffer.resize(bytes.len(), 0);
+            if backend
+                .read(Address::new(base.as_usize() + bytes.start), &mut buffer)
+                .is_err()
+            {
+                return Ok(());
+            }
+            for slot in slots.clone() {
+                if self
+                    .decode(&buffer, slot - slots.start)
+                    .is_some_and(|v| keep(&v))
+                {
+                    bitmap.set(slot);
+                }
+            }
+            data.write_at(bytes.start, &buffer)?;
+        }
+
+        if bitmap.count_ones() == 0 {
+            return Ok(());
+        }
+        self.regions.push(RegionCandidates { base, data, bitmap });
+        Ok(())
+    }
+
+    /// Re-read every region and keep only candidates matching `criteria`
+    ///
+    /// Chunks without candidates are not read. Regions that can no longer be
+    /// read are dropped. Returns the number of surviving candidates.
+    pub fn refine<B: MemoryBackend>(
+        &mut self,
+        backend: &B,
+        criteria: &ScanCriteria,
+    ) -> MemoryResult<usize> {
+        criteria.validate(self.value_type, true)?;
+
+        let mut regions = std::mem::take(&mut self.regions);
+        let mut current = Vec::new();
+        let mut previous = Vec::new();
+        for region in regions.iter_mut() {
+            let (first, count) = self.slots(region.base, region.data.len());
+
+            for slots in self.chunks(count) {
+                let candidates: Vec<usize> =
+                    slots.clone().filter(|&s| region.bitmap.get(s)).collect();
+                if candidates.is_empty() {
+                    continue;
+                }
+
+                let bytes = self.byte_range(first, &slots);
+                current.resize(bytes.len(), 0);
+                let address = Address::new(region.base.as_usize() + bytes.start);
+                if backend.read(address, &mut current).is_err() {
+                    region.bitmap = Bitmap::new(0);
+                    break;
+                }
+                previous.resize(bytes.len(), 0);
+                region.data.read_at(bytes.start, &mut previous)?;
+
+                for slot in candidates {
+                    let index = slot - slots.start;
+                    let keep = match (self.decode(&current, index), self.decode(&previous, index)) {
+                        (Some(now), Some(old)) => {
+                            criteria.matches(self.value_type, &now, Some(&old))
+                        }
+                        _ => false,
+                    };
+                    if !keep {
+                        region.bitmap.clear(slot);
+                    }
+                }
+                region.data.write_at(bytes.start, &current)?;
+            }
+        }
+
+        regions.retain(|r| r.bitmap.count_ones() > 0);
+        self.regions = regions;
+        Ok(self.candidate_count())
+    }
+
+    /// List every surviving candidate with its last recorded value
+    pub fn materialize(&self) -> MemoryResult<Vec<ScanResult>> {
+        let mut results = Vec::new();
+        let mut buffer = vec![0u8; self.width];
+
+        for region in &self.regions {
+            let (first, _) = self.slots(region.base, region.data.len());
+
+            for slot in region.bitmap.iter_ones() {
+                let offset = first + slot * self.alignment;
+                region.data.read_at(offset, &mut buffer)?;
+                if let Some(value) = self.decode(&buffer, 0) {
+                    let address = region.base.as_usize() + offset;
+                    results.push(ScanResult::new(Address::new(address), value));
+                }
+            }
+        }
+
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::types::ScanType;
+    use crate::memory::backend::{SnapshotProcess, SnapshotRegion};
+
+    const PAGE_READWRITE: u32 = 0x04;
+
+    fn config(memory_limit: usize, dir: &std::path::Path) -> StoreConfig {
+        StoreConfig {
+            memory_limit,
+            materialize_threshold: 2,
+            chunk_size: 16,
+            spill_dir: dir.to_path_buf(),
+        }
+    }
+
+    #[test]
+    fn test_store_rejects_variable_types() {
+        assert!(CandidateStore::new(ValueType::Bytes, 1, StoreConfig::default()).is_err());
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "File system operations not supported under Miri")]
+    fn test_store_spills_and_refines() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut process = SnapshotProcess::with_region(1, 0x10000, vec![0; 64], PAGE_READWRITE);
+        process
+            .add_region(SnapshotRegion::new(
+                Address::new(0x20000),
+                vec![0; 64],
+                PAGE_READWRITE,
+            ))
+            .unwrap();
+
+        // The first region fits in memory, the second spills
+        let mut store = CandidateStore::new(ValueType::U32, 4, config(80, dir.path())).unwrap();
+        for base in [0x10000, 0x20000] {
+            store
+                .add_region(&process, Address::new(base), 64, |_| true)
+                .unwrap();
+        }
+        assert_eq!(store.candidate_count(), 32);
+        assert_eq!(store.spilled_regions(), 1);
+        assert!(!store.should_materialize());
+
+        // Spill files go in a private directory, not straight into the spill dir
+        let entries: Vec<_> = std::fs::read_dir(dir.path())
+            .unwrap()
+            .map(|e| e.unwrap().path())
+            .collect();
+        assert_eq!(entries.len(), 1);
+        assert!(entries[0].is_dir());
+
+        process
+            .write(Address::new(0x10008), &7u32.to_le_bytes())
+            .unwrap();
+        process
+            .write(Address::new(0x20010), &9u32.to_le_bytes())
+            .unwrap();
+
+        let changed = ScanCriteria::new(ScanType::Changed);
+        assert_eq!(store.refine(&process, &changed).unwrap(), 2);
+        assert!(store.should_materialize());
+
+        let results = store.materialize().unwrap();
+        assert_eq!(results[0].address, Address::new(0x10008));
+        assert_eq!(results[0].value, MemoryValue::U32(7));
+        assert_eq!(results[1].address, Address::new(0x20010));
+        assert_eq!(results[1].value, MemoryValue::U32(9));
+
+        // Dropping the store removes its spill files
+        drop(store);
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_store_slots_respect_alignment() {
+        let process = SnapshotProcess::with_region(1, 0x1002, vec![0; 10], PAGE_READWRITE);
+        let mut store =
+            CandidateStore::new(ValueType::U16, 4, config(usize::MAX, &std::env::temp_dir()))
+                .unwrap();
+
+        // Base 0x1002 is unaligned: slots start at 0x1004 and 0x1008
+        store
+            .add_region(&process, Address::new(0x1002), 10, |_| true)
+            .unwrap();
+        let addresses: Vec<usize> = store
+            .materialize()
+            .unwrap()
+            .iter()
+            .map(|r| r.address.as_usize())
+            .collect();
+        assert_eq!(addresses, vec![0x1004, 0x1008]);
+    }
+
+    #[test]
+    fn test_store_matches_values_across_chunks() {
+        // Unaligned u32 values straddling the 16-byte chunk boundaries
+        let mut data = vec![0u8; 64];
+        data[14..18].copy_from_slice(&0xAABBCCDDu32.to_le_bytes());
+        data[45..49].copy_from_slice(&0xAABBCCDDu32.to_le_bytes());
+        let process = SnapshotProcess::with_region(1, 0x4000, data, PAGE_READWRITE);
+
+        let mut store =
+            CandidateStore::new(ValueType::U32, 1, config(usize::MAX, &std::env::temp_dir()))
+                .unwrap();
+        store
+            .add_region(&process, Address::new(0x4000), 64, |v| {
+                *v == MemoryValue::U32(0xAABBCCDD)
+            })
+            .unwrap();
+        assert_eq!(store.candidate_count(), 2);
+
+        process
+            .write(Address::new(0x402D), &0x11223344u32.to_le_bytes())
+            .unwrap();
+        let unchanged = ScanCriteria::new(ScanType::Unchanged);
+        assert_eq!(store.refine(&process, &unchanged).unwrap(), 1);
+        let results = store.materialize().unwrap();
+        assert_eq!(results[0].address, Address::new(0x400E));
+        assert_eq!(results[0].value, MemoryValue::U32(0xAABBCCDD));
+    }
+}
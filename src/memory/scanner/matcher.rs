@@ -0,0 +1,202 @@
+//! Signature matching over memory buffers
+//!
+//! The longest run of exact bytes before the first jump is used as an anchor
+//! and searched with Boyer–Moore–Horspool; each anchor hit is then verified
+//! against the whole signature.
+
+use super::signature::{Signature, SignatureToken};
+
+/// Compiled signature ready for searching
+#[derive(Debug, Clone)]
+pub(crate) struct PatternMatcher {
+    signature: Signature,
+    /// Exact bytes of the anchor
+    solid: Vec<u8>,
+    /// Offset of the anchor within a match
+    anchor: usize,
+    /// Horspool shift for each possible last byte of the anchor window
+    skip: [usize; 256],
+}
+
+impl PatternMatcher {
+    /// Compile a signature
+    pub fn new(signature: Signature) -> Self {
+        // Only the part before the first jump sits at a fixed offset
+        let exact: Vec<Option<u8>> = signature
+            .tokens()
+            .iter()
+            .take_while(|t| !matches!(t, SignatureToken::Jump { .. }))
+            .map(|t| match t {
+                SignatureToken::Byte(b) if b.mask == 0xFF => Some(b.value),
+                _ => None,
+            })
+            .collect();
+        let (anchor, anchor_len) = longest_solid_run(&exact);
+        let solid: Vec<u8> = exact[anchor..anchor + anchor_len]
+            .iter()
+            .flatten()
+            .copied()
+            .collect();
+
+        let mut skip = [anchor_len.max(1); 256];
+        for (i, &byte) in solid.iter().enumerate().take(anchor_len.saturating_sub(1)) {
+            skip[byte as usize] = anchor_len - 1 - i;
+        }
+
+        PatternMatcher {
+            signature,
+            solid,
+            anchor,
+            skip,
+        }
+    }
+
+    /// Longest possible match in bytes
+    pub fn max_len(&self) -> usize {
+        self.signature.max_len()
+    }
+
+    /// Check whether the signature matches at the start of `data`
+    pub fn matches_at(&self, data: &[u8]) -> bool {
+        self.signature.match_at(data).is_some()
+    }
+
+    /// Offsets in `data` where the signature matches, with their capture offsets
+    ///
+    /// Offsets are ascending. Only matches starting before `limit` are
+    /// reported, which lets chunked scans read past their end without
+    /// reporting matches twice.
+    pub fn find_iter<'d>(
+        &'d self,
+        data: &'d [u8],
+        limit: usize,
+    ) -> impl Iterator<Item = (usize, Option<usize>)> + 'd {
+        let min_len = self.signature.min_len();
+        // One past the last permitted start offset
+        let end = if min_len == 0 {
+            0
+        } else {
+            limit.min((data.len() + 1).saturating_sub(min_len))
+        };
+        let anchor_len = self.solid.len();
+        let mut pos = 0;
+
+        std::iter::from_fn(move || {
+            while pos < end {
+                let candidate = pos;
+                let window = &data[pos + self.anchor..pos + self.anchor + anchor_len];
+                pos += match window.last() {
+                    Some(&last) => self.skip[last as usize],
+                    None => 1,
+                };
+
+                if window == self.solid.as_slice() {
+                    if let Some(capture) = self.signature.match_at(&data[candidate..]) {
+                        return Some((candidate, capture));
+                    }
+                }
+            }
+            None
+        })
+    }
+}
+
+/// Offset and length of the longest run of exact bytes
+fn longest_solid_run(exact: &[Option<u8>]) -> (usize, usize) {
+    let mut best = (0, 0);
+    let mut start = 0;
+
+    for (i, byte) in exact.iter().enumerate() {
+        if byte.is_none() {
+            start = i + 1;
+        } else if i + 1 - start > best.1 {
+            best = (start, i + 1 - start);
+        }
+    }
+
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::scanner::signature::SignatureByte;
+
+    fn matcher(pattern: &[u8], mask: &[bool]) -> PatternMatcher {
+        PatternMatcher::new(Signature::from_bytes(pattern.iter().zip(mask).map(
+            |(&value, &solid)| SignatureByte {
+                value,
+                mask: if solid { 0xFF } else { 0 },
+            },
+        )))
+    }
+
+    fn naive(data: &[u8], pattern: &[u8], mask: &[bool]) -> Vec<usize> {
+        (0..(data.len() + 1).saturating_sub(pattern.len()))
+            .filter(|&i| {
+                pattern
+                    .iter()
+                    .zip(mask)
+                    .enumerate()
+                    .all(|(j, (&p, &m))| !m || data[i + j] == p)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_longest_solid_run() {
+        assert_eq!(
+            longest_solid_run(&[Some(1), None, Some(2), Some(3), None]),
+            (2, 2)
+        );
+        assert_eq!(longest_solid_run(&[None, None]), (0, 0));
+        assert_eq!(longest_solid_run(&[Some(1), Some(2), Some(3)]), (0, 3));
+    }
+
+    #[test]
+    fn test_find_iter_matches_naive_search() {
+        let data: Vec<u8> = (0..4096u32).map(|i| (i * 7 % 13) as u8).collect();
+        let cases: [(&[u8], &[bool]); 4] = [
+            (&[1, 8, 2], &[true, true, true]),
+            (&[1, 0, 2, 9], &[true, false, true, true]),
+            (&[0, 0], &[false, false]),
+            (&[7, 7, 7], &[true, true, true]),
+        ];
+
+        for (pattern, mask) in cases {
+            let found: Vec<usize> = matcher(pattern, mask)
+                .find_iter(&data, usize::MAX)
+                .map(|(offset, _)| offset)
+                .collect();
+            assert_eq!(found, naive(&data, pattern, mask));
+        }
+    }
+
+    #[test]
+    fn test_find_iter_respects_limit() {
+        let data = [0xAA; 10];
+        let matcher = matcher(&[0xAA, 0xAA], &[true, true]);
+        let offsets: Vec<usize> = matcher.find_iter(&data, 3).map(|(o, _)| o).collect();
+        assert_eq!(offsets, vec![0, 1, 2]);
+        assert_eq!(matcher.find_iter(&data[..1], usize::MAX).count(), 0);
+    }
+
+    #[test]
+    fn test_find_iter_with_jump_and_capture() {
+        let signature = Signature::parse("48 8B (05|0D) & ?? [1-3] C3").unwrap();
+        let matcher = PatternMatcher::new(signature);
+        let data = [
+            0x00, 0x48, 0x8B, 0x05, 0x11, 0x22, 0xC3, 0x48, 0x8B, 0x0D, 0x33, 0xC3,
+        ];
+        let found: Vec<_> = matcher.find_iter(&data, usize::MAX).collect();
+        assert_eq!(found, vec![(1, Some(3))]);
+    }
+
+    #[test]
+    fn test_matches_at_honors_mask() {
+        let matcher = matcher(&[1, 0, 3], &[true, false, true]);
+        assert!(matcher.matches_at(&[1, 9, 3]));
+        assert!(!matcher.matches_at(&[1, 9, 4]));
+        assert!(!matcher.matches_at(&[1, 9]));
+    }
+}
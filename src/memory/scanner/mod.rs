@@ -0,0 +1,561 @@
+//! Memory scanning functionality for pattern matching
+
+mod matcher;
+mod parallel;
+pub mod rip;
+pub mod signature;
+
+use matcher::PatternMatcher;
+pub use rip::RipRelative;
+pub use signature::{Signature, SignatureByte, SignatureToken};
+
+use crate::config::{default_config, ScannerConfig};
+use crate::core::types::{Address, MemoryError, MemoryResult};
+use crate::memory::backend::MemoryBackend;
+use crate::memory::regions::RegionState;
+use crate::process::ProcessHandle;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Pattern for memory scanning
+#[derive(Debug, Clone)]
+pub enum ScanPattern {
+    /// Exact byte pattern
+    Exact(Vec<u8>),
+    /// Pattern with wildcards (None = wildcard)
+    Masked(Vec<Option<u8>>),
+    /// String pattern
+    String(String),
+    /// Wide string pattern (UTF-16)
+    WideString(String),
+    /// Signature with nibble wildcards, jumps, alternatives or a capture
+    Signature(Signature),
+}
+
+impl ScanPattern {
+    /// Create pattern from hex string (e.g., "48 8B ?? ?? 89")
+    ///
+    /// Plain bytes and `??` produce a [`ScanPattern::Masked`]; anything using
+    /// the extended [`signature`] grammar produces a [`ScanPattern::Signature`].
+    pub fn from_hex_string(pattern: &str) -> MemoryResult<Self> {
+        let signature = Signature::parse(pattern)?;
+        if !signature.is_simple() {
+            return Ok(ScanPattern::Signature(signature));
+        }
+
+        let bytes = signature
+            .tokens()
+            .iter()
+            .map(|token| match token {
+                SignatureToken::Byte(b) if b.mask == 0xFF => Some(b.value),
+                _ => None,
+            })
+            .collect();
+        Ok(ScanPattern::Masked(bytes))
+    }
+
+    /// Get the pattern length
+    ///
+    /// For signatures with jumps this is the longest possible match.
+    pub fn len(&self) -> usize {
+        match self {
+            ScanPattern::Exact(v) => v.len(),
+            ScanPattern::Masked(v) => v.len(),
+            ScanPattern::String(s) => s.len() + 1, // +1 for null terminator
+            ScanPattern::WideString(s) => (s.len() + 1) * 2, // UTF-16 + null
+            ScanPattern::Signature(s) => s.max_len(),
+        }
+    }
+
+    /// Check if pattern is empty
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Compile the pattern for matching
+    fn matcher(&self) -> PatternMatcher {
+        PatternMatcher::new(self.to_signature())
+    }
+
+    /// Convert to a signature for matching
+    fn to_signature(&self) -> Signature {
+        let exact = |bytes: &[u8]| {
+            Signature::from_bytes(
+                bytes
+                    .iter()
+                    .map(|&value| SignatureByte { value, mask: 0xFF }),
+            )
+        };
+
+        match self {
+            ScanPattern::Exact(bytes) => exact(bytes),
+            ScanPattern::Masked(pattern) => {
+                Signature::from_bytes(pattern.iter().map(|byte| SignatureByte {
+                    value: byte.unwrap_or(0),
+                    mask: if byte.is_some() { 0xFF } else { 0 },
+                }))
+            }
+            ScanPattern::String(s) => {
+                let mut bytes = s.as_bytes().to_vec();
+                bytes.push(0); // Add null terminator
+                exact(&bytes)
+            }
+            ScanPattern::WideString(s) => {
+                let wide: Vec<u16> = s.encode_utf16().chain(std::iter::once(0)).collect();
+                let bytes: Vec<u8> = wide.iter().flat_map(|&w| w.to_le_bytes()).collect();
+                exact(&bytes)
+            }
+            ScanPattern::Signature(signature) => signature.clone(),
+        }
+    }
+}
+
+/// A pattern match and the location of its captured operand
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PatternMatch {
+    /// Address where the match starts
+    pub address: Address,
+    /// Offset of the `&` capture marker from `address`, if the pattern has one
+    pub capture_offset: Option<usize>,
+}
+
+impl PatternMatch {
+    /// Address of the captured operand
+    pub fn capture_address(&self) -> Option<Address> {
+        self.capture_offset
+            .map(|offset| Address::new(self.address.as_usize() + offset))
+    }
+}
+
+/// Options for memory scanning
+///
+/// Missing fields deserialize to their defaults.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScanOptions {
+    /// Start address for scanning
+    pub start_address: Option<Address>,
+    /// End address for scanning
+    pub end_address: Option<Address>,
+    /// Scan only executable regions
+    pub executable_only: bool,
+    /// Scan only writable regions
+    pub writable_only: bool,
+    /// Split regions into chunks and scan them on multiple threads
+    pub parallel: bool,
+    /// Alignment for scan (1, 2, 4, 8)
+    pub alignment: usize,
+    /// Maximum results to return
+    pub max_results: Option<usize>,
+}
+
+impl Default for ScanOptions {
+    fn default() -> Self {
+        ScanOptions {
+            start_address: None,
+            end_address: None,
+            executable_only: false,
+            writable_only: false,
+            parallel: true,
+            alignment: 1,
+            max_results: Some(1000),
+        }
+    }
+}
+
+/// Memory scanner for pattern matching
+pub struct MemoryScanner<'a, B: MemoryBackend = ProcessHandle> {
+    handle: &'a B,
+    max_threads: usize,
+    chunk_size: usize,
+}
+
+impl<'a, B: MemoryBackend> MemoryScanner<'a, B> {
+    /// Create a new memory scanner with the default scanner configuration
+    pub fn new(handle: &'a B) -> Self {
+        let defaults = default_config().scanner;
+        MemoryScanner {
+            handle,
+            max_threads: defaults.max_threads,
+            chunk_size: defaults.chunk_size,
+        }
+    }
+
+    /// Create a memory scanner using the thread count and chunk size from `config`
+    pub fn with_config(handle: &'a B, config: &ScannerConfig) -> Self {
+        MemoryScanner {
+            handle,
+            max_threads: config.max_threads.max(1),
+            chunk_size: config.chunk_size.max(1),
+        }
+    }
+
+    /// Scan memory for a pattern
+    ///
+    /// With `options.parallel` set, regions are split into overlapping chunks
+    /// scanned on up to `max_threads` threads, and the scan stops early once
+    /// `max_results` matches are found.
+    pub fn scan(&self, pattern: &ScanPattern, options: ScanOptions) -> MemoryResult<Vec<Address>> {
+        let matches = self.scan_matches(pattern, options)?;
+        Ok(matches.into_iter().map(|m| m.address).collect())
+    }
+
+    /// Scan memory for a pattern, reporting capture offsets with each match
+    pub fn scan_matches(
+        &self,
+        pattern: &ScanPattern,
+        options: ScanOptions,
+    ) -> MemoryResult<Vec<PatternMatch>> {
+        let matcher = pattern.matcher();
+        let regions = self.enumerate_regions(&options)?;
+
+        if options.parallel {
+            self.scan_parallel(&regions, &matcher, &options)
+        } else {
+            self.scan_sequential(&regions, &matcher, &options)
+        }
+    }
+
+    /// Scan a specific memory region
+    pub fn scan_region(
+        &self,
+        start: Address,
+        size: usize,
+        pattern: &ScanPattern,
+        options: &ScanOptions,
+    ) -> MemoryResult<Vec<Address>> {
+        let matches = self.scan_region_with(start, size, &pattern.matcher(), options)?;
+        Ok(matches.into_iter().map(|m| m.address).collect())
+    }
+
+    /// Find all occurrences of a value
+    pub fn find_value<T: Copy>(
+        &self,
+        value: T,
+        options: ScanOptions,
+    ) -> MemoryResult<Vec<Address>> {
+        let size = std::mem::size_of::<T>();
+        let ptr = &value as *const T as *const u8;
+        let pattern_bytes = unsafe { std::slice::from_raw_parts(ptr, size).to_vec() };
+
+        self.scan(&ScanPattern::Exact(pattern_bytes), options)
+    }
+
+    /// Compare scan - find changed values
+    ///
+    /// Values are compared as raw byte slices; use
+    /// [`ValueScanner`](crate::memory::ValueScanner) for numeric comparisons.
+    pub fn compare_scan(
+        &self,
+        previous: &HashMap<Address, Vec<u8>>,
+        comparison: ComparisonType,
+    ) -> MemoryResult<Vec<Address>> {
+        let mut results = Vec::new();
+
+        for (addr, old_value) in previous {
+            let mut new_value = vec![0u8; old_value.len()];
+
+            if self.handle.read(*addr, &mut new_value).is_ok()
+                && self.compare_values(old_value, &new_value, &comparison)
+            {
+                results.push(*addr);
+            }
+        }
+
+        Ok(results)
+    }
+
+    fn enumerate_regions(&self, options: &ScanOptions) -> MemoryResult<Vec<(Address, usize)>> {
+        scan_regions(self.handle, options)
+    }
+
+    fn scan_region_with(
+        &self,
+        start: Address,
+        size: usize,
+        matcher: &PatternMatcher,
+        options: &ScanOptions,
+    ) -> MemoryResult<Vec<PatternMatch>> {
+        let mut buffer = vec![0u8; size];
+        self.handle.read(start, &mut buffer)?;
+
+        let limit = options.max_results.unwrap_or(usize::MAX);
+        Ok(
+            collect_matches(matcher, &buffer, start, size, options.alignment)
+                .take(limit)
+                .collect(),
+        )
+    }
+
+    fn scan_sequential(
+        &self,
+        regions: &[(Address, usize)],
+        matcher: &PatternMatcher,
+        options: &ScanOptions,
+    ) -> MemoryResult<Vec<PatternMatch>> {
+        let mut all_results = Vec::new();
+
+        for (addr, size) in regions {
+            let results = self.scan_region_with(*addr, *size, matcher, options)?;
+            all_results.extend(results);
+
+            if let Some(max) = options.max_results {
+                if all_results.len() >= max {
+                    all_results.truncate(max);
+                    break;
+                }
+            }
+        }
+
+        Ok(all_results)
+    }
+
+    fn scan_parallel(
+        &self,
+        regions: &[(Address, usize)],
+        matcher: &PatternMatcher,
+        options: &ScanOptions,
+    ) -> MemoryResult<Vec<PatternMatch>> {
+        let overlap = matcher.max_len().saturating_sub(1);
+        let chunks = parallel::plan_chunks(regions, self.chunk_size, overlap);
+
+        parallel::ParallelScan {
+            matcher,
+            alignment: options.alignment,
+            max_results: options.max_results,
+            threads: self.max_threads,
+        }
+        .run(self.handle, &chunks)
+    }
+
+    fn compare_values(&self, old: &[u8], new: &[u8], comparison: &ComparisonType) -> bool {
+        match comparison {
+            ComparisonType::Equal => old == new,
+            ComparisonType::NotEqual => old != new,
+            ComparisonType::Greater => new > old,
+            ComparisonType::Less => new < old,
+            ComparisonType::GreaterOrEqual => new >= old,
+            ComparisonType::LessOrEqual => new <= old,
+        }
+    }
+}
+
+/// Aligned matches in `buffer` starting within its first `limit` bytes
+pub(crate) fn collect_matches<'d>(
+    matcher: &'d PatternMatcher,
+    buffer: &'d [u8],
+    start: Address,
+    limit: usize,
+    alignment: usize,
+) -> impl Iterator<Item = PatternMatch> + 'd {
+    let alignment = alignment.max(1);
+    matcher
+        .find_iter(buffer, limit)
+        .map(move |(offset, capture_offset)| PatternMatch {
+            address: Address::new(start.as_usize() + offset),
+            capture_offset,
+        })
+        .filter(move |m| m.address.as_usize() % alignment == 0)
+}
+
+/// Committed, readable regions selected by `options`, clipped to its address range
+pub(crate) fn scan_regions<B: MemoryBackend>(
+    backend: &B,
+    options: &ScanOptions,
+) -> MemoryResult<Vec<(Address, usize)>> {
+    let start = options.start_address.unwrap_or(Address::new(0x10000));
+    let end = options.end_address.unwrap_or(Address::new(0x7FFFFFFFFFFF));
+
+    let regions = backend
+        .enumerate_regions()?
+        .into_iter()
+        .filter(|region| region.state == RegionState::Committed && region.is_readable())
+        .filter(|region| !options.executable_only || region.is_executable())
+        .filter(|region| !options.writable_only || region.is_writable())
+        .filter_map(|region| {
+            // Clip the region to the requested address range
+            let base = region.base_address.max(start);
+            let limit = region.end_address().min(end);
+            (base < limit).then(|| (base, limit.as_usize() - base.as_usize()))
+        })
+        .collect();
+
+    Ok(regions)
+}
+
+/// Comparison type for compare scans
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ComparisonType {
+    Equal,
+    NotEqual,
+    Greater,
+    Less,
+    GreaterOrEqual,
+    LessOrEqual,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pattern_from_hex_string() {
+        let pattern = ScanPattern::from_hex_string("48 8B ?? ?? 89").unwrap();
+        match pattern {
+            ScanPattern::Masked(bytes) => {
+                assert_eq!(bytes.len(), 5);
+                assert_eq!(bytes[0], Some(0x48));
+                assert_eq!(bytes[1], Some(0x8B));
+                assert_eq!(bytes[2], None);
+                assert_eq!(bytes[3], None);
+                assert_eq!(bytes[4], Some(0x89));
+            }
+            _ => panic!("Wrong pattern type"),
+        }
+
+        assert!(ScanPattern::from_hex_string("").is_err());
+        assert!(ScanPattern::from_hex_string("GG").is_err());
+    }
+
+    #[test]
+    fn test_pattern_length() {
+        let exact = ScanPattern::Exact(vec![1, 2, 3]);
+        assert_eq!(exact.len(), 3);
+
+        let masked = ScanPattern::Masked(vec![Some(1), None, Some(3)]);
+        assert_eq!(masked.len(), 3);
+
+        let string = ScanPattern::String("test".to_string());
+        assert_eq!(string.len(), 5); // "test" + null
+
+        let wide = ScanPattern::WideString("test".to_string());
+        assert_eq!(wide.len(), 10); // "test" in UTF-16 + null = 5 * 2
+    }
+
+    #[test]
+    fn test_scan_options_default() {
+        let opts = ScanOptions::default();
+        assert_eq!(opts.alignment, 1);
+        assert!(opts.parallel);
+        assert_eq!(opts.max_results, Some(1000));
+        assert!(!opts.executable_only);
+        assert!(!opts.writable_only);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "FFI not supported in Miri")]
+    fn test_comparison_types() {
+        let handle = ProcessHandle::open_for_read(std::process::id()).unwrap();
+        let scanner = MemoryScanner::new(&handle);
+
+        assert!(scanner.compare_values(&[1, 2], &[1, 2], &ComparisonType::Equal));
+        assert!(!scanner.compare_values(&[1, 2], &[1, 3], &ComparisonType::Equal));
+        assert!(scanner.compare_values(&[1, 2], &[1, 3], &ComparisonType::NotEqual));
+        // Note: compare_values checks if NEW < OLD for Less
+        assert!(scanner.compare_values(&[1, 3], &[1, 2], &ComparisonType::Less));
+        assert!(scanner.compare_values(&[1, 2], &[1, 3], &ComparisonType::Greater));
+    }
+
+    #[test]
+    fn test_pattern_matching() {
+        let matcher = ScanPattern::from_hex_string("48 8B ?? ?? 89")
+            .unwrap()
+            .matcher();
+        assert!(matcher.matches_at(&[0x48, 0x8B, 0xC1, 0xFF, 0x89]));
+        assert!(!matcher.matches_at(&[0x48, 0x8C, 0xC1, 0xFF, 0x89]));
+    }
+
+    #[test]
+    fn test_sequential_scan_honors_wildcards() {
+        let mut data = vec![0u8; 256];
+        data[0x10..0x15].copy_from_slice(&[0x48, 0x8B, 0xC1, 0xFF, 0x89]);
+        data[0x90..0x95].copy_from_slice(&[0x48, 0x8B, 0x05, 0x10, 0x89]);
+        let process = snapshot(data);
+        let pattern = ScanPattern::from_hex_string("48 8B ?? ?? 89").unwrap();
+
+        let options = ScanOptions {
+            parallel: false,
+            ..ScanOptions::default()
+        };
+        let results = MemoryScanner::new(&process)
+            .scan(&pattern, options)
+            .unwrap();
+        assert_eq!(results, vec![Address::new(0x10010), Address::new(0x10090)]);
+    }
+
+    fn snapshot(data: Vec<u8>) -> crate::memory::SnapshotProcess {
+        crate::memory::SnapshotProcess::with_region(1, 0x10000, data, 0x04)
+    }
+
+    fn small_chunks(threads: usize) -> ScannerConfig {
+        ScannerConfig {
+            max_threads: threads,
+            chunk_size: 16,
+            ..crate::config::Config::default().scanner
+        }
+    }
+
+    #[test]
+    fn test_parallel_scan_finds_matches_across_chunks() {
+        let mut data = vec![0u8; 256];
+        // Straddles the boundary between the chunks at 0x10 and 0x20
+        data[0x1E..0x22].copy_from_slice(&[0xDE, 0xAD, 0xBE, 0xEF]);
+        data[0x80..0x84].copy_from_slice(&[0xDE, 0xAD, 0x00, 0xEF]);
+        let process = snapshot(data);
+        let pattern = ScanPattern::from_hex_string("DE AD ?? EF").unwrap();
+
+        for threads in [1, 4] {
+            let scanner = MemoryScanner::with_config(&process, &small_chunks(threads));
+            let results = scanner.scan(&pattern, ScanOptions::default()).unwrap();
+            assert_eq!(results, vec![Address::new(0x1001E), Address::new(0x10080)]);
+        }
+    }
+
+    #[test]
+    fn test_parallel_scan_respects_alignment_and_max_results() {
+        let process = snapshot(vec![0xAA; 256]);
+        let scanner = MemoryScanner::with_config(&process, &small_chunks(4));
+        let pattern = ScanPattern::Exact(vec![0xAA, 0xAA]);
+
+        let aligned = ScanOptions {
+            alignment: 8,
+            max_results: None,
+            ..ScanOptions::default()
+        };
+        let results = scanner.scan(&pattern, aligned).unwrap();
+        assert_eq!(results.len(), 32);
+        assert!(results.iter().all(|a| a.as_usize() % 8 == 0));
+
+        let limited = ScanOptions {
+            max_results: Some(5),
+            ..ScanOptions::default()
+        };
+        assert_eq!(scanner.scan(&pattern, limited).unwrap().len(), 5);
+    }
+
+    #[test]
+    fn test_signature_scan_reports_captures() {
+        let mut data = vec![0u8; 256];
+        // Crosses the chunk boundary at 0x20; the shortest jump wins
+        data[0x1C..0x25].copy_from_slice(&[0x48, 0x8D, 0x0D, 0x90, 0x90, 0x78, 0x56, 0x34, 0x12]);
+        data[0x60..0x68].copy_from_slice(&[0x48, 0x8D, 0x05, 0x90, 0x11, 0x22, 0x33, 0x44]);
+        let process = snapshot(data);
+        let pattern = ScanPattern::from_hex_string("48 8D (05|0D) [1-2] & ?? ?? ?? ??").unwrap();
+        assert!(matches!(pattern, ScanPattern::Signature(_)));
+        assert_eq!(pattern.len(), 9);
+
+        for parallel in [false, true] {
+            let scanner = MemoryScanner::with_config(&process, &small_chunks(4));
+            let options = ScanOptions {
+                parallel,
+                ..ScanOptions::default()
+            };
+            let matches = scanner.scan_matches(&pattern, options).unwrap();
+            assert_eq!(matches.len(), 2);
+            assert_eq!(matches[0].address, Address::new(0x1001C));
+            assert_eq!(matches[0].capture_offset, Some(4));
+            assert_eq!(matches[0].capture_address(), Some(Address::new(0x10020)));
+            assert_eq!(matches[1].capture_offset, Some(4));
+        }
+    }
+}
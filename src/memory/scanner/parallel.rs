@@ -0,0 +1,183 @@
+//! Chunked parallel pattern scanning
+//!
+//! Regions are split into `chunk_size` pieces that are scanned on a rayon
+//! pool. Each chunk reads `max_len - 1` extra bytes past its end so a
+//! match straddling the boundary is found, but only matches starting inside
+//! the chunk's own range are reported, so nothing is counted twice.
+//! Pools are built once per thread count and shared by every later scan.
+
+use super::matcher::PatternMatcher;
+use super::{collect_matches, PatternMatch};
+use crate::core::types::{Address, MemoryError, MemoryResult};
+use crate::memory::backend::MemoryBackend;
+use rayon::prelude::*;
+use rayon::ThreadPool;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+lazy_static::lazy_static! {
+    static ref SCAN_POOLS: Mutex<HashMap<usize, Arc<ThreadPool>>> = Mutex::new(HashMap::new());
+}
+
+/// Shared pool with `threads` scan threads, built on first use
+fn scan_pool(threads: usize) -> MemoryResult<Arc<ThreadPool>> {
+    let mut pools = SCAN_POOLS.lock().unwrap();
+    if let Some(pool) = pools.get(&threads) {
+        return Ok(pool.clone());
+    }
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .thread_name(|index| format!("memory-scan-{}", index))
+        .build()
+        .map_err(|e| MemoryError::Unknown(format!("Failed to start scan threads: {}", e)))?;
+    let pool = Arc::new(pool);
+    pools.insert(threads, pool.clone());
+    Ok(pool)
+}
+
+/// One unit of parallel work
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct Chunk {
+    /// First address owned by the chunk
+    pub start: Address,
+    /// Bytes owned by the chunk; matches must start inside this range
+    pub len: usize,
+    /// Bytes to read, including the overlap into the next chunk
+    pub read_len: usize,
+}
+
+/// Split regions into chunks overlapping by `overlap` bytes
+pub(super) fn plan_chunks(
+    regions: &[(Address, usize)],
+    chunk_size: usize,
+    overlap: usize,
+) -> Vec<Chunk> {
+    let chunk_size = chunk_size.max(1);
+    let mut chunks = Vec::new();
+
+    for &(base, size) in regions {
+        let mut offset = 0;
+        while offset < size {
+            let len = chunk_size.min(size - offset);
+            chunks.push(Chunk {
+                start: Address::new(base.as_usize() + offset),
+                len,
+                read_len: (len + overlap).min(size - offset),
+            });
+            offset += len;
+        }
+    }
+
+    chunks
+}
+
+/// Pattern scan settings shared by every chunk
+pub(super) struct ParallelScan<'p> {
+    pub matcher: &'p PatternMatcher,
+    pub alignment: usize,
+    pub max_results: Option<usize>,
+    pub threads: usize,
+}
+
+impl<'p> ParallelScan<'p> {
+    /// Scan all chunks, stopping early once `max_results` matches are found
+    ///
+    /// Chunks that cannot be read are skipped. Results are sorted; when the
+    /// scan is cut short they are not necessarily the lowest matching addresses.
+    pub fn run<B: MemoryBackend>(
+        &self,
+        backend: &B,
+        chunks: &[Chunk],
+    ) -> MemoryResult<Vec<PatternMatch>> {
+        if self.matcher.max_len() == 0 {
+            return Ok(Vec::new());
+        }
+
+        let pool = scan_pool(self.threads.max(1))?;
+
+        let found = AtomicUsize::new(0);
+        let cancelled = AtomicBool::new(false);
+
+        let mut results: Vec<PatternMatch> = pool.install(|| {
+            chunks
+                .par_iter()
+                .flat_map_iter(|chunk| {
+                    if cancelled.load(Ordering::Relaxed) {
+                        return Vec::new();
+                    }
+
+                    let matches = self.scan_chunk(backend, chunk);
+                    if let Some(max) = self.max_results {
+                        let total = found.fetch_add(matches.len(), Ordering::Relaxed);
+                        if total + matches.len() >= max {
+                            cancelled.store(true, Ordering::Relaxed);
+                        }
+                    }
+                    matches
+                })
+                .collect()
+        });
+
+        results.sort_by_key(|m| m.address);
+        if let Some(max) = self.max_results {
+            results.truncate(max);
+        }
+        Ok(results)
+    }
+
+    fn scan_chunk<B: MemoryBackend>(&self, backend: &B, chunk: &Chunk) -> Vec<PatternMatch> {
+        let mut buffer = vec![0u8; chunk.read_len];
+        if backend.read(chunk.start, &mut buffer).is_err() {
+            return Vec::new();
+        }
+
+        collect_matches(
+            self.matcher,
+            &buffer,
+            chunk.start,
+            chunk.len,
+            self.alignment,
+        )
+        .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plan_chunks_overlap() {
+        let chunks = plan_chunks(&[(Address::new(0x1000), 40)], 16, 3);
+        assert_eq!(
+            chunks,
+            vec![
+                Chunk {
+                    start: Address::new(0x1000),
+                    len: 16,
+                    read_len: 19
+                },
+                Chunk {
+                    start: Address::new(0x1010),
+                    len: 16,
+                    read_len: 19
+                },
+                Chunk {
+                    start: Address::new(0x1020),
+                    len: 8,
+                    read_len: 8
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_scan_pool_is_reused() {
+        let first = scan_pool(3).unwrap();
+        let second = scan_pool(3).unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+        assert_eq!(first.current_num_threads(), 3);
+        assert!(!Arc::ptr_eq(&first, &scan_pool(2).unwrap()));
+    }
+}
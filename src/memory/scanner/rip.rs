@@ -0,0 +1,148 @@
+//! RIP-relative operand resolution for x86-64 signature matches
+//!
+//! An instruction such as `48 8B 05 xx xx xx xx` (`mov rax, [rip+disp32]`)
+//! addresses a global relative to the end of the instruction. Resolving the
+//! displacement of a signature match gives an address that stays valid when
+//! the surrounding code moves between builds.
+
+use crate::core::types::{Address, MemoryError, MemoryResult};
+use crate::memory::backend::MemoryBackend;
+use crate::memory::reader::MemoryReader;
+
+/// Layout of a RIP-relative instruction found by a signature scan
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RipRelative {
+    /// Offset of the 32-bit displacement from the start of the instruction
+    pub displacement_offset: usize,
+    /// Total instruction length in bytes
+    pub instruction_length: usize,
+    /// Read a pointer at the resolved address and return that instead
+    pub dereference: bool,
+}
+
+impl RipRelative {
+    /// Describe an instruction whose displacement sits at `displacement_offset`
+    pub fn new(displacement_offset: usize, instruction_length: usize) -> Self {
+        RipRelative {
+            displacement_offset,
+            instruction_length,
+            dereference: false,
+        }
+    }
+
+    /// Follow the resolved address through one pointer
+    pub fn with_dereference(mut self) -> Self {
+        self.dereference = true;
+        self
+    }
+
+    /// Resolve the target of the instruction at `instruction`
+    pub fn resolve<B: MemoryBackend>(
+        &self,
+        reader: &MemoryReader<'_, B>,
+        instruction: Address,
+    ) -> MemoryResult<Address> {
+        if self.displacement_offset + 4 > self.instruction_length {
+            return Err(MemoryError::InvalidPattern(format!(
+                "Displacement at offset {} does not fit in a {}-byte instruction",
+                self.displacement_offset, self.instruction_length
+            )));
+        }
+
+        let field = instruction
+            .as_usize()
+            .checked_add(self.displacement_offset)
+            .map(Address::new)
+            .ok_or_else(|| MemoryError::InvalidAddress(instruction.to_string()))?;
+        let displacement = i32::from_le_bytes(reader.read::<[u8; 4]>(field)?);
+
+        let target = instruction
+            .as_usize()
+            .checked_add(self.instruction_length)
+            .and_then(|next| next.checked_add_signed(displacement as isize))
+            .map(Address::new)
+            .ok_or_else(|| {
+                MemoryError::InvalidAddress(format!(
+                    "{} with displacement {:#x}",
+                    instruction, displacement
+                ))
+            })?;
+
+        if self.dereference {
+            // RIP-relative addressing only exists in 64-bit code
+            let pointer = u64::from_le_bytes(reader.read::<[u8; 8]>(target)?);
+            Ok(Address::new(pointer as usize))
+        } else {
+            Ok(target)
+        }
+    }
+
+    /// Resolve every match, keeping per-match failures
+    pub fn resolve_all<B: MemoryBackend>(
+        &self,
+        reader: &MemoryReader<'_, B>,
+        instructions: &[Address],
+    ) -> Vec<MemoryResult<Address>> {
+        instructions
+            .iter()
+            .map(|&instruction| self.resolve(reader, instruction))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::backend::{SnapshotProcess, SnapshotRegion};
+
+    const BASE: usize = 0x140000000;
+
+    fn process() -> SnapshotProcess {
+        let mut data = vec![0u8; 0x100];
+        // mov rax, [rip+0x79] at 0x10 targets 0x10 + 7 + 0x79 = 0x90
+        data[0x10..0x17].copy_from_slice(&[0x48, 0x8B, 0x05, 0x79, 0x00, 0x00, 0x00]);
+        // lea rcx, [rip-0x20] at 0x40 targets 0x40 + 7 - 0x20 = 0x27
+        data[0x40..0x47].copy_from_slice(&[0x48, 0x8D, 0x0D, 0xE0, 0xFF, 0xFF, 0xFF]);
+        data[0x90..0x98].copy_from_slice(&(BASE as u64 + 0xC0).to_le_bytes());
+
+        SnapshotProcess::with_region(1, BASE, data, 0x04)
+    }
+
+    #[test]
+    fn test_resolve_forward_and_backward() {
+        let process = process();
+        let reader = MemoryReader::new(&process);
+        let rip = RipRelative::new(3, 7);
+
+        let targets = rip.resolve_all(
+            &reader,
+            &[Address::new(BASE + 0x10), Address::new(BASE + 0x40)],
+        );
+        assert_eq!(targets[0].as_ref().unwrap(), &Address::new(BASE + 0x90));
+        assert_eq!(targets[1].as_ref().unwrap(), &Address::new(BASE + 0x27));
+    }
+
+    #[test]
+    fn test_resolve_with_dereference() {
+        let process = process();
+        let reader = MemoryReader::new(&process);
+        let target = RipRelative::new(3, 7)
+            .with_dereference()
+            .resolve(&reader, Address::new(BASE + 0x10))
+            .unwrap();
+        assert_eq!(target, Address::new(BASE + 0xC0));
+    }
+
+    #[test]
+    fn test_resolve_errors() {
+        let process = process();
+        let reader = MemoryReader::new(&process);
+        assert!(matches!(
+            RipRelative::new(5, 7).resolve(&reader, Address::new(BASE)),
+            Err(MemoryError::InvalidPattern(_))
+        ));
+        assert!(RipRelative::new(3, 7)
+            .resolve(&reader, Address::new(BASE + 0x1000))
+            .is_err());
+    }
+}
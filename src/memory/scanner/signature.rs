@@ -0,0 +1,436 @@
+//! Array-of-bytes signature grammar
+//!
+//! Tokens are separated by whitespace:
+//!
+//! - `48` — an exact byte
+//! - `??` or `?` — any byte
+//! - `4?` / `?F` — a byte with one fixed nibble
+//! - `[4-8]` or `[4]` — skip between 4 and 8 bytes (decimal)
+//! - `(74|75)` — any one of the listed bytes, which may use nibble wildcards
+//! - `&` — marks the start of the operand to capture
+//!
+//! For example `48 8B 05 & ?? ?? ?? ?? [0-4] (74|75) ?F` matches a RIP-relative
+//! load and captures the offset of its displacement.
+
+use crate::core::types::{MemoryError, MemoryResult};
+
+/// Largest total of the jumps in a signature, bounding backtracking
+const MAX_JUMP: usize = 4096;
+
+/// A byte compared under a bit mask
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SignatureByte {
+    /// Expected bits
+    pub value: u8,
+    /// Bits that must equal `value`; 0xFF for an exact byte, 0 for `??`
+    pub mask: u8,
+}
+
+impl SignatureByte {
+    /// Check whether `byte` matches
+    pub fn matches(&self, byte: u8) -> bool {
+        byte & self.mask == self.value & self.mask
+    }
+}
+
+/// One element of a signature
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignatureToken {
+    /// A single, possibly partially masked, byte
+    Byte(SignatureByte),
+    /// Any one of several bytes
+    Either(Vec<SignatureByte>),
+    /// Between `min` and `max` arbitrary bytes
+    Jump { min: usize, max: usize },
+}
+
+/// Parsed signature with an optional capture marker
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Signature {
+    tokens: Vec<SignatureToken>,
+    capture: Option<usize>,
+}
+
+impl Signature {
+    /// Build a signature matching `bytes` in sequence
+    pub fn from_bytes(bytes: impl IntoIterator<Item = SignatureByte>) -> Self {
+        Signature {
+            tokens: bytes.into_iter().map(SignatureToken::Byte).collect(),
+            capture: None,
+        }
+    }
+
+    /// Parse a signature string
+    pub fn parse(pattern: &str) -> MemoryResult<Self> {
+        let mut tokens = Vec::new();
+        let mut capture = None;
+        let mut jump_total = 0usize;
+
+        for (column, text) in split_tokens(pattern)? {
+            let invalid = |reason: &str| {
+                MemoryError::InvalidPattern(format!(
+                    "Invalid token '{}' at column {}: {}",
+                    text, column, reason
+                ))
+            };
+
+            if text == "&" {
+                if capture.is_some() {
+                    return Err(invalid("only one capture marker is allowed"));
+                }
+                capture = Some(tokens.len());
+            } else if let Some(inner) = text.strip_prefix('[') {
+                let inner = inner
+                    .strip_suffix(']')
+                    .ok_or_else(|| invalid("missing ']'"))?;
+                let (min, max) = parse_jump(inner)
+                    .ok_or_else(|| invalid("expected a jump like [4] or [4-8] with min <= max"))?;
+                jump_total = jump_total.saturating_add(max);
+                if jump_total > MAX_JUMP {
+                    return Err(invalid(&format!(
+                        "jumps are limited to {} bytes in total",
+                        MAX_JUMP
+                    )));
+                }
+                if tokens.is_empty() {
+                    return Err(invalid("a signature cannot start with a jump"));
+                }
+                tokens.push(SignatureToken::Jump { min, max });
+            } else if let Some(inner) = text.strip_prefix('(') {
+                let inner = inner
+                    .strip_suffix(')')
+                    .ok_or_else(|| invalid("missing ')'"))?;
+                let options = inner
+                    .split('|')
+                    .map(|option| parse_byte(option.trim()))
+                    .collect::<Option<Vec<_>>>()
+                    .ok_or_else(|| invalid("alternatives must be bytes separated by '|'"))?;
+                tokens.push(SignatureToken::Either(options));
+            } else {
+                let byte = parse_byte(text)
+                    .ok_or_else(|| invalid("expected two hex digits, '?' or '??'"))?;
+                tokens.push(SignatureToken::Byte(byte));
+            }
+        }
+
+        match tokens.last() {
+            None => Err(MemoryError::InvalidPattern("Empty pattern".to_string())),
+            Some(SignatureToken::Jump { .. }) => Err(MemoryError::InvalidPattern(
+                "A signature cannot end with a jump".to_string(),
+            )),
+            Some(_) => Ok(Signature { tokens, capture }),
+        }
+    }
+
+    /// Signature elements in order
+    pub fn tokens(&self) -> &[SignatureToken] {
+        &self.tokens
+    }
+
+    /// Index of the token the capture marker precedes
+    pub fn capture(&self) -> Option<usize> {
+        self.capture
+    }
+
+    /// Whether the signature uses only exact bytes and `??`, with no capture
+    pub fn is_simple(&self) -> bool {
+        self.capture.is_none()
+            && self.tokens.iter().all(
+                |token| matches!(token, SignatureToken::Byte(b) if b.mask == 0xFF || b.mask == 0),
+            )
+    }
+
+    /// Shortest possible match in bytes
+    pub fn min_len(&self) -> usize {
+        self.tokens.iter().map(|t| token_len(t).0).sum()
+    }
+
+    /// Longest possible match in bytes
+    pub fn max_len(&self) -> usize {
+        self.tokens.iter().map(|t| token_len(t).1).sum()
+    }
+
+    /// Match at the start of `data`, returning the capture offset if any
+    ///
+    /// Jumps are tried shortest first, so the capture offset is that of the
+    /// shortest match.
+    pub fn match_at(&self, data: &[u8]) -> Option<Option<usize>> {
+        self.match_from(data, 0, 0, None, &mut None)
+    }
+
+    /// Match tokens from `token` on at `pos`
+    ///
+    /// Whether the rest of the signature matches depends only on the token
+    /// and position, so `failed` records every jump target that did not
+    /// match and each is tried at most once.
+    fn match_from(
+        &self,
+        data: &[u8],
+        mut token: usize,
+        mut pos: usize,
+        mut capture: Option<usize>,
+        failed: &mut Option<FailedTargets>,
+    ) -> Option<Option<usize>> {
+        loop {
+            if self.capture == Some(token) {
+                capture = Some(pos);
+            }
+
+            let matched = match self.tokens.get(token) {
+                None => return Some(capture),
+                Some(SignatureToken::Jump { min, max }) => {
+                    // The signature ends with a byte, so nothing matches past the data
+                    let last = (pos + max).min(data.len().saturating_sub(1));
+                    let mut next = pos + min;
+                    while let Some(target) = failed
+                        .get_or_insert_with(|| {
+                            FailedTargets::new(self.tokens.len(), self.max_len())
+                        })
+                        .next_untried(token + 1, next, last)
+                    {
+                        let found = self.match_from(data, token + 1, target, capture, failed);
+                        if found.is_some() {
+                            return found;
+                        }
+                        if let Some(failed) = failed {
+                            failed.insert(token + 1, target);
+                        }
+                        next = target + 1;
+                    }
+                    return None;
+                }
+                Some(SignatureToken::Byte(expected)) => expected.matches(*data.get(pos)?),
+                Some(SignatureToken::Either(options)) => {
+                    let byte = *data.get(pos)?;
+                    options.iter().any(|o| o.matches(byte))
+                }
+            };
+
+            if !matched {
+                return None;
+            }
+            token += 1;
+            pos += 1;
+        }
+    }
+}
+
+/// Jump targets known not to match, one bit per offset for each token
+struct FailedTargets {
+    rows: Vec<Vec<u64>>,
+    words: usize,
+}
+
+impl FailedTargets {
+    /// Room for offsets up to `max_len` at each of `tokens` tokens
+    fn new(tokens: usize, max_len: usize) -> Self {
+        FailedTargets {
+            rows: vec![Vec::new(); tokens],
+            words: max_len / 64 + 1,
+        }
+    }
+
+    fn row(&mut self, token: usize) -> &mut Vec<u64> {
+        let words = self.words;
+        let row = &mut self.rows[token];
+        if row.is_empty() {
+            row.resize(words, 0);
+        }
+        row
+    }
+
+    fn insert(&mut self, token: usize, pos: usize) {
+        self.row(token)[pos / 64] |= 1 << (pos % 64);
+    }
+
+    /// First offset in `from..=to` not yet known to fail at `token`
+    fn next_untried(&mut self, token: usize, from: usize, to: usize) -> Option<usize> {
+        let row = self.row(token);
+        let mut pos = from;
+        while pos <= to {
+            let free = !row[pos / 64] >> (pos % 64);
+            if free != 0 {
+                let found = pos + free.trailing_zeros() as usize;
+                return (found <= to).then_some(found);
+            }
+            pos = (pos / 64 + 1) * 64;
+        }
+        None
+    }
+}
+
+fn token_len(token: &SignatureToken) -> (usize, usize) {
+    match token {
+        SignatureToken::Jump { min, max } => (*min, *max),
+        _ => (1, 1),
+    }
+}
+
+/// Split on whitespace, keeping bracketed groups together, with 1-based columns
+fn split_tokens(pattern: &str) -> MemoryResult<Vec<(usize, &str)>> {
+    let mut tokens = Vec::new();
+    let mut start = None;
+    let mut closing = None;
+
+    for (i, c) in pattern.char_indices() {
+        match (start, closing) {
+            (_, Some(close)) if c == close => closing = None,
+            (_, Some(_)) => {}
+            (None, None) if c.is_whitespace() => {}
+            (None, None) => {
+                start = Some(i);
+                closing = group_end(c);
+            }
+            (Some(s), None) if c.is_whitespace() => {
+                tokens.push((s + 1, &pattern[s..i]));
+                start = None;
+            }
+            (Some(_), None) => closing = group_end(c),
+        }
+    }
+
+    if let Some(s) = start {
+        if closing.is_some() {
+            return Err(MemoryError::InvalidPattern(format!(
+                "Unclosed group '{}' at column {}",
+                &pattern[s..],
+                s + 1
+            )));
+        }
+        tokens.push((s + 1, &pattern[s..]));
+    }
+
+    Ok(tokens)
+}
+
+fn group_end(c: char) -> Option<char> {
+    match c {
+        '(' => Some(')'),
+        '[' => Some(']'),
+        _ => None,
+    }
+}
+
+fn parse_byte(text: &str) -> Option<SignatureByte> {
+    if text == "?" || text == "??" {
+        return Some(SignatureByte { value: 0, mask: 0 });
+    }
+
+    let digits: Vec<char> = text.chars().collect();
+    if digits.len() != 2 {
+        return None;
+    }
+
+    let mut value = 0;
+    let mut mask = 0;
+    for digit in digits {
+        value <<= 4;
+        mask <<= 4;
+        if digit != '?' {
+            value |= digit.to_digit(16)? as u8;
+            mask |= 0xF;
+        }
+    }
+    Some(SignatureByte { value, mask })
+}
+
+fn parse_jump(text: &str) -> Option<(usize, usize)> {
+    let (min, max) = match text.split_once('-') {
+        Some((min, max)) => (min.trim().parse().ok()?, max.trim().parse().ok()?),
+        None => {
+            let n = text.trim().parse().ok()?;
+            (n, n)
+        }
+    };
+    (min <= max).then_some((min, max))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn byte(value: u8, mask: u8) -> SignatureToken {
+        SignatureToken::Byte(SignatureByte { value, mask })
+    }
+
+    #[test]
+    fn test_parse_full_grammar() {
+        let signature = Signature::parse("48 4? ?F ?? [2-4] ( 74 | 7? ) & 05").unwrap();
+        assert_eq!(
+            signature.tokens(),
+            &[
+                byte(0x48, 0xFF),
+                byte(0x40, 0xF0),
+                byte(0x0F, 0x0F),
+                byte(0, 0),
+                SignatureToken::Jump { min: 2, max: 4 },
+                SignatureToken::Either(vec![
+                    SignatureByte {
+                        value: 0x74,
+                        mask: 0xFF
+                    },
+                    SignatureByte {
+                        value: 0x70,
+                        mask: 0xF0
+                    },
+                ]),
+                byte(0x05, 0xFF),
+            ]
+        );
+        assert_eq!(signature.capture(), Some(6));
+        assert_eq!(signature.min_len(), 8);
+        assert_eq!(signature.max_len(), 10);
+        assert!(!signature.is_simple());
+        assert!(Signature::parse("48 ?? 8B").unwrap().is_simple());
+    }
+
+    #[test]
+    fn test_parse_errors_point_at_token() {
+        let cases = [
+            ("48 4G", "'4G' at column 4"),
+            ("48 [8-4] 90", "'[8-4]' at column 4"),
+            ("48 (74|) 90", "'(74|)' at column 4"),
+            ("& 48 &", "'&' at column 6"),
+            ("[4] 90", "cannot start with a jump"),
+            ("90 [4]", "cannot end with a jump"),
+            ("90 (74|75", "Unclosed group '(74|75' at column 4"),
+            (
+                "90 [0-4000] 90 [0-100] 90",
+                "limited to 4096 bytes in total",
+            ),
+            ("   ", "Empty pattern"),
+        ];
+
+        for (pattern, expected) in cases {
+            let message = Signature::parse(pattern).unwrap_err().to_string();
+            assert!(message.contains(expected), "{}: {}", pattern, message);
+        }
+    }
+
+    #[test]
+    fn test_match_with_jump_and_capture() {
+        let signature = Signature::parse("E8 [0-2] (74|75) & ?? C3").unwrap();
+        assert_eq!(signature.match_at(&[0xE8, 0x75, 0x10, 0xC3]), Some(Some(2)));
+        assert_eq!(
+            signature.match_at(&[0xE8, 0x90, 0x90, 0x74, 0x10, 0xC3]),
+            Some(Some(4))
+        );
+        assert_eq!(signature.match_at(&[0xE8, 0x76, 0x10, 0xC3]), None);
+        assert_eq!(signature.match_at(&[0xE8, 0x74, 0x10]), None);
+    }
+
+    #[test]
+    fn test_many_jumps_match_quickly() {
+        // Without memoization this backtracks through 1025^4 jump combinations
+        let signature =
+            Signature::parse("00 [0-1024] 00 [0-1024] 00 [0-1024] 00 [0-1024] 01").unwrap();
+        let data = vec![0u8; 4096];
+        let start = std::time::Instant::now();
+        assert_eq!(signature.match_at(&data), None);
+        assert!(start.elapsed() < std::time::Duration::from_secs(10));
+
+        let mut data = data;
+        data[4000] = 1;
+        assert_eq!(signature.match_at(&data), Some(None));
+    }
+}
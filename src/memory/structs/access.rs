@@ -0,0 +1,377 @@
+//! Reading structures as JSON and accessing single fields by path
+
+use super::def::{FieldDef, FieldKind, StructDef, StructRegistry};
+use crate::core::types::{
+    Address, MemoryError, MemoryResult, MemoryValue, ProcessArchitecture, ValueType,
+};
+use crate::memory::backend::MemoryBackend;
+use crate::memory::policy::MemoryPolicy;
+use crate::memory::reader::SafeMemoryReader;
+use crate::memory::writer::{MemoryWrite, SafeMemoryWriter, WriteJournal};
+use crate::process::ProcessHandle;
+use serde_json::{Map, Value};
+
+/// Pointers followed by [`StructAccessor::read_struct`] unless configured otherwise
+pub const DEFAULT_MAX_DEPTH: usize = 4;
+
+/// Where a field path leads
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FieldLocation {
+    pub address: Address,
+    pub value_type: ValueType,
+    /// Byte or character length declared for variable-size values
+    pub length: Option<usize>,
+}
+
+/// Reads and writes structures described by a [`StructRegistry`]
+pub struct StructAccessor<'a, B: MemoryBackend = ProcessHandle> {
+    handle: &'a B,
+    reader: SafeMemoryReader<'a, B>,
+    writer: SafeMemoryWriter<'a, B>,
+    registry: &'a StructRegistry,
+    architecture: ProcessArchitecture,
+    max_depth: usize,
+}
+
+impl<'a, B: MemoryBackend> StructAccessor<'a, B> {
+    /// Create an accessor for the structures in `registry`
+    ///
+    /// Reads and writes follow the default [`MemoryPolicy`], without backups.
+    pub fn new(handle: &'a B, registry: &'a StructRegistry) -> Self {
+        let policy = MemoryPolicy::default();
+        let mut writer = SafeMemoryWriter::new(handle);
+        writer.set_policy(policy.clone());
+        StructAccessor {
+            handle,
+            reader: SafeMemoryReader::new(handle).with_policy(policy),
+            writer,
+            registry,
+            architecture: handle.architecture(),
+            max_depth: DEFAULT_MAX_DEPTH,
+        }
+    }
+
+    /// Read and write under `policy`, recording backups in `journal` if it asks for them
+    pub fn with_policy(mut self, policy: &MemoryPolicy, journal: &'a WriteJournal) -> Self {
+        self.reader = SafeMemoryReader::new(self.handle).with_policy(policy.clone());
+        self.writer = SafeMemoryWriter::with_policy(self.handle, policy, journal);
+        self
+    }
+
+    /// Set how many pointers deep `read_struct` follows
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Read the structure at `address` as a JSON object keyed by field name
+    ///
+    /// Pointer fields hold the structure they point to, `null` for null or
+    /// unreadable pointers, or the pointer as a hex string once the depth
+    /// limit is reached. Fields with a `count` become arrays.
+    pub fn read_struct(&self, address: Address, def: &StructDef) -> MemoryResult<Value> {
+        self.read_object(address, def, self.max_depth)
+    }
+
+    /// Resolve a path such as `player.inventory[3].count` from `def` at `address`
+    ///
+    /// A leading segment naming the structure itself is skipped. Pointer
+    /// fields along the path are dereferenced.
+    pub fn locate(
+        &self,
+        address: Address,
+        def: &StructDef,
+        path: &str,
+    ) -> MemoryResult<FieldLocation> {
+        let mut segments = parse_path(path)?;
+        if let Some((name, indices)) = segments.first() {
+            if indices.is_empty()
+                && def.field(name).is_none()
+                && name.eq_ignore_ascii_case(&def.name)
+            {
+                segments.remove(0);
+            }
+        }
+
+        let mut def = def;
+        let mut base = address;
+        let last = segments.len().saturating_sub(1);
+        for (i, (name, indices)) in segments.iter().enumerate() {
+            let field = def.field(name).ok_or_else(|| {
+                MemoryError::InvalidStruct(format!("'{}' has no field '{}'", def.name, name))
+            })?;
+            let mut indices = indices.iter().copied();
+            let mut address = offset(base, field.offset);
+            if let Some(count) = field.count {
+                let index = indices.next().ok_or_else(|| {
+                    MemoryError::InvalidStruct(format!("'{}' is an array and needs an index", name))
+                })?;
+                check_index(name, index, count)?;
+                let stride = self.registry.element_size(field, self.architecture)?;
+                address = offset(address, index * stride);
+            }
+
+            match field.kind()? {
+                FieldKind::Value(mut value_type) => {
+                    if let ValueType::Array { elem, len } = value_type {
+                        if let Some(index) = indices.next() {
+                            check_index(name, index, len)?;
+                            value_type = elem.value_type();
+                            let size = value_type.size_for(self.architecture).unwrap_or(0);
+                            address = offset(address, index * size);
+                        }
+                    }
+                    if indices.next().is_some() || i != last {
+                        return Err(MemoryError::InvalidStruct(format!(
+                            "path '{}' continues past value field '{}'",
+                            path, name
+                        )));
+                    }
+                    return Ok(FieldLocation {
+                        address,
+                        value_type,
+                        length: field.length,
+                    });
+                }
+                FieldKind::Struct { name, pointer } => {
+                    if indices.next().is_some() {
+                        return Err(MemoryError::InvalidStruct(format!(
+                            "'{}' is not an array",
+                            field.name
+                        )));
+                    }
+                    if pointer {
+                        address = self.read_pointer(address)?;
+                        if address.is_null() {
+                            return Err(MemoryError::pointer_chain_broken(
+                                i,
+                                format!("'{}' is a null pointer", field.name),
+                            ));
+                        }
+                    }
+                    def = self.registry.get(name)?;
+                    base = address;
+                }
+            }
+        }
+        Err(MemoryError::InvalidStruct(format!(
+            "path '{}' does not end at a value field",
+            path
+        )))
+    }
+
+    /// Read the single field at `path`
+    pub fn read_field(
+        &self,
+        address: Address,
+        def: &StructDef,
+        path: &str,
+    ) -> MemoryResult<MemoryValue> {
+        let location = self.locate(address, def, path)?;
+        self.reader
+            .read_value_len(location.address, location.value_type, location.length)
+    }
+
+    /// Write `value` to the single field at `path`
+    ///
+    /// The value must have the field's type and fit its declared length.
+    pub fn write_field(
+        &self,
+        address: Address,
+        def: &StructDef,
+        path: &str,
+        value: &MemoryValue,
+    ) -> MemoryResult<()> {
+        let location = self.locate(address, def, path)?;
+        if value.value_type() != location.value_type {
+            return Err(MemoryError::InvalidValueType(format!(
+                "'{}' holds {:?}, not {:?}",
+                path,
+                location.value_type,
+                value.value_type()
+            )));
+        }
+        if let Some(length) = location.length {
+            let (needed, capacity) = match value {
+                MemoryValue::String(s) => (s.len() + 1, length),
+                MemoryValue::WString(s) => (s.encode_utf16().count() + 1, length),
+                MemoryValue::Bytes(b) => (b.len(), length),
+                _ => (0, 0),
+            };
+            if needed > capacity {
+                return Err(MemoryError::InvalidValueType(format!(
+                    "'{}' holds at most {} units, the value needs {}",
+                    path, capacity, needed
+                )));
+            }
+        }
+        self.writer.write_value(location.address, value)
+    }
+
+    fn read_pointer(&self, address: Address) -> MemoryResult<Address> {
+        match self.reader.read_value(address, ValueType::Pointer)? {
+            MemoryValue::Pointer(pointer) => Ok(pointer),
+            other => Err(MemoryError::InvalidValueType(format!(
+                "Expected a pointer, read {}",
+                other
+            ))),
+        }
+    }
+
+    fn read_object(&self, address: Address, def: &StructDef, depth: usize) -> MemoryResult<Value> {
+        let mut object = Map::new();
+        for field in &def.fields {
+            let start = offset(address, field.offset);
+            let value = match field.count {
+                Some(count) => {
+                    let stride = self.registry.element_size(field, self.architecture)?;
+                    (0..count)
+                        .map(|i| self.read_element(offset(start, i * stride), field, depth))
+                        .collect::<MemoryResult<Vec<_>>>()
+                        .map(Value::Array)?
+                }
+                None => self.read_element(start, field, depth)?,
+            };
+            object.insert(field.name.clone(), value);
+        }
+        Ok(Value::Object(object))
+    }
+
+    fn read_element(
+        &self,
+        address: Address,
+        field: &FieldDef,
+        depth: usize,
+    ) -> MemoryResult<Value> {
+        match field.kind()? {
+            FieldKind::Value(value_type) => self
+                .reader
+                .read_value_len(address, value_type, field.length)
+                .map(|value| to_json(&value)),
+            FieldKind::Struct {
+                name,
+                pointer: false,
+            } => self.read_object(address, self.registry.get(name)?, depth),
+            FieldKind::Struct {
+                name,
+                pointer: true,
+            } => {
+                let pointer = self.read_pointer(address)?;
+                if pointer.is_null() {
+                    Ok(Value::Null)
+                } else if depth == 0 {
+                    Ok(Value::String(pointer.to_string()))
+                } else {
+                    let def = self.registry.get(name)?;
+                    Ok(self
+                        .read_object(pointer, def, depth - 1)
+                        .unwrap_or(Value::Null))
+                }
+            }
+        }
+    }
+}
+
+/// Plain JSON for a value: numbers, strings, booleans, and pointers as hex strings
+pub fn to_json(value: &MemoryValue) -> Value {
+    match value {
+        MemoryValue::I8(v) => Value::from(*v),
+        MemoryValue::I16(v) => Value::from(*v),
+        MemoryValue::I32(v) => Value::from(*v),
+        MemoryValue::I64(v) => Value::from(*v),
+        MemoryValue::U8(v) => Value::from(*v),
+        MemoryValue::U16(v) => Value::from(*v),
+        MemoryValue::U32(v) => Value::from(*v),
+        MemoryValue::U64(v) => Value::from(*v),
+        // Too wide for JSON numbers
+        MemoryValue::I128(v) => Value::String(v.to_string()),
+        MemoryValue::U128(v) => Value::String(v.to_string()),
+        MemoryValue::F32(v) => Value::from(*v),
+        MemoryValue::F64(v) => Value::from(*v),
+        MemoryValue::Bool(v) => Value::Bool(*v),
+        MemoryValue::String(s) | MemoryValue::WString(s) => Value::String(s.clone()),
+        MemoryValue::Bytes(b) => Value::from(b.clone()),
+        MemoryValue::Pointer(p) => Value::String(p.to_string()),
+        MemoryValue::Array { values, .. } => Value::Array(values.iter().map(to_json).collect()),
+    }
+}
+
+fn offset(address: Address, by: usize) -> Address {
+    Address::new(address.as_usize().wrapping_add(by))
+}
+
+fn check_index(name: &str, index: usize, len: usize) -> MemoryResult<()> {
+    if index >= len {
+        return Err(MemoryError::InvalidStruct(format!(
+            "index {} is out of bounds for '{}' of length {}",
+            index, name, len
+        )));
+    }
+    Ok(())
+}
+
+/// Split `a.b[1][2].c` into names and their indices
+fn parse_path(path: &str) -> MemoryResult<Vec<(String, Vec<usize>)>> {
+    let invalid = || MemoryError::InvalidStruct(format!("invalid field path '{}'", path));
+    path.split('.')
+        .map(|segment| {
+            let (name, mut rest) = segment.split_at(segment.find('[').unwrap_or(segment.len()));
+            if name.is_empty() {
+                return Err(invalid());
+            }
+            let mut indices = Vec::new();
+            while !rest.is_empty() {
+                let close = rest.find(']').ok_or_else(invalid)?;
+                let index = rest
+                    .get(1..close)
+                    .filter(|_| rest.starts_with('['))
+                    .and_then(|digits| digits.trim().parse().ok())
+                    .ok_or_else(invalid)?;
+                indices.push(index);
+                rest = &rest[close + 1..];
+            }
+            Ok((name.to_string(), indices))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_path() {
+        assert_eq!(
+            parse_path("player.inventory[3].count").unwrap(),
+            vec![
+                ("player".to_string(), vec![]),
+                ("inventory".to_string(), vec![3]),
+                ("count".to_string(), vec![]),
+            ]
+        );
+        assert_eq!(
+            parse_path("grid[1][2]").unwrap(),
+            vec![("grid".to_string(), vec![1, 2])]
+        );
+        for bad in ["", "a..b", "a[", "a[x]", "a[1]b", "[1]"] {
+            assert!(parse_path(bad).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_to_json() {
+        assert_eq!(to_json(&MemoryValue::I32(-5)), Value::from(-5));
+        assert_eq!(
+            to_json(&MemoryValue::Pointer(Address::new(0x10))),
+            Value::String("0x0000000000000010".to_string())
+        );
+        assert_eq!(
+            to_json(&MemoryValue::U128(u128::MAX)),
+            Value::String(u128::MAX.to_string())
+        );
+        assert_eq!(
+            to_json(&MemoryValue::Bytes(vec![1, 2])),
+            serde_json::json!([1, 2])
+        );
+    }
+}
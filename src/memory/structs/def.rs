@@ -0,0 +1,456 @@
+//! Structure definitions and the registry they are loaded into
+
+use crate::core::types::{MemoryError, MemoryResult, ProcessArchitecture, ValueType};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+/// One named field of a structure
+///
+/// A field holds either a value (`type`) or a structure (`struct`), which
+/// may be stored inline or behind a pointer.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldDef {
+    pub name: String,
+    /// Byte offset from the start of the structure
+    pub offset: usize,
+    #[serde(default, rename = "type", skip_serializing_if = "Option::is_none")]
+    pub value_type: Option<ValueType>,
+    #[serde(default, rename = "struct", skip_serializing_if = "Option::is_none")]
+    pub struct_name: Option<String>,
+    /// The field holds a pointer to `struct_name` rather than the structure
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub pointer: bool,
+    /// Number of consecutive elements, for arrays of structures or pointers
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub count: Option<usize>,
+    /// Bytes of a `bytes` field, maximum characters of a string field
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub length: Option<usize>,
+}
+
+/// What a validated field holds
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum FieldKind<'a> {
+    Value(ValueType),
+    Struct { name: &'a str, pointer: bool },
+}
+
+impl FieldDef {
+    fn new(name: &str, offset: usize) -> Self {
+        FieldDef {
+            name: name.to_string(),
+            offset,
+            value_type: None,
+            struct_name: None,
+            pointer: false,
+            count: None,
+            length: None,
+        }
+    }
+
+    /// A field holding a value of `value_type`
+    pub fn value(name: &str, offset: usize, value_type: ValueType) -> Self {
+        FieldDef {
+            value_type: Some(value_type),
+            ..Self::new(name, offset)
+        }
+    }
+
+    /// A field holding the structure `struct_name` inline
+    pub fn nested(name: &str, offset: usize, struct_name: &str) -> Self {
+        FieldDef {
+            struct_name: Some(struct_name.to_string()),
+            ..Self::new(name, offset)
+        }
+    }
+
+    /// A field holding a pointer to the structure `struct_name`
+    pub fn pointer(name: &str, offset: usize, struct_name: &str) -> Self {
+        FieldDef {
+            pointer: true,
+            ..Self::nested(name, offset, struct_name)
+        }
+    }
+
+    /// Repeat the field `count` times
+    pub fn with_count(mut self, count: usize) -> Self {
+        self.count = Some(count);
+        self
+    }
+
+    /// Set the byte or character length of a variable-size value
+    pub fn with_length(mut self, length: usize) -> Self {
+        self.length = Some(length);
+        self
+    }
+
+    pub(crate) fn kind(&self) -> MemoryResult<FieldKind<'_>> {
+        match (self.value_type, self.struct_name.as_deref()) {
+            (Some(value_type), None) if !self.pointer => Ok(FieldKind::Value(value_type)),
+            (None, Some(name)) => Ok(FieldKind::Struct {
+                name,
+                pointer: self.pointer,
+            }),
+            _ => Err(MemoryError::InvalidStruct(format!(
+                "field '{}' needs exactly one of `type` or `struct`, and `pointer` only with `struct`",
+                self.name
+            ))),
+        }
+    }
+}
+
+/// A named structure layout
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StructDef {
+    pub name: String,
+    /// Size in bytes; defaults to the end of the last field
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<usize>,
+    #[serde(default)]
+    pub fields: Vec<FieldDef>,
+}
+
+impl StructDef {
+    /// Create a structure with no fields
+    pub fn new(name: &str) -> Self {
+        StructDef {
+            name: name.to_string(),
+            size: None,
+            fields: Vec::new(),
+        }
+    }
+
+    /// Set the structure's size explicitly
+    pub fn with_size(mut self, size: usize) -> Self {
+        self.size = Some(size);
+        self
+    }
+
+    /// Add a field
+    pub fn with_field(mut self, field: FieldDef) -> Self {
+        self.fields.push(field);
+        self
+    }
+
+    /// Field with the given name
+    pub fn field(&self, name: &str) -> Option<&FieldDef> {
+        self.fields.iter().find(|f| f.name == name)
+    }
+}
+
+/// A value or pointer field reached through inline structures
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldSlot {
+    /// Path from the structure, e.g. `inventory[3].count`
+    pub path: String,
+    pub offset: usize,
+    /// `Pointer` for pointer-to-structure fields
+    pub value_type: ValueType,
+    pub length: Option<usize>,
+}
+
+/// On-disk layout: `[[structs]]` in TOML, `{"structs": [...]}` in JSON
+#[derive(Serialize, Deserialize)]
+struct StructFile {
+    structs: Vec<StructDef>,
+}
+
+/// Structure definitions by name
+#[derive(Debug, Clone, Default)]
+pub struct StructRegistry {
+    structs: HashMap<String, StructDef>,
+}
+
+impl StructRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add or replace a definition, returning the one it replaced
+    pub fn insert(&mut self, def: StructDef) -> Option<StructDef> {
+        self.structs.insert(def.name.clone(), def)
+    }
+
+    /// Definition with the given name
+    pub fn get(&self, name: &str) -> MemoryResult<&StructDef> {
+        self.structs
+            .get(name)
+            .ok_or_else(|| MemoryError::InvalidStruct(format!("unknown structure '{}'", name)))
+    }
+
+    /// Names of all definitions, sorted
+    pub fn names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.structs.keys().map(String::as_str).collect();
+        names.sort_unstable();
+        names
+    }
+
+    /// Parse and validate definitions from TOML
+    pub fn from_toml(text: &str) -> MemoryResult<Self> {
+        let file: StructFile =
+            toml::from_str(text).map_err(|e| MemoryError::InvalidStruct(e.to_string()))?;
+        Self::from_file(file)
+    }
+
+    /// Parse and validate definitions from JSON
+    pub fn from_json(text: &str) -> MemoryResult<Self> {
+        Self::from_file(serde_json::from_str(text)?)
+    }
+
+    /// Load definitions from `path`: JSON for `.json` paths, TOML otherwise
+    pub fn load(path: &Path) -> MemoryResult<Self> {
+        let text = fs::read_to_string(path)?;
+        match path.extension() {
+            Some(ext) if ext.eq_ignore_ascii_case("json") => Self::from_json(&text),
+            _ => Self::from_toml(&text),
+        }
+    }
+
+    fn from_file(file: StructFile) -> MemoryResult<Self> {
+        let mut registry = StructRegistry::new();
+        for def in file.structs {
+            if let Some(previous) = registry.insert(def) {
+                return Err(MemoryError::InvalidStruct(format!(
+                    "structure '{}' is defined twice",
+                    previous.name
+                )));
+            }
+        }
+        registry.validate()?;
+        Ok(registry)
+    }
+
+    /// Check field kinds, names and references, and that no structure contains itself inline
+    pub fn validate(&self) -> MemoryResult<()> {
+        for def in self.structs.values() {
+            let mut names = HashSet::new();
+            for field in &def.fields {
+                if !names.insert(field.name.as_str()) {
+                    return Err(MemoryError::InvalidStruct(format!(
+                        "'{}' has two fields named '{}'",
+                        def.name, field.name
+                    )));
+                }
+                if let FieldKind::Struct { name, .. } = field.kind()? {
+                    self.get(name)?;
+                }
+            }
+            self.check_inline(def, &mut Vec::new())?;
+        }
+        Ok(())
+    }
+
+    fn check_inline<'s>(&'s self, def: &'s StructDef, path: &mut Vec<&'s str>) -> MemoryResult<()> {
+        if path.contains(&def.name.as_str()) {
+            return Err(MemoryError::InvalidStruct(format!(
+                "'{}' contains itself inline",
+                def.name
+            )));
+        }
+        path.push(&def.name);
+        for field in &def.fields {
+            if let FieldKind::Struct {
+                name,
+                pointer: false,
+            } = field.kind()?
+            {
+                self.check_inline(self.get(name)?, path)?;
+            }
+        }
+        path.pop();
+        Ok(())
+    }
+
+    /// Size of a structure in a process of `architecture`
+    pub fn size_of(
+        &self,
+        def: &StructDef,
+        architecture: ProcessArchitecture,
+    ) -> MemoryResult<usize> {
+        if let Some(size) = def.size {
+            return Ok(size);
+        }
+        let mut end = 0;
+        for field in &def.fields {
+            let size = self.element_size(field, architecture)? * field.count.unwrap_or(1);
+            end = end.max(field.offset + size);
+        }
+        Ok(end)
+    }
+
+    /// Every value and pointer field of `def`, expanding inline structures and `count` arrays
+    pub fn flatten(
+        &self,
+        def: &StructDef,
+        architecture: ProcessArchitecture,
+    ) -> MemoryResult<Vec<FieldSlot>> {
+        let mut slots = Vec::new();
+        self.flatten_into(def, architecture, "", 0, &mut slots)?;
+        Ok(slots)
+    }
+
+    fn flatten_into(
+        &self,
+        def: &StructDef,
+        architecture: ProcessArchitecture,
+        prefix: &str,
+        base: usize,
+        slots: &mut Vec<FieldSlot>,
+    ) -> MemoryResult<()> {
+        for field in &def.fields {
+            let stride = self.element_size(field, architecture)?;
+            for i in 0..field.count.unwrap_or(1) {
+                let path = match field.count {
+                    Some(_) => format!("{}{}[{}]", prefix, field.name, i),
+                    None => format!("{}{}", prefix, field.name),
+                };
+                let offset = base + field.offset + i * stride;
+                let value_type = match field.kind()? {
+                    FieldKind::Value(value_type) => value_type,
+                    FieldKind::Struct { pointer: true, .. } => ValueType::Pointer,
+                    FieldKind::Struct { name, .. } => {
+                        let prefix = format!("{}.", path);
+                        self.flatten_into(self.get(name)?, architecture, &prefix, offset, slots)?;
+                        continue;
+                    }
+                };
+                slots.push(FieldSlot {
+                    path,
+                    offset,
+                    value_type,
+                    length: field.length,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Size of one element of `field`; strings without a length count as empty
+    pub fn element_size(
+        &self,
+        field: &FieldDef,
+        architecture: ProcessArchitecture,
+    ) -> MemoryResult<usize> {
+        Ok(match field.kind()? {
+            FieldKind::Value(value_type) => match value_type {
+                ValueType::Bytes | ValueType::String => field.length.unwrap_or(0),
+                ValueType::WString => field.length.unwrap_or(0) * 2,
+                _ => value_type.size_for(architecture).unwrap_or(0),
+            },
+            FieldKind::Struct { pointer: true, .. } => architecture.pointer_size(),
+            FieldKind::Struct { name, .. } => self.size_of(self.get(name)?, architecture)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DEFINITIONS: &str = r#"
+        [[structs]]
+        name = "Item"
+        fields = [
+            { name = "id", offset = 0, type = "u32" },
+            { name = "count", offset = 4, type = "u16" },
+        ]
+
+        [[structs]]
+        name = "Player"
+        size = 0x80
+        fields = [
+            { name = "health", offset = 0x10, type = "i32" },
+            { name = "name", offset = 0x20, type = "string", length = 16 },
+            { name = "inventory", offset = 0x40, struct = "Item", count = 4 },
+            { name = "target", offset = 0x60, struct = "Player", pointer = true },
+            { name = "position", offset = 0x70, type = { array = { elem = "f32", len = 3 } } },
+        ]
+    "#;
+
+    #[test]
+    fn test_load_toml_and_json() {
+        let registry = StructRegistry::from_toml(DEFINITIONS).unwrap();
+        assert_eq!(registry.names(), vec!["Item", "Player"]);
+
+        let player = registry.get("Player").unwrap();
+        assert_eq!(
+            player.field("target"),
+            Some(&FieldDef::pointer("target", 0x60, "Player"))
+        );
+        assert_eq!(
+            player.field("position").unwrap().value_type,
+            ValueType::array(ValueType::F32, 3)
+        );
+
+        let json = serde_json::to_string(&StructFile {
+            structs: vec![registry.get("Item").unwrap().clone()],
+        })
+        .unwrap();
+        let reloaded = StructRegistry::from_json(&json).unwrap();
+        assert_eq!(reloaded.get("Item").unwrap(), registry.get("Item").unwrap());
+    }
+
+    #[test]
+    fn test_sizes() {
+        let registry = StructRegistry::from_toml(DEFINITIONS).unwrap();
+        let item = registry.get("Item").unwrap();
+        let x86 = ProcessArchitecture::X86;
+        assert_eq!(registry.size_of(item, x86).unwrap(), 6);
+        assert_eq!(
+            registry
+                .size_of(registry.get("Player").unwrap(), x86)
+                .unwrap(),
+            0x80
+        );
+
+        let player = registry.get("Player").unwrap();
+        let target = player.field("target").unwrap();
+        assert_eq!(registry.element_size(target, x86).unwrap(), 4);
+        let inventory = player.field("inventory").unwrap();
+        assert_eq!(registry.element_size(inventory, x86).unwrap(), 6);
+    }
+
+    #[test]
+    fn test_flatten() {
+        let registry = StructRegistry::from_toml(DEFINITIONS).unwrap();
+        let slots = registry
+            .flatten(registry.get("Player").unwrap(), ProcessArchitecture::X64)
+            .unwrap();
+        let paths: Vec<&str> = slots.iter().map(|s| s.path.as_str()).collect();
+        assert_eq!(paths.len(), 2 + 4 * 2 + 2);
+        assert_eq!(paths[5], "inventory[1].count");
+        assert_eq!(slots[5].offset, 0x40 + 6 + 4);
+        let target = slots.iter().find(|s| s.path == "target").unwrap();
+        assert_eq!(target.value_type, ValueType::Pointer);
+        assert_eq!(slots[1].length, Some(16));
+    }
+
+    #[test]
+    fn test_validation() {
+        let mut registry = StructRegistry::new();
+        registry.insert(StructDef::new("Node").with_field(FieldDef::pointer("next", 0, "Node")));
+        assert!(registry.validate().is_ok());
+
+        registry.insert(StructDef::new("Loop").with_field(FieldDef::nested("me", 0, "Loop")));
+        assert!(registry.validate().is_err());
+
+        let mut registry = StructRegistry::new();
+        registry.insert(StructDef::new("A").with_field(FieldDef::nested("b", 0, "Missing")));
+        assert!(matches!(
+            registry.validate(),
+            Err(MemoryError::InvalidStruct(_))
+        ));
+
+        let both = FieldDef {
+            value_type: Some(ValueType::U8),
+            ..FieldDef::nested("both", 0, "A")
+        };
+        assert!(both.kind().is_err());
+
+        let twice = "[[structs]]\nname = \"A\"\n[[structs]]\nname = \"A\"\n";
+        assert!(StructRegistry::from_toml(twice).is_err());
+    }
+}
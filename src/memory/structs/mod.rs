@@ -0,0 +1,13 @@
+//! Structure definitions for reading and writing typed records in memory
+//!
+//! A [`StructDef`] lists named fields at fixed offsets. Each field holds a
+//! value, a nested structure, or a pointer to a structure, optionally
+//! repeated `count` times. Definitions are loaded into a [`StructRegistry`]
+//! from TOML or JSON, and a [`StructAccessor`] reads whole structures as
+//! JSON or single fields by path.
+
+pub mod access;
+pub mod def;
+
+pub use access::{FieldLocation, StructAccessor, DEFAULT_MAX_DEPTH};
+pub use def::{FieldDef, FieldSlot, StructDef, StructRegistry};
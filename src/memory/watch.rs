@@ -0,0 +1,566 @@
+//! Watch lists that poll values and report when they change
+//!
+//! Watched values are grouped by page and each group is fetched with a
+//! single read, so many values on one page cost one read per poll. Changes
+//! are broadcast as [`ChangeEvent`]s to every subscriber.
+
+use crate::core::types::{
+    Address, MemoryError, MemoryResult, MemoryValue, ProcessArchitecture, ValueType,
+};
+use crate::memory::backend::MemoryBackend;
+use crate::memory::policy::MemoryPolicy;
+use crate::memory::reader::SafeMemoryReader;
+use crate::memory::structs::{StructDef, StructRegistry};
+use crate::process::ProcessHandle;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+use tokio::time::MissedTickBehavior;
+
+/// Reads are coalesced within pages of this size
+const PAGE_SIZE: usize = 4096;
+
+/// Events buffered per subscriber before the slowest one starts missing them
+pub const EVENT_CAPACITY: usize = 1024;
+
+/// Poll interval used unless configured otherwise
+pub const DEFAULT_WATCH_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Identifier of a watched value within its watcher
+pub type WatchId = u64;
+
+/// A value to watch
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WatchSpec {
+    /// Name reported in events; defaults to the address
+    pub label: String,
+    pub address: Address,
+    pub value_type: ValueType,
+    /// Byte count for `bytes`, maximum characters for strings
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub length: Option<usize>,
+}
+
+impl WatchSpec {
+    /// Watch the value of `value_type` at `address`
+    pub fn new(address: Address, value_type: ValueType) -> Self {
+        WatchSpec {
+            label: address.to_string(),
+            address,
+            value_type,
+            length: None,
+        }
+    }
+
+    /// Set the label reported in events
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = label.into();
+        self
+    }
+
+    /// Set the byte or character length of a variable-size value
+    pub fn with_length(mut self, length: usize) -> Self {
+        self.length = Some(length);
+        self
+    }
+}
+
+/// A watched value changed; `None` means it could not be read
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ChangeEvent {
+    pub pid: u32,
+    pub id: WatchId,
+    pub label: String,
+    pub address: Address,
+    pub old: Option<MemoryValue>,
+    pub new: Option<MemoryValue>,
+    /// Milliseconds since the Unix epoch
+    pub timestamp_ms: u64,
+}
+
+/// A watched value and the value seen by the latest poll
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WatchInfo {
+    pub id: WatchId,
+    pub spec: WatchSpec,
+    pub value: Option<MemoryValue>,
+}
+
+struct Watched {
+    spec: WatchSpec,
+    size: usize,
+    /// `None` until the first poll
+    last: Option<Option<MemoryValue>>,
+}
+
+/// Polls a set of watched values in one process
+pub struct Watcher<B: MemoryBackend + 'static = ProcessHandle> {
+    handle: Weak<B>,
+    interval: Duration,
+    policy: MemoryPolicy,
+    watches: Mutex<BTreeMap<WatchId, Watched>>,
+    next_id: AtomicU64,
+    events: broadcast::Sender<ChangeEvent>,
+}
+
+impl<B: MemoryBackend + 'static> Watcher<B> {
+    /// Watch values through `handle` for as long as it can be upgraded
+    pub fn new(handle: Weak<B>) -> Self {
+        Watcher {
+            handle,
+            interval: DEFAULT_WATCH_INTERVAL,
+            policy: MemoryPolicy::default(),
+            watches: Mutex::new(BTreeMap::new()),
+            next_id: AtomicU64::new(1),
+            events: broadcast::channel(EVENT_CAPACITY).0,
+        }
+    }
+
+    /// Set the poll interval used by [`Watcher::spawn`]
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Read under `policy` instead of the default one
+    pub fn with_policy(mut self, policy: MemoryPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Publish events on an existing channel, e.g. one shared by several watchers
+    pub fn with_events(mut self, events: broadcast::Sender<ChangeEvent>) -> Self {
+        self.events = events;
+        self
+    }
+
+    /// Poll interval used by [`Watcher::spawn`]
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Receive the change events of later polls
+    pub fn subscribe(&self) -> broadcast::Receiver<ChangeEvent> {
+        self.events.subscribe()
+    }
+
+    fn upgrade(&self) -> MemoryResult<Arc<B>> {
+        self.handle
+            .upgrade()
+            .ok_or_else(|| MemoryError::InvalidHandle("process is no longer attached".to_string()))
+    }
+
+    /// Start watching a value
+    pub fn add(&self, spec: WatchSpec) -> MemoryResult<WatchId> {
+        let architecture = self.upgrade()?.architecture();
+        let size = match spec.value_type {
+            ValueType::Bytes | ValueType::String => spec.length,
+            ValueType::WString => match spec.length {
+                Some(len) => Some(len.checked_mul(2).ok_or_else(|| {
+                    MemoryError::InvalidArgument(format!("{} characters is too long", len))
+                })?),
+                None => None,
+            },
+            value_type => value_type.size_for(architecture),
+        }
+        .filter(|&size| size > 0)
+        .ok_or_else(|| {
+            MemoryError::InvalidValueType(format!(
+                "watching {:?} needs a non-zero length",
+                spec.value_type
+            ))
+        })?;
+        // Polls add the size to the address, so it must not overflow
+        if spec.address.as_usize().checked_add(size).is_none() {
+            return Err(MemoryError::InvalidArgument(format!(
+                "{} bytes at {} overflow the address space",
+                size, spec.address
+            )));
+        }
+        self.policy.check_read(spec.address, size)?;
+
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        self.watches.lock().unwrap().insert(
+            id,
+            Watched {
+                spec,
+                size,
+                last: None,
+            },
+        );
+        Ok(id)
+    }
+
+    /// Watch every value and pointer field of the structure at `address`
+    ///
+    /// Labels are field paths prefixed with the structure name.
+    pub fn add_struct(
+        &self,
+        address: Address,
+        def: &StructDef,
+        registry: &StructRegistry,
+    ) -> MemoryResult<Vec<WatchId>> {
+        let architecture = self.upgrade()?.architecture();
+        let specs = registry
+            .flatten(def, architecture)?
+            .into_iter()
+            .map(|slot| {
+                let field = address.as_usize().checked_add(slot.offset).ok_or_else(|| {
+                    MemoryError::InvalidArgument(format!(
+                        "'{}.{}' overflows the address space",
+                        def.name, slot.path
+                    ))
+                })?;
+                Ok(WatchSpec {
+                    label: format!("{}.{}", def.name, slot.path),
+                    address: Address::new(field),
+                    value_type: slot.value_type,
+                    length: slot.length,
+                })
+            })
+            .collect::<MemoryResult<Vec<_>>>()?;
+        // Reject the whole structure before adding any of it
+        for spec in &specs {
+            if matches!(
+                spec.value_type,
+                ValueType::Bytes | ValueType::String | ValueType::WString
+            ) && spec.length.unwrap_or(0) == 0
+            {
+                return Err(MemoryError::InvalidStruct(format!(
+                    "'{}' needs a length to be watched",
+                    spec.label
+                )));
+            }
+        }
+        specs.into_iter().map(|spec| self.add(spec)).collect()
+    }
+
+    /// Stop watching a value, returning whether it was watched
+    pub fn remove(&self, id: WatchId) -> bool {
+        self.watches.lock().unwrap().remove(&id).is_some()
+    }
+
+    /// Watched values in the order they were added
+    pub fn list(&self) -> Vec<WatchInfo> {
+        self.watches
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(&id, watched)| WatchInfo {
+                id,
+                spec: watched.spec.clone(),
+                value: watched.last.clone().flatten(),
+            })
+            .collect()
+    }
+
+    /// Read every watched value once, publishing and returning the changes
+    ///
+    /// The first poll of a value only records it.
+    pub fn poll(&self) -> MemoryResult<Vec<ChangeEvent>> {
+        let handle = self.upgrade()?;
+        let reader = SafeMemoryReader::new(&*handle).with_policy(self.policy.clone());
+        let architecture = handle.architecture();
+        let timestamp_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+
+        let mut watches = self.watches.lock().unwrap();
+        let mut order: Vec<(Address, WatchId)> = watches
+            .iter()
+            .map(|(&id, w)| (w.spec.address, id))
+            .collect();
+        order.sort_unstable();
+
+        let mut events = Vec::new();
+        for batch in page_batches(&order) {
+            let start = batch[0].0.as_usize();
+            let end = batch
+                .iter()
+                .map(|(address, id)| address.as_usize() + watches[id].size)
+                .max()
+                .unwrap_or(start);
+            let span = reader.read_raw(Address::new(start), end - start).ok();
+
+            for (address, id) in batch {
+                let watched = watches.get_mut(id).expect("batched watch exists");
+                let offset = address.as_usize() - start;
+                let bytes = match &span {
+                    Some(span) => Some(span[offset..offset + watched.size].to_vec()),
+                    // Retry alone so one bad value does not hide the rest of the page
+                    None => reader.read_raw(*address, watched.size).ok(),
+                };
+                let value = bytes.and_then(|b| decode(&b, watched.spec.value_type, architecture));
+
+                match watched.last.replace(value.clone()) {
+                    Some(old) if old != value => events.push(ChangeEvent {
+                        pid: handle.pid(),
+                        id: *id,
+                        label: watched.spec.label.clone(),
+                        address: *address,
+                        old,
+                        new: value,
+                        timestamp_ms,
+                    }),
+                    _ => {}
+                }
+            }
+        }
+        drop(watches);
+
+        for event in &events {
+            // Having no subscribers is not an error
+            let _ = self.events.send(event.clone());
+        }
+        Ok(events)
+    }
+
+    /// Poll on the watcher's interval until it is dropped or the process detaches
+    ///
+    /// Must be called within a tokio runtime.
+    pub fn spawn(self: &Arc<Self>) -> MemoryResult<JoinHandle<()>> {
+        let runtime = tokio::runtime::Handle::try_current().map_err(|e| {
+            MemoryError::UnsupportedOperation(format!("watching needs a tokio runtime: {}", e))
+        })?;
+        let watcher = Arc::downgrade(self);
+        let mut ticker = tokio::time::interval(self.interval.max(Duration::from_millis(1)));
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        Ok(runtime.spawn(async move {
+            loop {
+                ticker.tick().await;
+                let Some(watcher) = watcher.upgrade() else {
+                    return;
+                };
+                if watcher.poll().is_err() {
+                    return;
+                }
+            }
+        }))
+    }
+}
+
+/// Split address-sorted watches into runs starting on the same page
+fn page_batches(order: &[(Address, WatchId)]) -> Vec<&[(Address, WatchId)]> {
+    let mut batches = Vec::new();
+    let mut rest = order;
+    while let Some(&(first, _)) = rest.first() {
+        let page = first.as_usize() / PAGE_SIZE;
+        let len = rest
+            .iter()
+            .position(|(address, _)| address.as_usize() / PAGE_SIZE != page)
+            .unwrap_or(rest.len());
+        let (batch, tail) = rest.split_at(len);
+        batches.push(batch);
+        rest = tail;
+    }
+    batches
+}
+
+/// Decode a watched value; strings stop at their first NUL
+fn decode(
+    bytes: &[u8],
+    value_type: ValueType,
+    architecture: ProcessArchitecture,
+) -> Option<MemoryValue> {
+    match value_type {
+        ValueType::String => {
+            let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+            Some(MemoryValue::String(
+                String::from_utf8_lossy(&bytes[..end]).into_owned(),
+            ))
+        }
+        ValueType::WString => {
+            let units: Vec<u16> = bytes
+                .chunks_exact(2)
+                .map(|c| u16::from_le_bytes([c[0], c[1]]))
+                .take_while(|&unit| unit != 0)
+                .collect();
+            Some(MemoryValue::WString(String::from_utf16_lossy(&units)))
+        }
+        _ => MemoryValue::from_bytes_for(bytes, value_type, architecture),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::types::ModuleInfo;
+    use crate::memory::backend::{SnapshotProcess, SnapshotRegion};
+    use crate::memory::regions::RegionInfo;
+    use crate::memory::structs::FieldDef;
+    use crate::process::PolicyAccess;
+    use std::sync::atomic::AtomicUsize;
+
+    const BASE: usize = 0x10000;
+
+    /// Snapshot that counts the reads issued against it
+    struct Counting {
+        inner: SnapshotProcess,
+        reads: AtomicUsize,
+    }
+
+    impl MemoryBackend for Counting {
+        fn pid(&self) -> u32 {
+            self.inner.pid()
+        }
+        fn read(&self, address: Address, buffer: &mut [u8]) -> MemoryResult<usize> {
+            self.reads.fetch_add(1, Ordering::Relaxed);
+            self.inner.read(address, buffer)
+        }
+        fn write(&self, address: Address, data: &[u8]) -> MemoryResult<usize> {
+            self.inner.write(address, data)
+        }
+        fn query_region(&self, address: Address) -> MemoryResult<RegionInfo> {
+            self.inner.query_region(address)
+        }
+        fn enumerate_modules(&self) -> MemoryResult<Vec<ModuleInfo>> {
+            self.inner.enumerate_modules()
+        }
+        fn policy_access(&self) -> &PolicyAccess {
+            self.inner.policy_access()
+        }
+        fn architecture(&self) -> ProcessArchitecture {
+            self.inner.architecture()
+        }
+    }
+
+    fn process() -> Arc<Counting> {
+        let inner = SnapshotProcess::with_region(9, BASE, vec![0; 0x2000], 0x04);
+        Arc::new(Counting {
+            inner,
+            reads: AtomicUsize::new(0),
+        })
+    }
+
+    fn at(offset: usize) -> Address {
+        Address::new(BASE + offset)
+    }
+
+    #[test]
+    fn test_one_read_per_page() {
+        let process = process();
+        let watcher = Watcher::new(Arc::downgrade(&process));
+        for i in 0..1000 {
+            watcher
+                .add(WatchSpec::new(at(i * 4), ValueType::U32))
+                .unwrap();
+        }
+        watcher
+            .add(WatchSpec::new(at(0x1800), ValueType::F64))
+            .unwrap();
+
+        assert!(watcher.poll().unwrap().is_empty());
+        assert_eq!(process.reads.load(Ordering::Relaxed), 2);
+
+        process.write(at(0x10), &7u32.to_le_bytes()).unwrap();
+        let events = watcher.poll().unwrap();
+        assert_eq!(process.reads.load(Ordering::Relaxed), 4);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].address, at(0x10));
+        assert_eq!(events[0].old, Some(MemoryValue::U32(0)));
+        assert_eq!(events[0].new, Some(MemoryValue::U32(7)));
+        assert_eq!(events[0].pid, 9);
+        assert!(events[0].timestamp_ms > 0);
+    }
+
+    #[test]
+    fn test_unreadable_values_and_removal() {
+        let process = process();
+        let watcher = Watcher::new(Arc::downgrade(&process));
+        let edge = watcher
+            .add(WatchSpec::new(at(0x1FFC), ValueType::U64))
+            .unwrap();
+        let inside = watcher
+            .add(WatchSpec::new(at(0x1FF0), ValueType::U32).with_label("inside"))
+            .unwrap();
+        watcher.poll().unwrap();
+
+        let list = watcher.list();
+        assert_eq!(list[0].id, edge);
+        assert_eq!(list[0].value, None);
+        assert_eq!(list[1].spec.label, "inside");
+        assert_eq!(list[1].value, Some(MemoryValue::U32(0)));
+
+        assert!(watcher.remove(inside));
+        assert!(!watcher.remove(inside));
+        assert!(watcher
+            .add(WatchSpec::new(at(0), ValueType::String))
+            .is_err());
+
+        let limited = Watcher::new(Arc::downgrade(&process))
+            .with_policy(MemoryPolicy::default().with_max_read_size(16));
+        assert!(matches!(
+            limited.add(WatchSpec::new(at(0), ValueType::Bytes).with_length(32)),
+            Err(MemoryError::ReadLimitExceeded { .. })
+        ));
+        assert!(limited
+            .add(WatchSpec::new(at(0), ValueType::Bytes).with_length(16))
+            .is_ok());
+
+        // Sizes and ends past the address space are refused before any poll
+        for spec in [
+            WatchSpec::new(at(0), ValueType::WString).with_length(usize::MAX),
+            WatchSpec::new(Address::new(usize::MAX - 2), ValueType::U32),
+        ] {
+            assert!(matches!(
+                watcher.add(spec),
+                Err(MemoryError::InvalidArgument(_))
+            ));
+        }
+        watcher.poll().unwrap();
+    }
+
+    #[test]
+    fn test_structs_and_strings() {
+        let process = process();
+        let mut registry = StructRegistry::new();
+        registry.insert(
+            StructDef::new("Player")
+                .with_field(FieldDef::value("health", 0, ValueType::I32))
+                .with_field(FieldDef::value("name", 8, ValueType::String).with_length(8)),
+        );
+        let watcher = Watcher::new(Arc::downgrade(&process));
+        let ids = watcher
+            .add_struct(at(0x100), registry.get("Player").unwrap(), &registry)
+            .unwrap();
+        assert_eq!(ids.len(), 2);
+        watcher.poll().unwrap();
+
+        process.write(at(0x108), b"hero\0").unwrap();
+        let events = watcher.poll().unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].label, "Player.name");
+        assert_eq!(events[0].new, Some(MemoryValue::String("hero".to_string())));
+    }
+
+    #[tokio::test]
+    async fn test_spawned_watcher_publishes_until_detach() {
+        let process = process();
+        let watcher = Arc::new(
+            Watcher::new(Arc::downgrade(&process)).with_interval(Duration::from_millis(5)),
+        );
+        watcher.add(WatchSpec::new(at(0), ValueType::I32)).unwrap();
+        let mut events = watcher.subscribe();
+        let task = watcher.spawn().unwrap();
+        tokio::time::sleep(Duration::from_millis(30)).await;
+
+        process.write(at(0), &(-1i32).to_le_bytes()).unwrap();
+        let event = tokio::time::timeout(Duration::from_secs(2), events.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(event.new, Some(MemoryValue::I32(-1)));
+
+        drop(process);
+        tokio::time::timeout(Duration::from_secs(2), task)
+            .await
+            .unwrap()
+            .unwrap();
+    }
+}
@@ -4,34 +4,36 @@
 
 use super::{BatchWrite, ExtendedWrite, MemoryCopy, MemoryWrite};
 use crate::core::types::{Address, MemoryError, MemoryResult, MemoryValue};
+use crate::memory::backend::MemoryBackend;
 use crate::process::ProcessHandle;
 use std::mem;
 
 /// Basic memory writer for raw write operations
-pub struct BasicMemoryWriter<'a> {
-    handle: &'a ProcessHandle,
+pub struct BasicMemoryWriter<'a, B: MemoryBackend = ProcessHandle> {
+    handle: &'a B,
 }
 
-impl<'a> BasicMemoryWriter<'a> {
+impl<'a, B: MemoryBackend> BasicMemoryWriter<'a, B> {
     /// Create a new basic memory writer
-    pub fn new(handle: &'a ProcessHandle) -> Self {
+    pub fn new(handle: &'a B) -> Self {
         BasicMemoryWriter { handle }
     }
 
     /// Get the process handle
-    pub fn handle(&self) -> &ProcessHandle {
+    pub fn handle(&self) -> &B {
         self.handle
     }
 }
 
-impl<'a> MemoryWrite for BasicMemoryWriter<'a> {
+impl<'a, B: MemoryBackend> MemoryWrite for BasicMemoryWriter<'a, B> {
     /// Write raw bytes to memory
     fn write_bytes(&self, address: Address, data: &[u8]) -> MemoryResult<()> {
+        self.handle.policy_access().check_write(self.handle.pid())?;
         if data.is_empty() {
             return Ok(());
         }
 
-        let bytes_written = self.handle.write_memory(address.as_usize(), data)?;
+        let bytes_written = self.handle.write(address, data)?;
 
         if bytes_written != data.len() {
             return Err(MemoryError::WriteFailed {
@@ -77,11 +79,18 @@ impl<'a> MemoryWrite for BasicMemoryWriter<'a> {
                 self.write_bytes(address, &bytes)
             }
             MemoryValue::Bytes(b) => self.write_bytes(address, b),
+            MemoryValue::WString(s) => self.write_wide_string(address, s),
+            MemoryValue::Bool(v) => self.write(address, *v as u8),
+            MemoryValue::I128(v) => self.write(address, *v),
+            MemoryValue::U128(v) => self.write(address, *v),
+            MemoryValue::Pointer(_) | MemoryValue::Array { .. } => {
+                self.write_bytes(address, &value.to_bytes_for(self.handle.architecture()))
+            }
         }
     }
 }
 
-impl<'a> ExtendedWrite for BasicMemoryWriter<'a> {
+impl<'a, B: MemoryBackend> ExtendedWrite for BasicMemoryWriter<'a, B> {
     /// Write a null-terminated string to memory
     fn write_string(&self, address: Address, value: &str) -> MemoryResult<()> {
         let mut bytes = value.as_bytes().to_vec();
@@ -117,7 +126,7 @@ impl<'a> ExtendedWrite for BasicMemoryWriter<'a> {
     }
 }
 
-impl<'a> BatchWrite for BasicMemoryWriter<'a> {
+impl<'a, B: MemoryBackend> BatchWrite for BasicMemoryWriter<'a, B> {
     /// Write multiple values in a batch
     fn write_batch<T: Copy>(&self, writes: &[(Address, T)]) -> Vec<MemoryResult<()>> {
         writes
@@ -127,7 +136,7 @@ impl<'a> BatchWrite for BasicMemoryWriter<'a> {
     }
 }
 
-impl<'a> MemoryCopy for BasicMemoryWriter<'a> {
+impl<'a, B: MemoryBackend> MemoryCopy for BasicMemoryWriter<'a, B> {
     /// Copy memory from one location to another within the same process
     fn copy_memory(&self, source: Address, destination: Address, size: usize) -> MemoryResult<()> {
         if size == 0 {
@@ -143,8 +152,7 @@ impl<'a> MemoryCopy for BasicMemoryWriter<'a> {
             let src_addr = Address::new(source.as_usize() + offset);
             let dst_addr = Address::new(destination.as_usize() + offset);
 
-            self.handle
-                .read_memory(src_addr.as_usize(), &mut buffer[..copy_size])?;
+            self.handle.read(src_addr, &mut buffer[..copy_size])?;
             self.write_bytes(dst_addr, &buffer[..copy_size])?;
 
             offset += copy_size;
@@ -162,8 +170,8 @@ impl<'a> MemoryCopy for BasicMemoryWriter<'a> {
         let mut buffer1 = vec![0u8; size];
         let mut buffer2 = vec![0u8; size];
 
-        self.handle.read_memory(addr1.as_usize(), &mut buffer1)?;
-        self.handle.read_memory(addr2.as_usize(), &mut buffer2)?;
+        self.handle.read(addr1, &mut buffer1)?;
+        self.handle.read(addr2, &mut buffer2)?;
 
         self.write_bytes(addr1, &buffer2)?;
         self.write_bytes(addr2, &buffer1)?;
@@ -0,0 +1,427 @@
+//! Journal of the bytes overwritten by each write, for inspection and rollback
+//!
+//! Writes are grouped into named transactions. A [`Transaction`] either
+//! applies every write or restores everything it changed, and committed
+//! transactions stay in the [`WriteJournal`] as [`Patch`]es until reverted.
+
+use super::{MemoryWrite, SafeMemoryWriter};
+use crate::core::types::{Address, MemoryError, MemoryResult, MemoryValue};
+use crate::memory::backend::MemoryBackend;
+use crate::memory::policy::MemoryPolicy;
+use crate::memory::reader::SafeMemoryReader;
+use crate::process::ProcessHandle;
+use serde::Serialize;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// One committed write and the bytes it replaced
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Patch {
+    pub pid: u32,
+    pub transaction_id: u64,
+    pub transaction: String,
+    pub address: Address,
+    pub original: Vec<u8>,
+    pub written: Vec<u8>,
+    /// Milliseconds since the Unix epoch
+    pub timestamp_ms: u64,
+}
+
+/// Bytes changed by a write that is not yet committed
+#[derive(Debug, Clone)]
+pub(crate) struct Change {
+    pub address: Address,
+    pub original: Vec<u8>,
+    pub written: Vec<u8>,
+}
+
+#[derive(Debug, Default)]
+struct JournalState {
+    next_transaction: u64,
+    /// In the order they were committed
+    patches: Vec<Patch>,
+}
+
+/// Committed writes of every process, oldest first
+#[derive(Debug, Default)]
+pub struct WriteJournal {
+    state: Mutex<JournalState>,
+}
+
+impl WriteJournal {
+    /// Create an empty journal
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start a transaction writing through `handle` under `policy`
+    pub fn begin<'a, B: MemoryBackend>(
+        &'a self,
+        handle: &'a B,
+        policy: &MemoryPolicy,
+        name: impl Into<String>,
+    ) -> Transaction<'a, B> {
+        // The transaction records its own changes, so its writer must not back them up again
+        let policy = policy.clone().with_backups(false);
+        Transaction {
+            journal: self,
+            handle,
+            writer: SafeMemoryWriter::with_policy(handle, &policy, self),
+            name: name.into(),
+            changes: Vec::new(),
+            failed: false,
+            finished: false,
+        }
+    }
+
+    /// Every patch, oldest first
+    pub fn patches(&self) -> Vec<Patch> {
+        self.state.lock().unwrap().patches.clone()
+    }
+
+    /// Patches applied to one process, oldest first
+    pub fn patches_for(&self, pid: u32) -> Vec<Patch> {
+        self.state
+            .lock()
+            .unwrap()
+            .patches
+            .iter()
+            .filter(|p| p.pid == pid)
+            .cloned()
+            .collect()
+    }
+
+    /// Record committed changes as one transaction, returning its id
+    pub(crate) fn record(&self, pid: u32, name: &str, changes: Vec<Change>) -> u64 {
+        let timestamp_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+        let mut state = self.state.lock().unwrap();
+        state.next_transaction += 1;
+        let transaction_id = state.next_transaction;
+        state
+            .patches
+            .extend(changes.into_iter().map(|change| Patch {
+                pid,
+                transaction_id,
+                transaction: name.to_string(),
+                address: change.address,
+                original: change.original,
+                written: change.written,
+                timestamp_ms,
+            }));
+        transaction_id
+    }
+
+    /// Restore the bytes replaced by one transaction, returning the patches reverted
+    pub fn revert_transaction<B: MemoryBackend>(
+        &self,
+        handle: &B,
+        transaction_id: u64,
+    ) -> MemoryResult<usize> {
+        let pid = handle.pid();
+        self.revert_where(handle, |p| {
+            p.pid == pid && p.transaction_id == transaction_id
+        })
+    }
+
+    /// Restore every byte written to the process behind `handle`, newest first
+    pub fn revert_all<B: MemoryBackend>(&self, handle: &B) -> MemoryResult<usize> {
+        let pid = handle.pid();
+        self.revert_where(handle, |p| p.pid == pid)
+    }
+
+    /// Revert matching patches newest first, stopping at the first failure
+    ///
+    /// Patches that were not restored stay in the journal.
+    fn revert_where<B: MemoryBackend>(
+        &self,
+        handle: &B,
+        matches: impl Fn(&Patch) -> bool,
+    ) -> MemoryResult<usize> {
+        let writer = unjournaled_writer(handle);
+        let mut state = self.state.lock().unwrap();
+        let mut reverted = 0;
+        for index in (0..state.patches.len()).rev() {
+            let patch = &state.patches[index];
+            if !matches(patch) {
+                continue;
+            }
+            writer.write_bytes(patch.address, &patch.original)?;
+            state.patches.remove(index);
+            reverted += 1;
+        }
+        Ok(reverted)
+    }
+}
+
+/// Writer used to restore journaled bytes
+///
+/// The writes being undone already passed the policy they were made under.
+fn unjournaled_writer<B: MemoryBackend>(handle: &B) -> SafeMemoryWriter<'_, B> {
+    SafeMemoryWriter::new(handle)
+}
+
+/// Writes that are applied together or not at all
+///
+/// If a write fails, everything the transaction already wrote is restored
+/// and further writes are refused. Dropping an uncommitted transaction also
+/// restores its writes.
+pub struct Transaction<'a, B: MemoryBackend = ProcessHandle> {
+    journal: &'a WriteJournal,
+    handle: &'a B,
+    writer: SafeMemoryWriter<'a, B>,
+    name: String,
+    changes: Vec<Change>,
+    failed: bool,
+    finished: bool,
+}
+
+impl<'a, B: MemoryBackend> Transaction<'a, B> {
+    /// Name the transaction is journaled under
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Number of writes applied so far
+    pub fn len(&self) -> usize {
+        self.changes.len()
+    }
+
+    /// Whether nothing has been written yet
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Write raw bytes, rolling the whole transaction back on failure
+    pub fn write_bytes(&mut self, address: Address, data: &[u8]) -> MemoryResult<()> {
+        if self.failed {
+            return Err(MemoryError::WriteFailed {
+                address: address.to_string(),
+                reason: format!("transaction '{}' was rolled back", self.name),
+            });
+        }
+        let result = SafeMemoryReader::new(self.handle)
+            .read_raw(address, data.len())
+            .and_then(|original| {
+                self.writer.write_bytes(address, data)?;
+                Ok(original)
+            });
+        match result {
+            Ok(original) => {
+                self.changes.push(Change {
+                    address,
+                    original,
+                    written: data.to_vec(),
+                });
+                Ok(())
+            }
+            Err(e) => {
+                self.failed = true;
+                // The original error matters more than a failed restore
+                let _ = self.undo();
+                Err(e)
+            }
+        }
+    }
+
+    /// Write a value as the writers store it, strings with their terminator
+    pub fn write_value(&mut self, address: Address, value: &MemoryValue) -> MemoryResult<()> {
+        let bytes = super::stored_bytes(value, self.handle.architecture());
+        self.write_bytes(address, &bytes)
+    }
+
+    /// Keep the writes and add them to the journal, returning the transaction id
+    pub fn commit(mut self) -> MemoryResult<u64> {
+        self.finished = true;
+        if self.failed {
+            return Err(MemoryError::WriteFailed {
+                address: self
+                    .changes
+                    .first()
+                    .map(|c| c.address.to_string())
+                    .unwrap_or_default(),
+                reason: format!("transaction '{}' was rolled back", self.name),
+            });
+        }
+        let changes = std::mem::take(&mut self.changes);
+        Ok(self.journal.record(self.handle.pid(), &self.name, changes))
+    }
+
+    /// Restore everything written so far
+    pub fn rollback(mut self) -> MemoryResult<()> {
+        self.finished = true;
+        self.undo()
+    }
+
+    fn undo(&mut self) -> MemoryResult<()> {
+        while let Some(change) = self.changes.pop() {
+            self.writer.write_bytes(change.address, &change.original)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a, B: MemoryBackend> Drop for Transaction<'a, B> {
+    fn drop(&mut self) {
+        if !self.finished {
+            let _ = self.undo();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::backend::{SnapshotProcess, SnapshotRegion};
+
+    const BASE: usize = 0x10000;
+
+    fn process(pid: u32) -> SnapshotProcess {
+        SnapshotProcess::with_region(pid, BASE, vec![0xAA; 0x1000], 0x04)
+    }
+
+    fn bytes(process: &SnapshotProcess, offset: usize, len: usize) -> Vec<u8> {
+        SafeMemoryReader::new(process)
+            .read_raw(Address::new(BASE + offset), len)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_commit_and_inspect() {
+        let process = process(1);
+        let journal = WriteJournal::new();
+        let mut tx = journal.begin(&process, &MemoryPolicy::default(), "god mode");
+        tx.write_value(Address::new(BASE), &MemoryValue::U16(0x1234))
+            .unwrap();
+        tx.write_value(
+            Address::new(BASE + 0x10),
+            &MemoryValue::String("hi".to_string()),
+        )
+        .unwrap();
+        assert_eq!(tx.len(), 2);
+        let id = tx.commit().unwrap();
+
+        let patches = journal.patches_for(1);
+        assert_eq!(patches.len(), 2);
+        assert_eq!(patches[0].transaction_id, id);
+        assert_eq!(patches[0].transaction, "god mode");
+        assert_eq!(patches[0].original, vec![0xAA, 0xAA]);
+        assert_eq!(patches[0].written, vec![0x34, 0x12]);
+        assert_eq!(patches[1].written, b"hi\0".to_vec());
+        assert!(journal.patches_for(2).is_empty());
+    }
+
+    #[test]
+    fn test_failed_write_rolls_back_transaction() {
+        let process = process(1);
+        let journal = WriteJournal::new();
+        let mut tx = journal.begin(&process, &MemoryPolicy::default(), "partial");
+        tx.write_bytes(Address::new(BASE), &[1, 2, 3]).unwrap();
+        assert!(tx
+            .write_bytes(Address::new(BASE + 0xFFE), &[1, 2, 3, 4])
+            .is_err());
+        assert_eq!(bytes(&process, 0, 3), vec![0xAA; 3]);
+        assert!(tx.write_bytes(Address::new(BASE), &[9]).is_err());
+        assert!(tx.commit().is_err());
+        assert!(journal.patches().is_empty());
+
+        {
+            let mut dropped = journal.begin(&process, &MemoryPolicy::default(), "dropped");
+            dropped.write_bytes(Address::new(BASE), &[5]).unwrap();
+        }
+        assert_eq!(bytes(&process, 0, 1), vec![0xAA]);
+        assert!(journal.patches().is_empty());
+    }
+
+    #[test]
+    fn test_transaction_follows_policy() {
+        let mut process = process(1);
+        process
+            .add_region(SnapshotRegion::new(
+                Address::new(BASE + 0x1000),
+                vec![0xCC; 0x1000],
+                0x40,
+            ))
+            .unwrap();
+        let journal = WriteJournal::new();
+        let policy = MemoryPolicy::default()
+            .with_write_protection(true)
+            .with_backups(true);
+
+        let mut tx = journal.begin(&process, &policy, "patch code");
+        tx.write_bytes(Address::new(BASE), &[1]).unwrap();
+        assert!(matches!(
+            tx.write_bytes(Address::new(BASE + 0x1000), &[0x90]),
+            Err(MemoryError::WriteProtected { .. })
+        ));
+        assert!(tx.commit().is_err());
+        assert_eq!(bytes(&process, 0, 1), vec![0xAA]);
+        assert_eq!(bytes(&process, 0x1000, 1), vec![0xCC]);
+
+        // Backups are the transaction's own, not one patch per write
+        let mut tx = journal.begin(&process, &policy, "patch data");
+        tx.write_bytes(Address::new(BASE), &[1, 2]).unwrap();
+        tx.commit().unwrap();
+        assert_eq!(journal.patches().len(), 1);
+    }
+
+    #[test]
+    fn test_revert_transaction_and_all() {
+        let first = process(1);
+        let second = process(2);
+        let journal = WriteJournal::new();
+        let policy = MemoryPolicy::default();
+
+        let mut tx = journal.begin(&first, &policy, "a");
+        tx.write_bytes(Address::new(BASE), &[1]).unwrap();
+        tx.commit().unwrap();
+        let mut tx = journal.begin(&first, &policy, "b");
+        tx.write_bytes(Address::new(BASE), &[2]).unwrap();
+        tx.write_bytes(Address::new(BASE + 1), &[3]).unwrap();
+        let b = tx.commit().unwrap();
+        let mut tx = journal.begin(&second, &policy, "c");
+        tx.write_bytes(Address::new(BASE), &[4]).unwrap();
+        tx.commit().unwrap();
+
+        assert_eq!(journal.revert_transaction(&first, b).unwrap(), 2);
+        assert_eq!(bytes(&first, 0, 2), vec![1, 0xAA]);
+
+        assert_eq!(journal.revert_all(&first).unwrap(), 1);
+        assert_eq!(bytes(&first, 0, 2), vec![0xAA, 0xAA]);
+        assert_eq!(bytes(&second, 0, 1), vec![4]);
+        assert_eq!(journal.patches().len(), 1);
+    }
+
+    #[test]
+    fn test_safe_writer_journals_each_write() {
+        use crate::memory::writer::MemoryCopy;
+
+        let process = process(1);
+        let journal = WriteJournal::new();
+        let mut writer = SafeMemoryWriter::new(&process);
+        writer.set_journal(&journal);
+
+        writer.write(Address::new(BASE), 0x01020304u32).unwrap();
+        writer
+            .write_value(Address::new(BASE + 8), &MemoryValue::Bool(true))
+            .unwrap();
+        writer
+            .swap_memory(Address::new(BASE), Address::new(BASE + 0x20), 2)
+            .unwrap();
+        assert!(writer
+            .write_bytes(Address::new(BASE + 0xFFF), &[1, 2])
+            .is_err());
+
+        let patches = journal.patches();
+        assert_eq!(patches.len(), 4);
+        assert_eq!(patches[0].transaction, "write");
+        assert_eq!(patches[0].written, vec![4, 3, 2, 1]);
+        assert_eq!(patches[1].written, vec![1]);
+        assert_eq!(patches[2].transaction_id, patches[3].transaction_id);
+
+        assert_eq!(journal.revert_all(&process).unwrap(), 4);
+        assert_eq!(bytes(&process, 0, 0x24), vec![0xAA; 0x24]);
+    }
+}
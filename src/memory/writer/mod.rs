@@ -5,14 +5,18 @@
 //! - Safe writing with validation
 //! - Batch operations
 //! - Memory manipulation utilities
+//! - Journaled writes that can be rolled back
 
 pub mod basic;
+pub mod journal;
 pub mod safe;
 
 pub use basic::BasicMemoryWriter;
+pub use journal::{Patch, Transaction, WriteJournal};
 pub use safe::SafeMemoryWriter;
 
-use crate::core::types::{Address, MemoryResult, MemoryValue};
+use crate::core::types::{Address, MemoryResult, MemoryValue, ProcessArchitecture, ValueEncoding};
+use crate::memory::backend::MemoryBackend;
 use crate::process::ProcessHandle;
 
 /// Common trait for memory write operations
@@ -25,6 +29,19 @@ pub trait MemoryWrite {
 
     /// Write a memory value to memory
     fn write_value(&self, address: Address, value: &MemoryValue) -> MemoryResult<()>;
+
+    /// Write a numeric value stored with `encoding`
+    fn write_encoded(
+        &self,
+        address: Address,
+        value: &MemoryValue,
+        encoding: &ValueEncoding,
+    ) -> MemoryResult<()> {
+        if encoding.is_identity() {
+            return self.write_value(address, value);
+        }
+        self.write_bytes(address, &encoding.encode(value)?)
+    }
 }
 
 /// Extended write operations
@@ -55,11 +72,25 @@ pub trait MemoryCopy: MemoryWrite {
 }
 
 /// Create a memory writer for the given process handle
-pub fn create_writer<'a>(handle: &'a ProcessHandle) -> BasicMemoryWriter<'a> {
+pub fn create_writer<B: MemoryBackend>(handle: &B) -> BasicMemoryWriter<'_, B> {
     BasicMemoryWriter::new(handle)
 }
 
 /// Create a safe memory writer with validation
-pub fn create_safe_writer<'a>(handle: &'a ProcessHandle) -> SafeMemoryWriter<'a> {
+pub fn create_safe_writer<B: MemoryBackend>(handle: &B) -> SafeMemoryWriter<'_, B> {
     SafeMemoryWriter::new(handle)
 }
+
+/// Bytes `write_value` stores for a value, including string terminators
+pub(crate) fn stored_bytes(value: &MemoryValue, arch: ProcessArchitecture) -> Vec<u8> {
+    match value {
+        MemoryValue::String(s) => s.bytes().chain(std::iter::once(0)).collect(),
+        MemoryValue::WString(s) => s
+            .encode_utf16()
+            .chain(std::iter::once(0))
+            .flat_map(u16::to_le_bytes)
+            .collect(),
+        MemoryValue::Bool(v) => vec![*v as u8],
+        _ => value.to_bytes_for(arch),
+    }
+}
@@ -3,28 +3,48 @@
 //! This module provides memory writing functionality with additional safety checks
 //! including verification, bounds checking, and permission validation.
 
+use super::journal::{Change, WriteJournal};
 use super::{BasicMemoryWriter, BatchWrite, ExtendedWrite, MemoryCopy, MemoryWrite};
 use crate::core::types::{Address, MemoryError, MemoryResult, MemoryValue};
+use crate::memory::backend::MemoryBackend;
+use crate::memory::policy::MemoryPolicy;
 use crate::process::ProcessHandle;
 use std::mem;
 
 /// Safe memory writer with validation and verification
-pub struct SafeMemoryWriter<'a> {
-    basic_writer: BasicMemoryWriter<'a>,
+pub struct SafeMemoryWriter<'a, B: MemoryBackend = ProcessHandle> {
+    basic_writer: BasicMemoryWriter<'a, B>,
     verify_writes: bool,
     check_permissions: bool,
+    journal: Option<&'a WriteJournal>,
+    policy: Option<MemoryPolicy>,
 }
 
-impl<'a> SafeMemoryWriter<'a> {
+impl<'a, B: MemoryBackend> SafeMemoryWriter<'a, B> {
     /// Create a new safe memory writer
-    pub fn new(handle: &'a ProcessHandle) -> Self {
+    pub fn new(handle: &'a B) -> Self {
         SafeMemoryWriter {
             basic_writer: BasicMemoryWriter::new(handle),
             verify_writes: true,
             check_permissions: true,
+            journal: None,
+            policy: None,
         }
     }
 
+    /// Create a writer enforcing `policy`
+    ///
+    /// If the policy backs up writes, each one is recorded in `journal`.
+    /// Writers that act on behalf of a user should all be built this way.
+    pub fn with_policy(handle: &'a B, policy: &MemoryPolicy, journal: &'a WriteJournal) -> Self {
+        let mut writer = Self::new(handle);
+        writer.policy = Some(policy.clone());
+        if policy.backup_before_write {
+            writer.journal = Some(journal);
+        }
+        writer
+    }
+
     /// Enable or disable write verification
     pub fn set_verify_writes(&mut self, verify: bool) {
         self.verify_writes = verify;
@@ -35,20 +55,34 @@ impl<'a> SafeMemoryWriter<'a> {
         self.check_permissions = check;
     }
 
+    /// Record the original bytes of every write in `journal`
+    ///
+    /// Each write becomes its own transaction, named after the operation.
+    pub fn set_journal(&mut self, journal: &'a WriteJournal) {
+        self.journal = Some(journal);
+    }
+
+    /// Refuse writes the policy protects
+    ///
+    /// Backups the policy asks for need a journal from [`set_journal`](Self::set_journal).
+    pub fn set_policy(&mut self, policy: MemoryPolicy) {
+        self.policy = Some(policy);
+    }
+
     /// Write with verification - reads back to confirm write succeeded
     pub fn write_verified<T: Copy + PartialEq>(
         &self,
         address: Address,
         value: T,
     ) -> MemoryResult<()> {
-        self.basic_writer.write(address, value)?;
-
         let size = mem::size_of::<T>();
+        self.journaled("write_verified", &[(address, size)], || {
+            self.basic_writer.write(address, value)
+        })?;
+
         let mut buffer = vec![0u8; size];
 
-        self.basic_writer
-            .handle()
-            .read_memory(address.as_usize(), &mut buffer)?;
+        self.basic_writer.handle().read(address, &mut buffer)?;
 
         let read_value = unsafe { *(buffer.as_ptr() as *const T) };
 
@@ -63,21 +97,25 @@ impl<'a> SafeMemoryWriter<'a> {
     }
 
     /// Write with automatic backup
+    ///
+    /// See [`set_journal`](Self::set_journal) for backups that are kept for you.
     pub fn write_with_backup<T: Copy>(&self, address: Address, value: T) -> MemoryResult<Vec<u8>> {
         let size = mem::size_of::<T>();
         let mut backup = vec![0u8; size];
 
-        self.basic_writer
-            .handle()
-            .read_memory(address.as_usize(), &mut backup)?;
-        self.basic_writer.write(address, value)?;
+        self.basic_writer.handle().read(address, &mut backup)?;
+        self.journaled("write_with_backup", &[(address, size)], || {
+            self.basic_writer.write(address, value)
+        })?;
 
         Ok(backup)
     }
 
     /// Restore from backup
     pub fn restore_from_backup(&self, address: Address, backup: &[u8]) -> MemoryResult<()> {
-        self.basic_writer.write_bytes(address, backup)
+        self.journaled("restore_from_backup", &[(address, backup.len())], || {
+            self.basic_writer.write_bytes(address, backup)
+        })
     }
 
     /// Check if address is writable
@@ -105,66 +143,123 @@ impl<'a> SafeMemoryWriter<'a> {
 
         Ok(())
     }
+
+    /// Run `write` over `ranges` if the policy allows, journaling the bytes it replaces
+    ///
+    /// If `write` fails, the original bytes are put back before returning.
+    fn journaled(
+        &self,
+        operation: &str,
+        ranges: &[(Address, usize)],
+        write: impl FnOnce() -> MemoryResult<()>,
+    ) -> MemoryResult<()> {
+        let handle = self.basic_writer.handle();
+        handle.policy_access().check_write(handle.pid())?;
+        if let Some(policy) = &self.policy {
+            for &(address, size) in ranges {
+                policy.check_write(handle, address, size)?;
+            }
+        }
+        let Some(journal) = self.journal.filter(|_| ranges.iter().any(|r| r.1 > 0)) else {
+            return write();
+        };
+        let read = |&(address, size): &(Address, usize)| -> MemoryResult<Vec<u8>> {
+            let mut buffer = vec![0u8; size];
+            handle.read(address, &mut buffer)?;
+            Ok(buffer)
+        };
+        let originals = ranges.iter().map(read).collect::<MemoryResult<Vec<_>>>()?;
+
+        if let Err(e) = write() {
+            for (&(address, _), original) in ranges.iter().zip(&originals).rev() {
+                let _ = self.basic_writer.write_bytes(address, original);
+            }
+            return Err(e);
+        }
+
+        let mut changes = Vec::with_capacity(ranges.len());
+        for (range, original) in ranges.iter().zip(originals) {
+            changes.push(Change {
+                address: range.0,
+                original,
+                written: read(range)?,
+            });
+        }
+        journal.record(handle.pid(), operation, changes);
+        Ok(())
+    }
 }
 
-impl<'a> MemoryWrite for SafeMemoryWriter<'a> {
+impl<'a, B: MemoryBackend> MemoryWrite for SafeMemoryWriter<'a, B> {
     fn write_bytes(&self, address: Address, data: &[u8]) -> MemoryResult<()> {
         self.check_writable(address, data.len())?;
 
-        if self.verify_writes && !data.is_empty() {
-            self.basic_writer.write_bytes(address, data)?;
-
-            let mut verify_buffer = vec![0u8; data.len()];
-            self.basic_writer
-                .handle()
-                .read_memory(address.as_usize(), &mut verify_buffer)?;
-
-            if verify_buffer != data {
-                return Err(MemoryError::WriteFailed {
-                    address: format!("0x{:X}", address.as_usize()),
-                    reason: "Verification failed: written data doesn't match".to_string(),
-                });
+        self.journaled("write_bytes", &[(address, data.len())], || {
+            if self.verify_writes && !data.is_empty() {
+                self.basic_writer.write_bytes(address, data)?;
+
+                let mut verify_buffer = vec![0u8; data.len()];
+                self.basic_writer
+                    .handle()
+                    .read(address, &mut verify_buffer)?;
+
+                if verify_buffer != data {
+                    return Err(MemoryError::WriteFailed {
+                        address: format!("0x{:X}", address.as_usize()),
+                        reason: "Verification failed: written data doesn't match".to_string(),
+                    });
+                }
+
+                Ok(())
+            } else {
+                self.basic_writer.write_bytes(address, data)
             }
-
-            Ok(())
-        } else {
-            self.basic_writer.write_bytes(address, data)
-        }
+        })
     }
 
     fn write<T: Copy>(&self, address: Address, value: T) -> MemoryResult<()> {
         let size = mem::size_of::<T>();
         self.check_writable(address, size)?;
-        self.basic_writer.write(address, value)
+        self.journaled("write", &[(address, size)], || {
+            self.basic_writer.write(address, value)
+        })
     }
 
     fn write_value(&self, address: Address, value: &MemoryValue) -> MemoryResult<()> {
-        let size = value.size();
+        let size = super::stored_bytes(value, self.basic_writer.handle().architecture()).len();
         self.check_writable(address, size)?;
-        self.basic_writer.write_value(address, value)
+        self.journaled("write_value", &[(address, size)], || {
+            self.basic_writer.write_value(address, value)
+        })
     }
 }
 
-impl<'a> ExtendedWrite for SafeMemoryWriter<'a> {
+impl<'a, B: MemoryBackend> ExtendedWrite for SafeMemoryWriter<'a, B> {
     fn write_string(&self, address: Address, value: &str) -> MemoryResult<()> {
         let size = value.len() + 1; // +1 for null terminator
         self.check_writable(address, size)?;
-        self.basic_writer.write_string(address, value)
+        self.journaled("write_string", &[(address, size)], || {
+            self.basic_writer.write_string(address, value)
+        })
     }
 
     fn write_wide_string(&self, address: Address, value: &str) -> MemoryResult<()> {
         let size = (value.encode_utf16().count() + 1) * 2; // +1 for null terminator, *2 for wide chars
         self.check_writable(address, size)?;
-        self.basic_writer.write_wide_string(address, value)
+        self.journaled("write_wide_string", &[(address, size)], || {
+            self.basic_writer.write_wide_string(address, value)
+        })
     }
 
     fn fill(&self, address: Address, value: u8, count: usize) -> MemoryResult<()> {
         self.check_writable(address, count)?;
-        self.basic_writer.fill(address, value, count)
+        self.journaled("fill", &[(address, count)], || {
+            self.basic_writer.fill(address, value, count)
+        })
     }
 }
 
-impl<'a> BatchWrite for SafeMemoryWriter<'a> {
+impl<'a, B: MemoryBackend> BatchWrite for SafeMemoryWriter<'a, B> {
     fn write_batch<T: Copy>(&self, writes: &[(Address, T)]) -> Vec<MemoryResult<()>> {
         let size = mem::size_of::<T>();
         writes
@@ -177,15 +272,19 @@ impl<'a> BatchWrite for SafeMemoryWriter<'a> {
     }
 }
 
-impl<'a> MemoryCopy for SafeMemoryWriter<'a> {
+impl<'a, B: MemoryBackend> MemoryCopy for SafeMemoryWriter<'a, B> {
     fn copy_memory(&self, source: Address, destination: Address, size: usize) -> MemoryResult<()> {
         self.check_writable(destination, size)?;
-        self.basic_writer.copy_memory(source, destination, size)
+        self.journaled("copy_memory", &[(destination, size)], || {
+            self.basic_writer.copy_memory(source, destination, size)
+        })
     }
 
     fn swap_memory(&self, addr1: Address, addr2: Address, size: usize) -> MemoryResult<()> {
         self.check_writable(addr1, size)?;
         self.check_writable(addr2, size)?;
-        self.basic_writer.swap_memory(addr1, addr2, size)
+        self.journaled("swap_memory", &[(addr1, size), (addr2, size)], || {
+            self.basic_writer.swap_memory(addr1, addr2, size)
+        })
     }
 }
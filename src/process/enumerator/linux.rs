@@ -0,0 +1,119 @@
+//! Process enumeration using `/proc`
+
+use crate::core::types::MemoryResult;
+use crate::process::info::{ProcessArchitecture, ProcessInfo};
+use std::fs;
+use std::path::PathBuf;
+
+/// Process enumerator walking the numeric entries of `/proc`
+pub struct ProcessEnumerator {
+    entries: fs::ReadDir,
+}
+
+impl ProcessEnumerator {
+    /// Create a new process enumerator
+    pub fn new() -> MemoryResult<Self> {
+        Ok(ProcessEnumerator {
+            entries: fs::read_dir("/proc")?,
+        })
+    }
+
+    /// Get the next process in the enumeration
+    fn next_process(&mut self) -> Option<ProcessInfo> {
+        for entry in self.entries.by_ref().flatten() {
+            let pid = match entry
+                .file_name()
+                .to_str()
+                .and_then(|n| n.parse::<u32>().ok())
+            {
+                Some(pid) => pid,
+                None => continue,
+            };
+
+            // The process may exit between read_dir and here
+            if let Some(info) = read_process(pid) {
+                return Some(info);
+            }
+        }
+
+        None
+    }
+}
+
+impl Iterator for ProcessEnumerator {
+    type Item = ProcessInfo;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_process()
+    }
+}
+
+/// Fields of `/proc/<pid>/stat` we care about
+#[derive(Debug, PartialEq, Eq)]
+struct StatFields {
+    comm: String,
+    parent_pid: u32,
+    thread_count: u32,
+}
+
+/// Parse `/proc/<pid>/stat`; `comm` is parenthesised and may contain spaces
+fn parse_stat(stat: &str) -> Option<StatFields> {
+    let open = stat.find('(')?;
+    let close = stat.rfind(')')?;
+    let comm = stat.get(open + 1..close)?.to_string();
+
+    // Fields after comm start at field 3 (state); ppid is 4, num_threads is 20
+    let rest: Vec<&str> = stat.get(close + 1..)?.split_whitespace().collect();
+    Some(StatFields {
+        comm,
+        parent_pid: rest.get(1)?.parse().ok()?,
+        thread_count: rest.get(17)?.parse().ok()?,
+    })
+}
+
+fn read_process(pid: u32) -> Option<ProcessInfo> {
+    let stat = parse_stat(&fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?)?;
+
+    // comm is truncated to 15 bytes, so prefer the executable name when visible
+    let path: Option<PathBuf> = fs::read_link(format!("/proc/{}/exe", pid)).ok();
+    let name = path
+        .as_ref()
+        .and_then(|p| p.file_name())
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or(stat.comm);
+
+    Some(ProcessInfo::with_details(
+        pid,
+        name,
+        path,
+        Some(stat.parent_pid),
+        ProcessArchitecture::X64,
+        stat.thread_count,
+        false,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_stat() {
+        let stat = "1234 (my (odd) proc) S 1 1234 1234 0 -1 4194560 100 0 0 0 \
+                    5 3 0 0 20 0 7 0 12345 1000000 200 18446744073709551615";
+        let fields = parse_stat(stat).unwrap();
+
+        assert_eq!(fields.comm, "my (odd) proc");
+        assert_eq!(fields.parent_pid, 1);
+        assert_eq!(fields.thread_count, 7);
+
+        assert!(parse_stat("1234 (truncated) S").is_none());
+        assert!(parse_stat("garbage").is_none());
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "FFI not supported in Miri")]
+    fn test_read_missing_process() {
+        assert!(read_process(u32::MAX).is_none());
+    }
+}
@@ -1,140 +1,23 @@
-//! Process enumeration using Windows ToolHelp32 API
-
-use crate::core::types::{MemoryError, MemoryResult};
-use crate::process::info::{ProcessArchitecture, ProcessInfo};
-use crate::windows::bindings::ntdll;
-use crate::windows::utils::string_conv::wide_to_string;
-use std::mem;
-use std::path::PathBuf;
-use winapi::shared::minwindef::FALSE;
-use winapi::um::handleapi::CloseHandle;
-use winapi::um::tlhelp32::{
-    CreateToolhelp32Snapshot, Process32First, Process32Next, PROCESSENTRY32, TH32CS_SNAPPROCESS,
-};
-use winapi::um::winnt::HANDLE;
-
-/// Process enumerator using ToolHelp32 API
-pub struct ProcessEnumerator {
-    snapshot: HANDLE,
-    first_called: bool,
-}
+//! Process enumeration
+//!
+//! Uses the ToolHelp32 snapshot API on Windows and `/proc` on Linux.
 
-impl ProcessEnumerator {
-    /// Create a new process enumerator
-    pub fn new() -> MemoryResult<Self> {
-        unsafe {
-            let snapshot = CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0);
-            if snapshot.is_null() || snapshot == winapi::um::handleapi::INVALID_HANDLE_VALUE {
-                return Err(MemoryError::WindowsApi(
-                    "Failed to create process snapshot".to_string(),
-                ));
-            }
-            Ok(ProcessEnumerator {
-                snapshot,
-                first_called: false,
-            })
-        }
-    }
+#[cfg(target_os = "linux")]
+mod linux;
+#[cfg(windows)]
+mod windows;
 
-    /// Get the next process in the enumeration
-    fn next_process(&mut self) -> Option<ProcessInfo> {
-        unsafe {
-            let mut entry: PROCESSENTRY32 = mem::zeroed();
-            entry.dwSize = mem::size_of::<PROCESSENTRY32>() as u32;
-
-            let success = if !self.first_called {
-                self.first_called = true;
-                Process32First(self.snapshot, &mut entry)
-            } else {
-                Process32Next(self.snapshot, &mut entry)
-            };
-
-            if success == FALSE {
-                return None;
-            }
-
-            // Convert the process name from fixed-size array
-            let name = {
-                let name_bytes = &entry.szExeFile;
-                let null_pos = name_bytes
-                    .iter()
-                    .position(|&c| c == 0)
-                    .unwrap_or(name_bytes.len());
-                // Convert from i8 to u8 for String conversion
-                let name_u8: Vec<u8> = name_bytes[..null_pos].iter().map(|&c| c as u8).collect();
-                String::from_utf8_lossy(&name_u8).into_owned()
-            };
-
-            // Check if process is WoW64 (32-bit on 64-bit Windows)
-            let is_wow64 = if let Ok(handle) = crate::windows::bindings::kernel32::open_process(
-                entry.th32ProcessID,
-                0x0400, // PROCESS_QUERY_INFORMATION
-            ) {
-                let result = ntdll::is_wow64_process(handle);
-                let _ = CloseHandle(handle);
-                result.unwrap_or(false)
-            } else {
-                false
-            };
-
-            // Determine architecture
-            let architecture = if is_wow64 {
-                ProcessArchitecture::X86
-            } else {
-                // On 64-bit Windows, native processes are x64
-                // On 32-bit Windows, all processes are x86
-                #[cfg(target_pointer_width = "64")]
-                {
-                    ProcessArchitecture::X64
-                }
-                #[cfg(target_pointer_width = "32")]
-                {
-                    ProcessArchitecture::X86
-                }
-            };
-
-            Some(ProcessInfo::with_details(
-                entry.th32ProcessID,
-                name,
-                None, // Path would require OpenProcess + GetModuleFileNameEx
-                Some(entry.th32ParentProcessID),
-                architecture,
-                entry.cntThreads,
-                is_wow64,
-            ))
-        }
-    }
-}
-
-impl Drop for ProcessEnumerator {
-    fn drop(&mut self) {
-        if !self.snapshot.is_null() && self.snapshot != winapi::um::handleapi::INVALID_HANDLE_VALUE
-        {
-            unsafe {
-                let _ = CloseHandle(self.snapshot);
-            }
-        }
-    }
-}
+#[cfg(target_os = "linux")]
+pub use linux::ProcessEnumerator;
+#[cfg(windows)]
+pub use windows::ProcessEnumerator;
 
-impl Iterator for ProcessEnumerator {
-    type Item = ProcessInfo;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        self.next_process()
-    }
-}
+use crate::core::types::MemoryResult;
+use crate::process::info::ProcessInfo;
 
 /// Enumerate all running processes
 pub fn enumerate_processes() -> MemoryResult<Vec<ProcessInfo>> {
-    let mut processes = Vec::new();
-    let mut enumerator = ProcessEnumerator::new()?;
-
-    while let Some(process) = enumerator.next_process() {
-        processes.push(process);
-    }
-
-    Ok(processes)
+    Ok(ProcessEnumerator::new()?.collect())
 }
 
 /// Find processes by name (case-insensitive)
@@ -160,6 +43,7 @@ pub fn get_process_by_pid(pid: u32) -> MemoryResult<Option<ProcessInfo>> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::process::info::ProcessArchitecture;
 
     #[test]
     #[cfg_attr(miri, ignore = "FFI not supported in Miri")]
@@ -169,6 +53,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(windows)]
     #[cfg_attr(miri, ignore = "FFI not supported in Miri")]
     fn test_enumerate_processes() {
         let result = enumerate_processes();
@@ -200,6 +85,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(windows)]
     #[cfg_attr(miri, ignore = "FFI not supported in Miri")]
     fn test_find_processes_by_name() {
         // System process should always exist
@@ -210,6 +96,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(windows)]
     #[cfg_attr(miri, ignore = "FFI not supported in Miri")]
     fn test_find_process_by_name() {
         let result = find_process_by_name("System");
@@ -223,6 +110,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(windows)]
     #[cfg_attr(miri, ignore = "FFI not supported in Miri")]
     fn test_get_process_by_pid() {
         // PID 4 is System process
@@ -258,6 +146,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(windows)]
     #[cfg_attr(miri, ignore = "FFI not supported in Miri")]
     fn test_process_info_details() {
         let processes = enumerate_processes().unwrap();
@@ -271,6 +160,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(windows)]
     #[cfg_attr(miri, ignore = "FFI not supported in Miri")]
     fn test_case_insensitive_search() {
         // These should all find the same process
@@ -287,4 +177,26 @@ mod tests {
         assert!(!upper.unwrap().is_empty());
         assert!(!mixed.unwrap().is_empty());
     }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    #[cfg_attr(miri, ignore = "FFI not supported in Miri")]
+    fn test_current_process_details_linux() {
+        let current = get_process_by_pid(std::process::id()).unwrap().unwrap();
+        let exe = std::env::current_exe().unwrap();
+
+        assert!(current.name_matches(&exe.file_name().unwrap().to_string_lossy()));
+        assert_eq!(current.path.as_deref(), Some(exe.as_path()));
+        assert_eq!(
+            current.parent_pid,
+            Some(std::os::unix::process::parent_id())
+        );
+        assert!(current.thread_count >= 1);
+        assert_eq!(current.architecture, ProcessArchitecture::X64);
+
+        assert!(get_process_by_pid(u32::MAX).unwrap().is_none());
+        assert!(find_process_by_name("NonExistentProcess123456")
+            .unwrap()
+            .is_none());
+    }
 }
@@ -0,0 +1,126 @@
+//! Process enumeration using the Windows ToolHelp32 API
+
+use crate::core::types::{MemoryError, MemoryResult};
+use crate::process::info::{ProcessArchitecture, ProcessInfo};
+use crate::windows::bindings::ntdll;
+use crate::windows::utils::string_conv::wide_to_string;
+use std::mem;
+use std::path::PathBuf;
+use winapi::shared::minwindef::FALSE;
+use winapi::um::handleapi::CloseHandle;
+use winapi::um::tlhelp32::{
+    CreateToolhelp32Snapshot, Process32First, Process32Next, PROCESSENTRY32, TH32CS_SNAPPROCESS,
+};
+use winapi::um::winnt::HANDLE;
+
+/// Process enumerator using ToolHelp32 API
+pub struct ProcessEnumerator {
+    snapshot: HANDLE,
+    first_called: bool,
+}
+
+impl ProcessEnumerator {
+    /// Create a new process enumerator
+    pub fn new() -> MemoryResult<Self> {
+        unsafe {
+            let snapshot = CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0);
+            if snapshot.is_null() || snapshot == winapi::um::handleapi::INVALID_HANDLE_VALUE {
+                return Err(MemoryError::WindowsApi(
+                    "Failed to create process snapshot".to_string(),
+                ));
+            }
+            Ok(ProcessEnumerator {
+                snapshot,
+                first_called: false,
+            })
+        }
+    }
+
+    /// Get the next process in the enumeration
+    fn next_process(&mut self) -> Option<ProcessInfo> {
+        unsafe {
+            let mut entry: PROCESSENTRY32 = mem::zeroed();
+            entry.dwSize = mem::size_of::<PROCESSENTRY32>() as u32;
+
+            let success = if !self.first_called {
+                self.first_called = true;
+                Process32First(self.snapshot, &mut entry)
+            } else {
+                Process32Next(self.snapshot, &mut entry)
+            };
+
+            if success == FALSE {
+                return None;
+            }
+
+            // Convert the process name from fixed-size array
+            let name = {
+                let name_bytes = &entry.szExeFile;
+                let null_pos = name_bytes
+                    .iter()
+                    .position(|&c| c == 0)
+                    .unwrap_or(name_bytes.len());
+                // Convert from i8 to u8 for String conversion
+                let name_u8: Vec<u8> = name_bytes[..null_pos].iter().map(|&c| c as u8).collect();
+                String::from_utf8_lossy(&name_u8).into_owned()
+            };
+
+            // Check if process is WoW64 (32-bit on 64-bit Windows)
+            let is_wow64 = if let Ok(handle) = crate::windows::bindings::kernel32::open_process(
+                entry.th32ProcessID,
+                0x0400, // PROCESS_QUERY_INFORMATION
+            ) {
+                let result = ntdll::is_wow64_process(handle);
+                let _ = CloseHandle(handle);
+                result.unwrap_or(false)
+            } else {
+                false
+            };
+
+            // Determine architecture
+            let architecture = if is_wow64 {
+                ProcessArchitecture::X86
+            } else {
+                // On 64-bit Windows, native processes are x64
+                // On 32-bit Windows, all processes are x86
+                #[cfg(target_pointer_width = "64")]
+                {
+                    ProcessArchitecture::X64
+                }
+                #[cfg(target_pointer_width = "32")]
+                {
+                    ProcessArchitecture::X86
+                }
+            };
+
+            Some(ProcessInfo::with_details(
+                entry.th32ProcessID,
+                name,
+                None, // Path would require OpenProcess + GetModuleFileNameEx
+                Some(entry.th32ParentProcessID),
+                architecture,
+                entry.cntThreads,
+                is_wow64,
+            ))
+        }
+    }
+}
+
+impl Drop for ProcessEnumerator {
+    fn drop(&mut self) {
+        if !self.snapshot.is_null() && self.snapshot != winapi::um::handleapi::INVALID_HANDLE_VALUE
+        {
+            unsafe {
+                let _ = CloseHandle(self.snapshot);
+            }
+        }
+    }
+}
+
+impl Iterator for ProcessEnumerator {
+    type Item = ProcessInfo;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_process()
+    }
+}
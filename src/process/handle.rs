@@ -1,9 +1,14 @@
 //! Safe process handle wrapper with RAII semantics
 
-use crate::core::types::{MemoryError, MemoryResult};
+use crate::core::types::{Address, MemoryError, MemoryResult};
+use crate::memory::backend::MemoryBackend;
+use crate::process::policy::PolicyAccess;
+#[cfg(windows)]
 use crate::windows::bindings::kernel32;
+#[cfg(windows)]
 use crate::windows::types::Handle;
 use std::fmt;
+#[cfg(windows)]
 use winapi::um::winnt::HANDLE;
 
 /// Access rights for process handles
@@ -37,13 +42,24 @@ impl ProcessAccess {
     pub fn value(&self) -> u32 {
         self.value
     }
+
+    /// Check whether every right in `other` is granted
+    pub fn contains(&self, other: Self) -> bool {
+        self.value & other.value == other.value
+    }
 }
 
-/// Safe wrapper around a Windows process handle
+/// Safe wrapper around a process handle
+///
+/// On Windows this owns a kernel handle; on Linux the process is addressed
+/// by PID and the requested access rights are enforced by the backend.
+/// The handle is `Send + Sync`, so scan threads can share one by reference.
 pub struct ProcessHandle {
+    #[cfg(windows)]
     handle: Handle,
     pid: u32,
     access: ProcessAccess,
+    pub(crate) policy: PolicyAccess,
 }
 
 impl ProcessHandle {
@@ -53,30 +69,54 @@ impl ProcessHandle {
     /// This function is intended for testing purposes only.
     /// The handle must be valid or null.
     #[doc(hidden)]
+    #[cfg(windows)]
     pub fn from_raw_handle(handle: *mut winapi::ctypes::c_void, pid: u32) -> Self {
         ProcessHandle {
             handle: Handle::new(handle),
             pid,
             access: ProcessAccess::QUERY_INFORMATION,
+            policy: PolicyAccess::ReadWrite,
         }
     }
 
     /// Create a new ProcessHandle (for internal testing only)
-    #[cfg(test)]
+    #[cfg(all(test, windows))]
     pub fn new(handle: *mut winapi::ctypes::c_void, pid: u32) -> Self {
         Self::from_raw_handle(handle, pid)
     }
 
     /// Open a process with specified access rights
+    #[cfg(windows)]
     pub fn open(pid: u32, access: ProcessAccess) -> MemoryResult<Self> {
         let raw_handle = kernel32::open_process(pid, access.value())?;
         Ok(ProcessHandle {
             handle: Handle::new(raw_handle),
             pid,
             access,
+            policy: PolicyAccess::ReadWrite,
         })
     }
 
+    /// Open a process with specified access rights
+    #[cfg(target_os = "linux")]
+    pub fn open(pid: u32, access: ProcessAccess) -> MemoryResult<Self> {
+        crate::memory::backend::linux::check_process(pid)?;
+        Ok(ProcessHandle {
+            pid,
+            access,
+            policy: PolicyAccess::ReadWrite,
+        })
+    }
+
+    /// Restrict writes to what the process policy granted
+    ///
+    /// Handles start out read-write; [`ProcessAttacher`](crate::process::ProcessAttacher)
+    /// applies the access from its policy before handing the handle out.
+    pub fn with_policy_access(mut self, policy: PolicyAccess) -> Self {
+        self.policy = policy;
+        self
+    }
+
     /// Open a process with all access rights
     pub fn open_all_access(pid: u32) -> MemoryResult<Self> {
         Self::open(pid, ProcessAccess::ALL_ACCESS)
@@ -112,6 +152,7 @@ impl ProcessHandle {
     ///
     /// # Safety
     /// The returned handle is only valid as long as this ProcessHandle exists
+    #[cfg(windows)]
     pub unsafe fn raw(&self) -> HANDLE {
         self.handle.raw()
     }
@@ -122,28 +163,26 @@ impl ProcessHandle {
     }
 
     /// Check if handle is valid
+    #[cfg(windows)]
     pub fn is_valid(&self) -> bool {
         !self.handle.is_null()
     }
 
+    /// Check if handle is valid (the process still exists)
+    #[cfg(target_os = "linux")]
+    pub fn is_valid(&self) -> bool {
+        crate::memory::backend::linux::process_exists(self.pid)
+    }
+
     /// Read memory from the process
     pub fn read_memory(&self, address: usize, buffer: &mut [u8]) -> MemoryResult<usize> {
-        if !self.is_valid() {
-            return Err(MemoryError::InvalidHandle(
-                "Process handle is null".to_string(),
-            ));
-        }
-        unsafe { kernel32::read_process_memory(self.handle.raw(), address, buffer) }
+        MemoryBackend::read(self, Address::new(address), buffer)
     }
 
-    /// Write memory to the process
+    /// Write memory to the process, unless the process policy made it read-only
     pub fn write_memory(&self, address: usize, data: &[u8]) -> MemoryResult<usize> {
-        if !self.is_valid() {
-            return Err(MemoryError::InvalidHandle(
-                "Process handle is null".to_string(),
-            ));
-        }
-        unsafe { kernel32::write_process_memory(self.handle.raw(), address, data) }
+        self.policy.check_write(self.pid)?;
+        MemoryBackend::write(self, Address::new(address), data)
     }
 }
 
@@ -173,6 +212,13 @@ mod tests {
     use super::*;
 
     #[test]
+    fn test_process_handle_is_send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<ProcessHandle>();
+    }
+
+    #[test]
+    #[cfg(windows)]
     fn test_process_handle_new() {
         let handle = ProcessHandle::new(std::ptr::null_mut(), 1234);
         assert_eq!(handle.pid(), 1234);
@@ -201,6 +247,15 @@ mod tests {
         assert_eq!(all_combined.value(), 0x0438);
     }
 
+    #[test]
+    fn test_process_access_contains() {
+        let read_write = ProcessAccess::combine(&[ProcessAccess::VM_READ, ProcessAccess::VM_WRITE]);
+        assert!(read_write.contains(ProcessAccess::VM_READ));
+        assert!(read_write.contains(ProcessAccess::VM_WRITE));
+        assert!(!read_write.contains(ProcessAccess::VM_OPERATION));
+        assert!(ProcessAccess::ALL_ACCESS.contains(read_write));
+    }
+
     #[test]
     fn test_process_access_copy() {
         let access = ProcessAccess::VM_READ;
@@ -256,12 +311,14 @@ mod tests {
     }
 
     #[test]
+    #[cfg(windows)]
     fn test_process_handle_display() {
         // Create a mock handle for testing display
         let handle = ProcessHandle {
             handle: Handle::null(),
             pid: 1234,
             access: ProcessAccess::VM_READ,
+            policy: PolicyAccess::ReadWrite,
         };
 
         let display = format!("{}", handle);
@@ -270,11 +327,13 @@ mod tests {
     }
 
     #[test]
+    #[cfg(windows)]
     fn test_process_handle_debug() {
         let handle = ProcessHandle {
             handle: Handle::null(),
             pid: 5678,
             access: ProcessAccess::ALL_ACCESS,
+            policy: PolicyAccess::ReadWrite,
         };
 
         let debug = format!("{:?}", handle);
@@ -285,11 +344,13 @@ mod tests {
     }
 
     #[test]
+    #[cfg(windows)]
     fn test_invalid_handle_operations() {
         let handle = ProcessHandle {
             handle: Handle::null(),
             pid: 1234,
             access: ProcessAccess::VM_READ,
+            policy: PolicyAccess::ReadWrite,
         };
 
         assert!(!handle.is_valid());
@@ -324,4 +385,22 @@ mod tests {
         assert!(debug.contains("ProcessAccess"));
         assert!(debug.contains("0x10") || debug.contains("16"));
     }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    #[cfg_attr(miri, ignore = "FFI not supported in Miri")]
+    fn test_process_handle_missing_process() {
+        let result = ProcessHandle::open_for_read(u32::MAX);
+        assert!(matches!(result, Err(MemoryError::ProcessNotFound(_))));
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    #[cfg_attr(miri, ignore = "FFI not supported in Miri")]
+    fn test_process_handle_display_linux() {
+        let handle = ProcessHandle::open_for_read(std::process::id()).unwrap();
+        let display = format!("{}", handle);
+        assert!(display.contains(&format!("pid={}", std::process::id())));
+        assert!(display.contains("valid=true"));
+    }
 }
@@ -1,14 +1,8 @@
 //! Module enumeration and information retrieval
 
-use crate::core::types::{Address, MemoryError, MemoryResult, ModuleInfo, ProcessId};
+use crate::core::types::{MemoryError, MemoryResult, ModuleInfo, ProcessId};
+use crate::memory::backend::MemoryBackend;
 use crate::process::ProcessHandle;
-use crate::windows::utils::string_conv::wide_to_string;
-use std::mem;
-use std::ptr;
-use winapi::shared::minwindef::{DWORD, FALSE, HMODULE, MAX_PATH};
-use winapi::um::psapi::{
-    EnumProcessModules, GetModuleBaseNameW, GetModuleFileNameExW, GetModuleInformation, MODULEINFO,
-};
 
 /// Enumerates modules loaded in a process
 pub struct ModuleEnumerator {
@@ -23,104 +17,7 @@ impl ModuleEnumerator {
 
     /// Enumerate all modules in the process
     pub fn enumerate(&self) -> MemoryResult<Vec<ModuleInfo>> {
-        // First, get the count of modules
-        let mut modules: Vec<HMODULE> = Vec::with_capacity(1024);
-        let mut cb_needed: DWORD = 0;
-
-        unsafe {
-            // Initial call to get the required buffer size
-            let result = EnumProcessModules(
-                self.handle.raw(),
-                modules.as_mut_ptr(),
-                (modules.capacity() * mem::size_of::<HMODULE>()) as DWORD,
-                &mut cb_needed,
-            );
-
-            if result == FALSE {
-                return Err(MemoryError::WindowsApi(
-                    "Failed to enumerate process modules".to_string(),
-                ));
-            }
-
-            // Calculate the actual number of modules
-            let module_count = cb_needed as usize / mem::size_of::<HMODULE>();
-            modules.set_len(module_count);
-        }
-
-        // Now get information for each module
-        let mut module_infos = Vec::with_capacity(modules.len());
-        for &module in &modules {
-            if let Ok(info) = self.get_module_info(module) {
-                module_infos.push(info);
-            }
-        }
-
-        Ok(module_infos)
-    }
-
-    /// Get information about a specific module
-    fn get_module_info(&self, module: HMODULE) -> MemoryResult<ModuleInfo> {
-        unsafe {
-            // Get module base name
-            let mut base_name: [u16; MAX_PATH] = [0; MAX_PATH];
-            let name_len = GetModuleBaseNameW(
-                self.handle.raw(),
-                module,
-                base_name.as_mut_ptr(),
-                MAX_PATH as DWORD,
-            );
-
-            if name_len == 0 {
-                return Err(MemoryError::WindowsApi(
-                    "Failed to get module base name".to_string(),
-                ));
-            }
-
-            let name = wide_to_string(&base_name[..name_len as usize]);
-
-            // Get module file path
-            let mut file_path: [u16; MAX_PATH] = [0; MAX_PATH];
-            let path_len = GetModuleFileNameExW(
-                self.handle.raw(),
-                module,
-                file_path.as_mut_ptr(),
-                MAX_PATH as DWORD,
-            );
-
-            let path = if path_len > 0 {
-                Some(wide_to_string(&file_path[..path_len as usize]))
-            } else {
-                None
-            };
-
-            // Get module information (base address and size)
-            let mut mod_info: MODULEINFO = mem::zeroed();
-            let result = GetModuleInformation(
-                self.handle.raw(),
-                module,
-                &mut mod_info,
-                mem::size_of::<MODULEINFO>() as DWORD,
-            );
-
-            if result == FALSE {
-                return Err(MemoryError::WindowsApi(
-                    "Failed to get module information".to_string(),
-                ));
-            }
-
-            let mut module_info = ModuleInfo::new(
-                name,
-                Address::from(mod_info.lpBaseOfDll as usize),
-                mod_info.SizeOfImage as usize,
-            );
-
-            // Set the path if available
-            if let Some(path_str) = path {
-                module_info.path = std::path::PathBuf::from(path_str);
-            }
-
-            Ok(module_info)
-        }
+        self.handle.enumerate_modules()
     }
 
     /// Find a module by name (case-insensitive)
@@ -176,9 +73,8 @@ mod tests {
         // Get current process handle
         let handle =
             ProcessHandle::open_for_read(process::id()).expect("Failed to open current process");
-        let enumerator = ModuleEnumerator::new(handle);
         // Just ensure it creates without panic
-        drop(enumerator);
+        let _enumerator = ModuleEnumerator::new(handle);
     }
 
     #[test]
@@ -242,6 +138,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(windows)]
     #[cfg_attr(miri, ignore = "FFI not supported in Miri")]
     fn test_find_module_by_name_helper() {
         let pid = process::id();
@@ -250,6 +147,16 @@ mod tests {
         assert!(result.unwrap().is_some());
     }
 
+    #[test]
+    #[cfg(target_os = "linux")]
+    #[cfg_attr(miri, ignore = "FFI not supported in Miri")]
+    fn test_find_libc_module_helper() {
+        let pid = process::id();
+        let module = find_module_by_name(pid, "libc.so.6").unwrap().unwrap();
+        assert!(module.path.is_absolute());
+        assert!(module.size > 0);
+    }
+
     #[test]
     #[cfg_attr(miri, ignore = "FFI not supported in Miri")]
     fn test_get_process_main_module_helper() {
@@ -1,9 +1,11 @@
 //! Safe process attachment with automatic cleanup
 
 use crate::core::types::{MemoryError, MemoryResult, ProcessId};
+use crate::memory::writer::WriteJournal;
+use crate::process::policy::{PolicyAccess, ProcessPolicy};
 use crate::process::ProcessHandle;
 use std::collections::HashSet;
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, Weak};
 
 /// Options for process attachment
 #[derive(Debug, Clone)]
@@ -30,25 +32,49 @@ impl Default for AttachOptions {
 }
 
 /// RAII guard for automatic process detachment
+///
+/// Background tasks hold the handle through [`AttachmentGuard::share`] and
+/// stop once the guard detaches. With a journal attached, every change it
+/// recorded for the process is reverted before detaching.
 pub struct AttachmentGuard {
-    handle: Option<ProcessHandle>,
+    handle: Option<Arc<ProcessHandle>>,
     pid: ProcessId,
     auto_detach: bool,
+    journal: Option<Arc<WriteJournal>>,
+    access: PolicyAccess,
 }
 
 impl AttachmentGuard {
     /// Create a new attachment guard
-    fn new(handle: ProcessHandle, pid: ProcessId, auto_detach: bool) -> Self {
+    fn new(handle: ProcessHandle, pid: ProcessId, auto_detach: bool, access: PolicyAccess) -> Self {
         AttachmentGuard {
-            handle: Some(handle),
+            handle: Some(Arc::new(handle)),
             pid,
             auto_detach,
+            journal: None,
+            access,
         }
     }
 
+    /// Revert the changes `journal` recorded for this process on detach
+    pub fn with_journal(mut self, journal: Arc<WriteJournal>) -> Self {
+        self.journal = Some(journal);
+        self
+    }
+
+    /// Journal reverted on detach, if any
+    pub fn journal(&self) -> Option<&Arc<WriteJournal>> {
+        self.journal.as_ref()
+    }
+
     /// Get the process handle
     pub fn handle(&self) -> Option<&ProcessHandle> {
-        self.handle.as_ref()
+        self.handle.as_deref()
+    }
+
+    /// Weak reference to the handle that stops upgrading once the guard detaches
+    pub fn share(&self) -> Weak<ProcessHandle> {
+        self.handle.as_ref().map(Arc::downgrade).unwrap_or_default()
     }
 
     /// Get the process ID
@@ -56,35 +82,57 @@ impl AttachmentGuard {
         self.pid
     }
 
+    /// Access the attacher's policy granted, for writers to enforce
+    pub fn access(&self) -> &PolicyAccess {
+        &self.access
+    }
+
     /// Manually detach the process
+    ///
+    /// The handle is closed even if reverting journaled changes fails.
     pub fn detach(mut self) -> MemoryResult<()> {
-        if let Some(handle) = self.handle.take() {
-            drop(handle);
-        }
-        Ok(())
+        let reverted = self.revert_journal();
+        // Dropping the handle closes it
+        self.handle = None;
+        reverted
     }
 
     /// Take ownership of the handle, preventing automatic detachment
+    ///
+    /// Returns `None` if a task holding a [`share`](Self::share) reference is
+    /// using the handle at that moment.
     pub fn into_handle(mut self) -> Option<ProcessHandle> {
         self.auto_detach = false;
-        self.handle.take()
+        self.handle
+            .take()
+            .and_then(|handle| Arc::try_unwrap(handle).ok())
+    }
+
+    fn revert_journal(&mut self) -> MemoryResult<()> {
+        match (self.journal.take(), self.handle.as_deref()) {
+            (Some(journal), Some(handle)) => journal.revert_all(handle).map(|_| ()),
+            _ => Ok(()),
+        }
     }
 }
 
 impl Drop for AttachmentGuard {
     fn drop(&mut self) {
         if self.auto_detach {
-            if let Some(handle) = self.handle.take() {
-                drop(handle);
-            }
+            let _ = self.revert_journal();
+            self.handle = None;
         }
     }
 }
 
 /// Manages process attachments with safety guarantees
+///
+/// Processes are checked against a [`ProcessPolicy`] before any handle is
+/// opened; by default only system processes are refused.
 pub struct ProcessAttacher {
     attached_pids: Arc<Mutex<HashSet<ProcessId>>>,
     default_options: AttachOptions,
+    policy: ProcessPolicy,
 }
 
 impl ProcessAttacher {
@@ -93,6 +141,7 @@ impl ProcessAttacher {
         ProcessAttacher {
             attached_pids: Arc::new(Mutex::new(HashSet::new())),
             default_options: AttachOptions::default(),
+            policy: ProcessPolicy::default(),
         }
     }
 
@@ -101,9 +150,16 @@ impl ProcessAttacher {
         ProcessAttacher {
             attached_pids: Arc::new(Mutex::new(HashSet::new())),
             default_options: options,
+            policy: ProcessPolicy::default(),
         }
     }
 
+    /// Enforce `policy` on every attachment
+    pub fn with_policy(mut self, policy: ProcessPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
     /// Attach to a process by ID
     pub fn attach(&self, pid: ProcessId) -> MemoryResult<AttachmentGuard> {
         self.attach_with_options(pid, &self.default_options)
@@ -123,6 +179,10 @@ impl ProcessAttacher {
             }
         }
 
+        let access = self
+            .policy
+            .check(pid, options.all_access || !options.read_only)?;
+
         // Open the process with appropriate access
         let handle = if options.all_access {
             ProcessHandle::open_all_access(pid)?
@@ -130,7 +190,8 @@ impl ProcessAttacher {
             ProcessHandle::open_for_read(pid)?
         } else {
             ProcessHandle::open_for_read_write(pid)?
-        };
+        }
+        .with_policy_access(access.clone());
 
         // Verify the handle is valid
         if !handle.is_valid() {
@@ -147,7 +208,7 @@ impl ProcessAttacher {
         }
 
         // Create the attachment guard with the handle
-        Ok(AttachmentGuard::new(handle, pid, true))
+        Ok(AttachmentGuard::new(handle, pid, true, access))
     }
 
     /// Get the number of attached processes
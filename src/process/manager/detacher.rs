@@ -70,9 +70,7 @@ impl ProcessDetacher {
             );
         }
 
-        // Drop the handle to close it
-        drop(handle);
-
+        // The handle is closed when it goes out of scope here
         Ok(())
     }
 
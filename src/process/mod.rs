@@ -1,4 +1,4 @@
-//! Process management functionality for Windows
+//! Process management functionality for Windows and Linux
 //!
 //! This module provides safe abstractions for process enumeration,
 //! process information retrieval, and process handle management.
@@ -7,6 +7,8 @@ pub mod enumerator;
 pub mod handle;
 pub mod info;
 pub mod manager;
+pub mod policy;
+#[cfg(windows)]
 pub mod privileges;
 
 pub use enumerator::{enumerate_processes, ProcessEnumerator};
@@ -18,6 +20,8 @@ pub use info::{
 pub use manager::{
     AttachOptions, AttachmentGuard, DetachOptions, ProcessAttacher, ProcessDetacher,
 };
+pub use policy::{PolicyAccess, ProcessPolicy};
+#[cfg(windows)]
 pub use privileges::{
     enable_debug_privilege, has_debug_privilege, require_privilege, DebugPrivilegeGuard,
     ElevationOptions, PrivilegeChecker, PrivilegeElevator, PrivilegeState,
@@ -26,15 +30,46 @@ pub use privileges::{
 use crate::core::types::MemoryResult;
 
 /// Check if we have debug privileges
+#[cfg(windows)]
 pub fn has_debug_privileges() -> bool {
     has_debug_privilege()
 }
 
+/// Check if we can access other users' processes (root or CAP_SYS_PTRACE)
+#[cfg(target_os = "linux")]
+pub fn has_debug_privileges() -> bool {
+    const CAP_SYS_PTRACE: u32 = 19;
+
+    if unsafe { libc::geteuid() } == 0 {
+        return true;
+    }
+
+    std::fs::read_to_string("/proc/self/status")
+        .ok()
+        .and_then(|status| {
+            status
+                .lines()
+                .find_map(|line| line.strip_prefix("CapEff:"))
+                .and_then(|caps| u64::from_str_radix(caps.trim(), 16).ok())
+        })
+        .is_some_and(|caps| caps & (1 << CAP_SYS_PTRACE) != 0)
+}
+
 /// Request debug privileges for the current process
+#[cfg(windows)]
 pub fn enable_debug_privileges() -> MemoryResult<()> {
     enable_debug_privilege()
 }
 
+/// Request debug privileges for the current process
+///
+/// Linux has nothing to enable: ptrace access is decided per target by
+/// credentials and the Yama policy, so this always succeeds.
+#[cfg(target_os = "linux")]
+pub fn enable_debug_privileges() -> MemoryResult<()> {
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,301 @@
+//! Which processes may be attached, and which only for reading
+//!
+//! Rules are process names, or path globs when they contain a path
+//! separator. Both match case-insensitively, with `*` standing for any run
+//! of characters and `?` for a single one.
+
+use crate::config::PolicyConfig;
+use crate::core::types::{MemoryError, MemoryResult, ProcessId};
+use crate::process::enumerator::get_process_by_pid;
+use crate::process::info::ProcessInfo;
+
+/// Access the policy grants to one process
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PolicyAccess {
+    /// Memory may be read and written
+    ReadWrite,
+    /// Memory may only be read, because of `rule`
+    ReadOnly { rule: String },
+}
+
+impl PolicyAccess {
+    /// Whether writes are allowed
+    pub fn is_writable(&self) -> bool {
+        matches!(self, PolicyAccess::ReadWrite)
+    }
+
+    /// Refuse writes to a read-only process
+    pub fn check_write(&self, pid: ProcessId) -> MemoryResult<()> {
+        match self {
+            PolicyAccess::ReadWrite => Ok(()),
+            PolicyAccess::ReadOnly { rule } => Err(MemoryError::PermissionDenied(format!(
+                "process {} is read-only by rule '{}'",
+                pid, rule
+            ))),
+        }
+    }
+}
+
+/// Allow, deny and read-only rules checked before a process is opened
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProcessPolicy {
+    /// When not empty, only matching processes may be attached
+    pub allow: Vec<String>,
+    /// Processes that may never be attached
+    pub deny: Vec<String>,
+    /// Processes that may be read but not written
+    pub read_only: Vec<String>,
+    /// Whether system processes may be attached
+    pub allow_system_processes: bool,
+}
+
+impl ProcessPolicy {
+    /// Policy described by the `[policy]` section of the configuration
+    pub fn from_config(config: &PolicyConfig) -> Self {
+        ProcessPolicy {
+            allow: config.allow.clone(),
+            deny: config.deny.clone(),
+            read_only: config.read_only.clone(),
+            allow_system_processes: config.allow_system_processes,
+        }
+    }
+
+    /// Add a rule processes must match to be attached
+    pub fn with_allow(mut self, rule: impl Into<String>) -> Self {
+        self.allow.push(rule.into());
+        self
+    }
+
+    /// Add a rule for processes that may never be attached
+    pub fn with_deny(mut self, rule: impl Into<String>) -> Self {
+        self.deny.push(rule.into());
+        self
+    }
+
+    /// Add a rule for processes that may only be read
+    pub fn with_read_only(mut self, rule: impl Into<String>) -> Self {
+        self.read_only.push(rule.into());
+        self
+    }
+
+    /// Allow or deny attaching to system processes
+    pub fn with_system_processes(mut self, allow: bool) -> Self {
+        self.allow_system_processes = allow;
+        self
+    }
+
+    /// Access granted to `process`, or `PermissionDenied` naming the rule that refused it
+    pub fn access(&self, process: &ProcessInfo) -> MemoryResult<PolicyAccess> {
+        let denied = |reason: String| {
+            Err(MemoryError::PermissionDenied(format!(
+                "process '{}' ({}) {}",
+                process.name, process.pid, reason
+            )))
+        };
+
+        if let Some(rule) = matching(&self.deny, process) {
+            return denied(format!("matches deny rule '{}'", rule));
+        }
+        if process.is_system_process() && !self.allow_system_processes {
+            return denied("is a system process".to_string());
+        }
+        if !self.allow.is_empty() && matching(&self.allow, process).is_none() {
+            return denied("matches no allow rule".to_string());
+        }
+        Ok(match matching(&self.read_only, process) {
+            Some(rule) => PolicyAccess::ReadOnly {
+                rule: rule.to_string(),
+            },
+            None => PolicyAccess::ReadWrite,
+        })
+    }
+
+    /// Access granted to the process with `pid`, looking up its name and path
+    ///
+    /// When any name or path rule is set, a process that cannot be looked up
+    /// is refused rather than matched against no rules.
+    pub fn access_pid(&self, pid: ProcessId) -> MemoryResult<PolicyAccess> {
+        if self.allow.is_empty() && self.deny.is_empty() && self.read_only.is_empty() {
+            return self.access(&ProcessInfo::new(pid, String::new()));
+        }
+        match get_process_by_pid(pid) {
+            Ok(Some(process)) => self.access(&process),
+            Ok(None) => Err(MemoryError::PermissionDenied(format!(
+                "process {} could not be found to check the policy rules",
+                pid
+            ))),
+            Err(e) => Err(MemoryError::PermissionDenied(format!(
+                "process {} could not be looked up to check the policy rules: {}",
+                pid, e
+            ))),
+        }
+    }
+
+    /// Access granted to `pid`, refusing it entirely if `write` is needed and not allowed
+    pub fn check(&self, pid: ProcessId, write: bool) -> MemoryResult<PolicyAccess> {
+        let access = self.access_pid(pid)?;
+        if write {
+            access.check_write(pid)?;
+        }
+        Ok(access)
+    }
+}
+
+/// First rule matching the process name or path
+fn matching<'r>(rules: &'r [String], process: &ProcessInfo) -> Option<&'r str> {
+    rules
+        .iter()
+        .map(String::as_str)
+        .find(|rule| rule_matches(rule, process))
+}
+
+fn rule_matches(rule: &str, process: &ProcessInfo) -> bool {
+    if rule.contains(['/', '\\']) {
+        process
+            .path
+            .as_ref()
+            .is_some_and(|path| glob_match(rule, &path.to_string_lossy()))
+    } else {
+        !process.name.is_empty() && glob_match(rule, &process.name)
+    }
+}
+
+/// Case-insensitive match of `text` against a pattern with `*` and `?`
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+
+    let (mut p, mut t) = (0, 0);
+    // Position after the last `*` and the text position it is matched up to
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p + 1, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match star {
+                Some((after, matched)) => {
+                    p = after;
+                    t = matched + 1;
+                    star = Some((after, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn process(pid: u32, name: &str, path: &str) -> ProcessInfo {
+        let mut info = ProcessInfo::new(pid, name.to_string());
+        info.path = Some(PathBuf::from(path));
+        info
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("game.exe", "Game.EXE"));
+        assert!(glob_match("steam*", "steamwebhelper"));
+        assert!(glob_match("*.exe", "notepad.exe"));
+        assert!(glob_match("g?me", "game"));
+        assert!(glob_match("/usr/*/bash", "/usr/local/bin/bash"));
+        assert!(glob_match("*", ""));
+        assert!(!glob_match("game", "game2"));
+        assert!(!glob_match("*.exe", "notepad.ex"));
+        assert!(!glob_match("g?me", "gme"));
+    }
+
+    #[test]
+    fn test_access_rules() {
+        let policy = ProcessPolicy::default()
+            .with_deny("lsass.exe")
+            .with_deny("C:\\Windows\\*")
+            .with_read_only("game*");
+        let game = process(100, "game.exe", "D:\\Games\\game.exe");
+
+        assert_eq!(
+            policy.access(&game).unwrap(),
+            PolicyAccess::ReadOnly {
+                rule: "game*".to_string()
+            }
+        );
+        assert!(policy
+            .access(&process(200, "editor.exe", "D:\\editor.exe"))
+            .unwrap()
+            .is_writable());
+
+        for (info, rule) in [
+            (process(300, "LSASS.EXE", "C:\\lsass.exe"), "lsass.exe"),
+            (
+                process(400, "svchost.exe", "C:\\Windows\\System32\\svchost.exe"),
+                "C:\\Windows\\*",
+            ),
+        ] {
+            match policy.access(&info) {
+                Err(MemoryError::PermissionDenied(message)) => {
+                    assert!(message.contains(rule), "{}", message)
+                }
+                other => panic!("Expected PermissionDenied, got {:?}", other),
+            }
+        }
+
+        let err = policy
+            .access(&game)
+            .unwrap()
+            .check_write(game.pid)
+            .unwrap_err();
+        assert!(err.to_string().contains("read-only by rule 'game*'"));
+    }
+
+    #[test]
+    fn test_allow_list_and_system_processes() {
+        let policy = ProcessPolicy::default().with_allow("game.exe");
+        assert!(policy.access(&process(100, "game.exe", "")).is_ok());
+        let err = policy.access(&process(200, "other.exe", "")).unwrap_err();
+        assert!(err.to_string().contains("no allow rule"));
+
+        let system = ProcessInfo::new(4, "System".to_string());
+        let err = ProcessPolicy::default().access(&system).unwrap_err();
+        assert!(matches!(err, MemoryError::PermissionDenied(_)));
+        assert!(ProcessPolicy::default()
+            .with_system_processes(true)
+            .access(&system)
+            .is_ok());
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "FFI not supported in Miri")]
+    fn test_check_pid() {
+        let pid = std::process::id();
+        assert!(ProcessPolicy::default().check(pid, true).is_ok());
+        assert!(ProcessPolicy::default().check(0, false).is_err());
+
+        if let Ok(Some(info)) = get_process_by_pid(pid) {
+            let policy = ProcessPolicy::default().with_read_only(info.name.clone());
+            assert!(policy.check(pid, false).is_ok());
+            assert!(matches!(
+                policy.check(pid, true),
+                Err(MemoryError::PermissionDenied(_))
+            ));
+        }
+
+        // Rules cannot be checked against a process that does not exist
+        let missing = u32::MAX - 1;
+        assert!(ProcessPolicy::default().access_pid(missing).is_ok());
+        let policy = ProcessPolicy::default().with_read_only("game.exe");
+        assert!(matches!(
+            policy.access_pid(missing),
+            Err(MemoryError::PermissionDenied(_))
+        ));
+    }
+}
@@ -404,6 +404,7 @@ fn test_value_type_conversions() {
             ValueType::F64 => assert_eq!(*value_type, ValueType::F64),
             ValueType::String => assert_eq!(*value_type, ValueType::String),
             ValueType::Bytes => assert_eq!(*value_type, ValueType::Bytes),
+            other => assert_eq!(value_type, other),
         }
     }
 }
@@ -444,6 +445,7 @@ fn test_memory_value_conversions() {
             MemoryValue::F64(_) => {}
             MemoryValue::String(s) => assert!(!s.is_empty()),
             MemoryValue::Bytes(b) => assert!(!b.is_empty()),
+            _ => {}
         }
     }
 }
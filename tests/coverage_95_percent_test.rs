@@ -1,5 +1,7 @@
 //! Comprehensive tests to achieve 95% code coverage across entire project
 
+#![cfg(windows)]
+
 use memory_mcp::core::types::{Address, ValueType};
 use memory_mcp::memory::{
     writer::{BatchWrite, ExtendedWrite, MemoryCopy, MemoryWrite},
@@ -121,10 +121,7 @@ fn test_logging_messages() {
     let config_msg = "Configuration loaded successfully";
     assert!(config_msg.contains("successfully"));
 
-    let pending_msg = "MCP server initialization pending implementation";
-    assert!(pending_msg.contains("pending"));
-
-    let ready_msg = "Memory-MCP ready. Press Ctrl+C to shutdown.";
+    let ready_msg = "Memory-MCP ready on stdio. Press Ctrl+C to shutdown.";
     assert!(ready_msg.contains("Ctrl+C"));
 
     let shutdown_msg = "Shutting down Memory-MCP server";
@@ -0,0 +1,167 @@
+//! End-to-end tests for the MCP server binary over piped stdio
+
+use serde_json::{json, Value};
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+
+/// Running server process with line-oriented access to its stdio
+struct ServerProcess {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+}
+
+impl ServerProcess {
+    fn spawn() -> Self {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_memory-mcp"))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .expect("Failed to spawn memory-mcp");
+        let stdin = child.stdin.take().unwrap();
+        let stdout = BufReader::new(child.stdout.take().unwrap());
+        ServerProcess {
+            child,
+            stdin,
+            stdout,
+        }
+    }
+
+    fn send(&mut self, message: Value) {
+        writeln!(self.stdin, "{}", message).unwrap();
+        self.stdin.flush().unwrap();
+    }
+
+    fn request(&mut self, id: u64, method: &str, params: Value) -> Value {
+        self.send(json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params }));
+        let mut line = String::new();
+        self.stdout.read_line(&mut line).unwrap();
+        let response: Value = serde_json::from_str(&line).expect("Response is not JSON");
+        assert_eq!(response["id"], json!(id));
+        response
+    }
+
+    fn call_tool(&mut self, id: u64, name: &str, arguments: Value) -> Value {
+        let response = self.request(
+            id,
+            "tools/call",
+            json!({ "name": name, "arguments": arguments }),
+        );
+        response["result"].clone()
+    }
+
+    fn finish(mut self) {
+        drop(self.stdin);
+        let status = self.child.wait().unwrap();
+        assert!(status.success());
+    }
+}
+
+/// Extracts the JSON payload from a tool result's text content
+fn tool_payload(result: &Value) -> Value {
+    let text = result["content"][0]["text"].as_str().unwrap();
+    serde_json::from_str(text).unwrap()
+}
+
+#[test]
+#[cfg_attr(miri, ignore = "Process spawning not supported in Miri")]
+fn test_initialize_and_list_tools() {
+    let mut server = ServerProcess::spawn();
+
+    let init = server.request(
+        1,
+        "initialize",
+        json!({
+            "protocolVersion": "2024-11-05",
+            "capabilities": {},
+            "clientInfo": { "name": "test", "version": "0" }
+        }),
+    );
+    assert_eq!(init["result"]["serverInfo"]["name"], json!("memory-mcp"));
+
+    server.send(json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }));
+
+    let list = server.request(2, "tools/list", json!({}));
+    let names: Vec<&str> = list["result"]["tools"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|t| t["name"].as_str().unwrap())
+        .collect();
+    for expected in [
+        "list_processes",
+        "list_modules",
+        "read_memory",
+        "write_memory",
+        "scan_memory",
+    ] {
+        assert!(names.contains(&expected), "missing tool {}", expected);
+    }
+
+    server.finish();
+}
+
+#[test]
+#[cfg_attr(miri, ignore = "Process spawning not supported in Miri")]
+fn test_list_processes_contains_server() {
+    let mut server = ServerProcess::spawn();
+    let server_pid = server.child.id();
+
+    let result = server.call_tool(1, "list_processes", json!({}));
+    assert_eq!(result["isError"], json!(false));
+
+    let payload = tool_payload(&result);
+    let found = payload["processes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|p| p["pid"] == json!(server_pid));
+    assert!(found, "server process should be listed");
+
+    server.finish();
+}
+
+#[test]
+#[cfg_attr(miri, ignore = "Process spawning not supported in Miri")]
+fn test_read_own_module_header() {
+    let mut server = ServerProcess::spawn();
+    let server_pid = server.child.id();
+
+    let modules = tool_payload(&server.call_tool(1, "list_modules", json!({ "pid": server_pid })));
+    let base = modules["modules"][0]["base_address"].as_u64().unwrap();
+
+    let result = server.call_tool(
+        2,
+        "read_memory",
+        json!({ "pid": server_pid, "address": format!("0x{:X}", base), "value_type": "u16" }),
+    );
+    assert_eq!(result["isError"], json!(false));
+
+    let payload = tool_payload(&result);
+    let magic = payload["value"]["value"].as_u64().unwrap();
+    #[cfg(windows)]
+    assert_eq!(magic, 0x5A4D); // "MZ"
+    #[cfg(not(windows))]
+    assert_eq!(magic, 0x457F); // "\x7FE"
+
+    server.finish();
+}
+
+#[test]
+#[cfg_attr(miri, ignore = "Process spawning not supported in Miri")]
+fn test_tool_errors() {
+    let mut server = ServerProcess::spawn();
+
+    let unknown = server.request(
+        1,
+        "tools/call",
+        json!({ "name": "does_not_exist", "arguments": {} }),
+    );
+    assert_eq!(unknown["error"]["code"], json!(-32602));
+
+    let failed = server.call_tool(2, "scan_memory", json!({ "pid": 1 }));
+    assert_eq!(failed["isError"], json!(true));
+
+    server.finish();
+}
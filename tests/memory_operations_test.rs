@@ -145,8 +145,7 @@ fn test_memory_reader_value_types() {
 }
 
 #[test]
-#[cfg(windows)]
-#[cfg_attr(miri, ignore = "Miri doesn't support Windows memory APIs")]
+#[cfg_attr(miri, ignore = "FFI not supported in Miri")]
 fn test_memory_writer_with_current_process() {
     // Get handle with write access for current process
     let pid = process::id();
@@ -161,7 +160,7 @@ fn test_memory_writer_with_current_process() {
     let data = vec![1u8, 2, 3, 4];
     let result = writer.write_bytes(buffer_addr, &data);
 
-    // On Windows with proper permissions, this should work
+    // With proper permissions, this should work
     if result.is_ok() {
         assert_eq!(&test_buffer[..4], &data[..]);
 
@@ -340,18 +340,17 @@ fn test_safe_reader_all_value_types() {
 
     // Test Bytes type
     let bytes_data: [u8; 10] = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9];
+    let bytes_address = Address::new(&bytes_data as *const _ as usize);
     if let MemoryValue::Bytes(val) = reader
-        .read_value(
-            Address::new(&bytes_data as *const _ as usize),
-            ValueType::Bytes,
-        )
+        .read_value_len(bytes_address, ValueType::Bytes, Some(10))
         .unwrap()
     {
-        assert!(val.len() == 256); // Default size for Bytes
-        assert_eq!(&val[..10], &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        assert_eq!(val, vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
     } else {
         panic!("Expected Bytes");
     }
+    // Bytes have no implied length
+    assert!(reader.read_value(bytes_address, ValueType::Bytes).is_err());
 }
 
 #[test]
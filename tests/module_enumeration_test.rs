@@ -11,10 +11,8 @@ use std::process;
 fn test_module_enumerator_with_current_process() {
     let handle =
         ProcessHandle::open_for_read(process::id()).expect("Failed to open current process");
-    let enumerator = ModuleEnumerator::new(handle);
-
     // Enumerator should be created without issues
-    drop(enumerator);
+    let _enumerator = ModuleEnumerator::new(handle);
 }
 
 #[test]
@@ -40,6 +38,7 @@ fn test_enumerate_current_process_modules() {
 }
 
 #[test]
+#[cfg(windows)]
 #[cfg_attr(miri, ignore = "FFI not supported in Miri")]
 fn test_find_system_dlls() {
     let handle =
@@ -72,6 +71,7 @@ fn test_find_system_dlls() {
 }
 
 #[test]
+#[cfg(windows)]
 #[cfg_attr(miri, ignore = "FFI not supported in Miri")]
 fn test_get_main_module() {
     let handle =
@@ -142,13 +142,16 @@ fn test_enumerate_modules_helper_function() {
     );
 
     // Verify we can find system DLLs
+    #[cfg(windows)]
     let has_kernel32 = modules
         .iter()
         .any(|m| m.name.eq_ignore_ascii_case("kernel32.dll"));
+    #[cfg(windows)]
     assert!(has_kernel32, "kernel32.dll not found in module list");
 }
 
 #[test]
+#[cfg(windows)]
 #[cfg_attr(miri, ignore = "FFI not supported in Miri")]
 fn test_find_module_by_name_helper() {
     let pid = process::id();
@@ -171,6 +174,7 @@ fn test_find_module_by_name_helper() {
 }
 
 #[test]
+#[cfg(windows)]
 #[cfg_attr(miri, ignore = "FFI not supported in Miri")]
 fn test_get_process_main_module_helper() {
     let pid = process::id();
@@ -184,6 +188,7 @@ fn test_get_process_main_module_helper() {
 }
 
 #[test]
+#[cfg(windows)]
 #[cfg_attr(miri, ignore = "FFI not supported in Miri")]
 fn test_case_insensitive_module_search() {
     let handle =
@@ -239,3 +244,19 @@ fn test_module_address_ranges() {
         }
     }
 }
+
+#[test]
+#[cfg(target_os = "linux")]
+#[cfg_attr(miri, ignore = "FFI not supported in Miri")]
+fn test_get_main_module_linux() {
+    let exe = std::env::current_exe().expect("Failed to get current executable");
+    let main_module = get_process_main_module(process::id()).expect("Failed to get main module");
+
+    // The main module is the test binary itself, mapped from its ELF header
+    assert_eq!(main_module.path, exe);
+    assert!(main_module.size > 0);
+    assert!(!main_module.base_address.is_null());
+
+    let libc = find_module_by_name(process::id(), "LIBC.SO.6").expect("Failed to search");
+    assert!(libc.is_some(), "libc.so.6 not found");
+}
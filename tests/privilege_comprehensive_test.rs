@@ -1,5 +1,7 @@
 //! Comprehensive tests for privilege management to achieve 95%+ coverage
 
+#![cfg(windows)]
+
 use memory_mcp::process::{
     enable_debug_privilege, has_debug_privilege, require_privilege, DebugPrivilegeGuard,
     ElevationOptions, PrivilegeChecker, PrivilegeElevator, PrivilegeState,
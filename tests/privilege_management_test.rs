@@ -1,5 +1,7 @@
 //! Integration tests for privilege management
 
+#![cfg(windows)]
+
 use memory_mcp::process::{
     enable_debug_privilege, has_debug_privilege, require_privilege, DebugPrivilegeGuard,
     ElevationOptions, PrivilegeChecker, PrivilegeElevator, PrivilegeState,
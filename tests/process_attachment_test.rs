@@ -154,6 +154,19 @@ fn test_attachment_guard_into_handle() {
     }
 }
 
+#[test]
+#[cfg_attr(miri, ignore = "FFI not supported in Miri")]
+fn test_attachment_guard_share_ends_on_drop() {
+    let attacher = ProcessAttacher::new();
+
+    if let Ok(guard) = attacher.attach(std::process::id()) {
+        let shared = guard.share();
+        assert!(shared.upgrade().is_some());
+        drop(guard);
+        assert!(shared.upgrade().is_none());
+    }
+}
+
 #[test]
 fn test_detach_all() {
     let attacher = ProcessAttacher::new();
@@ -234,3 +247,79 @@ fn test_detacher_history() {
     detacher.clear_history();
     assert_eq!(detacher.get_detach_history().len(), 0);
 }
+
+#[test]
+#[cfg_attr(miri, ignore = "FFI not supported in Miri")]
+fn test_attachment_guard_reverts_journal_on_detach() {
+    use memory_mcp::core::types::Address;
+    use memory_mcp::memory::writer::MemoryWrite;
+    use memory_mcp::memory::{SafeMemoryWriter, WriteJournal};
+    use std::sync::Arc;
+
+    let target: &'static mut u32 = Box::leak(Box::new(7));
+    let address = Address::new(target as *mut u32 as usize);
+    let options = AttachOptions {
+        all_access: true,
+        read_only: false,
+        ..AttachOptions::default()
+    };
+    let journal = Arc::new(WriteJournal::new());
+
+    if let Ok(guard) = ProcessAttacher::new().attach_with_options(std::process::id(), &options) {
+        let guard = guard.with_journal(journal.clone());
+        let mut writer = SafeMemoryWriter::new(guard.handle().unwrap());
+        writer.set_journal(&journal);
+        if writer.write(address, 42u32).is_ok() {
+            assert_eq!(journal.patches_for(std::process::id()).len(), 1);
+            guard.detach().unwrap();
+            assert_eq!(unsafe { std::ptr::read_volatile(target) }, 7);
+            assert!(journal.patches().is_empty());
+        }
+    }
+}
+
+#[test]
+#[cfg_attr(miri, ignore = "FFI not supported in Miri")]
+fn test_attach_enforces_process_policy() {
+    use memory_mcp::core::types::MemoryError;
+    use memory_mcp::process::{PolicyAccess, ProcessPolicy};
+
+    let pid = std::process::id();
+    let denied = ProcessAttacher::new().with_policy(ProcessPolicy::default().with_deny("*"));
+    match denied.attach(pid) {
+        Err(MemoryError::PermissionDenied(message)) => assert!(message.contains("deny rule '*'")),
+        other => panic!("Expected PermissionDenied, got {:?}", other.err()),
+    }
+    assert!(!denied.is_attached(pid));
+
+    let read_only =
+        ProcessAttacher::new().with_policy(ProcessPolicy::default().with_read_only("*"));
+    let options = AttachOptions {
+        read_only: false,
+        ..AttachOptions::default()
+    };
+    assert!(matches!(
+        read_only.attach_with_options(pid, &options),
+        Err(MemoryError::PermissionDenied(_))
+    ));
+    if let Ok(guard) = read_only.attach(pid) {
+        assert_eq!(
+            guard.access(),
+            &PolicyAccess::ReadOnly {
+                rule: "*".to_string()
+            }
+        );
+        // The handle carries the access, so no writer can bypass it
+        let handle = guard.handle().unwrap();
+        assert!(matches!(
+            handle.write_memory(0x10000, &[0]),
+            Err(MemoryError::PermissionDenied(_))
+        ));
+    }
+
+    // System processes are refused by default
+    assert!(matches!(
+        ProcessAttacher::new().attach(4),
+        Err(MemoryError::PermissionDenied(_))
+    ));
+}
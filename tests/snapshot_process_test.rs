@@ -0,0 +1,531 @@
+//! Integration tests running the memory subsystems against a `SnapshotProcess`
+
+use memory_mcp::core::types::{
+    Address, MemoryError, MemoryValue, ModuleInfo, ProcessArchitecture, ValueEncoding, ValueType,
+};
+use memory_mcp::memory::{
+    writer::{ExtendedWrite, MemoryWrite},
+    BasicMemoryWriter, FilterCriteria, MemoryBackend, MemoryPolicy, MemoryReader, MemoryScanner,
+    RegionFilter, RegionState, RegionType, SafeMemoryReader, SafeMemoryWriter, ScanOptions,
+    ScanPattern, SnapshotProcess, SnapshotRegion, StructAccessor, StructRegistry, WriteJournal,
+};
+use memory_mcp::process::{ModuleEnumerator, ProcessHandle};
+
+const PAGE_READONLY: u32 = 0x02;
+const PAGE_READWRITE: u32 = 0x04;
+const PAGE_EXECUTE_READ: u32 = 0x20;
+
+const CODE_BASE: usize = 0x140000000;
+const HEAP_BASE: usize = 0x200000;
+const RDATA_BASE: usize = 0x140002000;
+
+/// A small fake game: code, read-only data and a heap holding a health value
+fn game_snapshot() -> SnapshotProcess {
+    let mut code = vec![0xCC; 0x1000];
+    code[0x100..0x107].copy_from_slice(&[0x48, 0x8B, 0x05, 0x10, 0x20, 0x30, 0x40]);
+
+    let mut heap = vec![0u8; 0x1000];
+    heap[0x40..0x44].copy_from_slice(&100i32.to_le_bytes());
+    heap[0x80..0x84].copy_from_slice(&100i32.to_le_bytes());
+    heap[0x100..0x104].copy_from_slice(&1.5f32.to_le_bytes());
+
+    let mut rdata = vec![0u8; 0x1000];
+    rdata[..6].copy_from_slice(b"Player");
+
+    let mut snapshot = SnapshotProcess::new(4242);
+    snapshot
+        .add_region(
+            SnapshotRegion::new(Address::new(CODE_BASE), code, PAGE_EXECUTE_READ)
+                .with_type(RegionType::Image),
+        )
+        .unwrap();
+    snapshot
+        .add_region(
+            SnapshotRegion::new(Address::new(RDATA_BASE), rdata, PAGE_READONLY)
+                .with_type(RegionType::Image),
+        )
+        .unwrap();
+    snapshot
+        .add_region(SnapshotRegion::new(
+            Address::new(HEAP_BASE),
+            heap,
+            PAGE_READWRITE,
+        ))
+        .unwrap();
+    snapshot.add_module(ModuleInfo::new(
+        "game.exe".to_string(),
+        Address::new(CODE_BASE),
+        0x3000,
+    ));
+    snapshot
+}
+
+#[test]
+fn test_readers_against_snapshot() {
+    let snapshot = game_snapshot();
+
+    let reader = MemoryReader::new(&snapshot);
+    assert_eq!(
+        reader.read::<i32>(Address::new(HEAP_BASE + 0x40)).unwrap(),
+        100
+    );
+    assert_eq!(
+        reader
+            .read_value(Address::new(HEAP_BASE + 0x100), ValueType::F32)
+            .unwrap(),
+        MemoryValue::F32(1.5)
+    );
+
+    let safe = SafeMemoryReader::new(&snapshot);
+    assert_eq!(safe.read::<u8>(Address::new(RDATA_BASE)).unwrap(), b'P');
+    assert!(safe.is_readable(Address::new(HEAP_BASE), 4));
+    assert!(!safe.is_readable(Address::new(0x300000), 4));
+    assert!(safe.read::<u32>(Address::new(0x1000)).is_err());
+}
+
+#[test]
+fn test_writers_against_snapshot() {
+    let snapshot = game_snapshot();
+    let health = Address::new(HEAP_BASE + 0x40);
+
+    let writer = BasicMemoryWriter::new(&snapshot);
+    writer.write(health, 250i32).unwrap();
+    writer
+        .write_value(Address::new(HEAP_BASE + 0x100), &MemoryValue::F32(3.0))
+        .unwrap();
+    writer
+        .fill(Address::new(HEAP_BASE + 0x200), 0x7F, 8)
+        .unwrap();
+
+    let safe = SafeMemoryWriter::new(&snapshot);
+    safe.write_verified(Address::new(HEAP_BASE + 0x80), 999i32)
+        .unwrap();
+
+    // Code and read-only data reject writes like a real process would
+    assert!(writer.write(Address::new(RDATA_BASE), 0u8).is_err());
+    assert!(safe.write(Address::new(CODE_BASE + 0x100), 0x90u8).is_err());
+
+    let reader = MemoryReader::new(&snapshot);
+    assert_eq!(reader.read::<i32>(health).unwrap(), 250);
+    assert_eq!(
+        reader.read::<i32>(Address::new(HEAP_BASE + 0x80)).unwrap(),
+        999
+    );
+    assert_eq!(
+        reader.read::<f32>(Address::new(HEAP_BASE + 0x100)).unwrap(),
+        3.0
+    );
+    assert_eq!(
+        reader.read::<u64>(Address::new(HEAP_BASE + 0x200)).unwrap(),
+        0x7F7F_7F7F_7F7F_7F7F
+    );
+    assert_eq!(reader.read::<u8>(Address::new(RDATA_BASE)).unwrap(), b'P');
+}
+
+#[test]
+fn test_scanner_against_snapshot() {
+    let snapshot = game_snapshot();
+    let scanner = MemoryScanner::new(&snapshot);
+
+    let hits = scanner.find_value(100i32, ScanOptions::default()).unwrap();
+    assert_eq!(
+        hits,
+        vec![
+            Address::new(HEAP_BASE + 0x40),
+            Address::new(HEAP_BASE + 0x80)
+        ]
+    );
+
+    let code = scanner
+        .scan(
+            &ScanPattern::Exact(vec![0x48, 0x8B, 0x05]),
+            ScanOptions {
+                executable_only: true,
+                ..ScanOptions::default()
+            },
+        )
+        .unwrap();
+    assert_eq!(code, vec![Address::new(CODE_BASE + 0x100)]);
+
+    let strings = scanner
+        .scan(
+            &ScanPattern::Exact(b"Player".to_vec()),
+            ScanOptions::default(),
+        )
+        .unwrap();
+    assert_eq!(strings, vec![Address::new(RDATA_BASE)]);
+}
+
+#[test]
+fn test_region_filter_against_snapshot() {
+    let snapshot = game_snapshot();
+    let regions = snapshot.enumerate_regions().unwrap();
+
+    let committed = RegionFilter::new(FilterCriteria::new().committed_memory_only());
+    assert_eq!(committed.count(&regions), 3);
+
+    let writable = RegionFilter::new(FilterCriteria::new().writable()).apply(&regions);
+    assert_eq!(writable.len(), 1);
+    assert_eq!(writable[0].base_address, Address::new(HEAP_BASE));
+
+    let images = RegionFilter::new(FilterCriteria::new().with_type(RegionType::Image));
+    assert_eq!(
+        images
+            .apply(&regions)
+            .iter()
+            .filter(|r| r.state == RegionState::Committed)
+            .count(),
+        2
+    );
+}
+
+#[test]
+fn test_dump_replay() {
+    let snapshot = game_snapshot();
+    BasicMemoryWriter::new(&snapshot)
+        .write(Address::new(HEAP_BASE + 0x40), 77i32)
+        .unwrap();
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("game.dump");
+    snapshot.save(&path).unwrap();
+
+    let replay = SnapshotProcess::load(&path).unwrap();
+    assert_eq!(replay.pid(), 4242);
+    assert_eq!(replay.regions(), snapshot.regions());
+    assert_eq!(replay.enumerate_modules().unwrap()[0].name, "game.exe");
+
+    let hits = MemoryScanner::new(&replay)
+        .find_value(77i32, ScanOptions::default())
+        .unwrap();
+    assert_eq!(hits, vec![Address::new(HEAP_BASE + 0x40)]);
+}
+
+#[test]
+#[cfg_attr(miri, ignore = "FFI not supported in Miri")]
+fn test_capture_live_process() {
+    let marker: u64 = 0x5EED_CAFE_F00D_BEEF;
+    let marker_address = Address::from(&marker as *const u64 as usize);
+
+    let handle = ProcessHandle::open_for_read(std::process::id()).unwrap();
+    let snapshot = SnapshotProcess::capture(&handle).unwrap();
+
+    assert_eq!(snapshot.pid(), std::process::id());
+    assert_eq!(
+        MemoryReader::new(&snapshot)
+            .read::<u64>(marker_address)
+            .unwrap(),
+        marker
+    );
+
+    let modules = ModuleEnumerator::new(handle).enumerate().unwrap();
+    assert_eq!(snapshot.modules()[0].name, modules[0].name);
+}
+
+#[test]
+fn test_encoded_values_round_trip_through_writer_and_readers() {
+    let snapshot = game_snapshot();
+    let address = Address::new(HEAP_BASE + 0x200);
+    let encoding = ValueEncoding::big_endian()
+        .with_xor(0x5A5A)
+        .with_linear(0.1, 0.0);
+
+    SafeMemoryWriter::new(&snapshot)
+        .write_encoded(address, &MemoryValue::U16(250), &encoding)
+        .unwrap();
+    // 250 / 0.1 = 2500 = 0x09C4, XOR 0x5A5A = 0x539E, stored big-endian
+    let mut raw = [0u8; 2];
+    snapshot.read(address, &mut raw).unwrap();
+    assert_eq!(raw, [0x53, 0x9E]);
+
+    let value = MemoryReader::new(&snapshot)
+        .read_encoded(address, ValueType::U16, &encoding)
+        .unwrap();
+    assert_eq!(value, MemoryValue::U16(250));
+    let value = SafeMemoryReader::new(&snapshot)
+        .read_encoded(address, ValueType::U16, &ValueEncoding::big_endian())
+        .unwrap();
+    assert_eq!(value, MemoryValue::U16(0x539E));
+    assert!(MemoryReader::new(&snapshot)
+        .read_encoded(address, ValueType::String, &encoding)
+        .is_err());
+}
+
+#[test]
+fn test_extended_value_types_through_writer_and_readers() {
+    let snapshot = game_snapshot().with_architecture(ProcessArchitecture::X86);
+    let writer = BasicMemoryWriter::new(&snapshot);
+    let reader = SafeMemoryReader::new(&snapshot);
+    let pointers = ValueType::array(ValueType::Pointer, 2).unwrap();
+
+    let values = [
+        (0x300, MemoryValue::WString("Hero".to_string())),
+        (0x320, MemoryValue::Pointer(Address::new(HEAP_BASE + 0x40))),
+        (0x330, MemoryValue::Bool(true)),
+        (0x340, MemoryValue::I128(-5)),
+        (
+            0x360,
+            MemoryValue::from_bytes_for(
+                &[1, 0, 0, 0, 2, 0, 0, 0],
+                pointers,
+                ProcessArchitecture::X86,
+            )
+            .unwrap(),
+        ),
+    ];
+    for (offset, value) in &values {
+        let address = Address::new(HEAP_BASE + offset);
+        writer.write_value(address, value).unwrap();
+        assert_eq!(
+            &reader.read_value(address, value.value_type()).unwrap(),
+            value
+        );
+    }
+
+    // Pointers in a 32-bit target take four bytes
+    let mut raw = [0u8; 8];
+    snapshot
+        .read(Address::new(HEAP_BASE + 0x320), &mut raw)
+        .unwrap();
+    assert_eq!(raw, [0x40, 0, 0x20, 0, 0, 0, 0, 0]);
+    assert_eq!(pointers.size_for(snapshot.architecture()), Some(8));
+}
+
+#[test]
+fn test_strings_and_bytes_read_to_their_length() {
+    let mut snapshot = SnapshotProcess::new(1);
+    let mut data = vec![0x41u8; 0x1000];
+    data[0xFFC..].copy_from_slice(b"end\0");
+    snapshot
+        .add_region(SnapshotRegion::new(
+            Address::new(0x10000),
+            data,
+            PAGE_READWRITE,
+        ))
+        .unwrap();
+    let reader = MemoryReader::new(&snapshot);
+
+    // A string ending 4 bytes before unmapped memory reads without touching it
+    let tail = Address::new(0x10FFC);
+    assert_eq!(
+        reader.read_value(tail, ValueType::String).unwrap(),
+        MemoryValue::String("end".to_string())
+    );
+    assert_eq!(
+        reader
+            .read_value_len(Address::new(0x10000), ValueType::String, Some(5))
+            .unwrap(),
+        MemoryValue::String("AAAAA".to_string())
+    );
+    BasicMemoryWriter::new(&snapshot)
+        .write_wide_string(Address::new(0x10100), "wide")
+        .unwrap();
+    assert_eq!(
+        reader
+            .read_value(Address::new(0x10100), ValueType::WString)
+            .unwrap(),
+        MemoryValue::WString("wide".to_string())
+    );
+    assert_eq!(
+        reader
+            .read_value_len(tail, ValueType::Bytes, Some(3))
+            .unwrap(),
+        MemoryValue::Bytes(b"end".to_vec())
+    );
+    assert!(reader.read_value(tail, ValueType::Bytes).is_err());
+
+    // Without its terminator the string runs into unmapped memory
+    snapshot.write(Address::new(0x10FFF), b"!").unwrap();
+    assert!(reader.read_value(tail, ValueType::String).is_err());
+}
+
+const PLAYER_STRUCTS: &str = r#"
+[[structs]]
+name = "Item"
+size = 8
+fields = [
+    { name = "id", offset = 0, type = "u32" },
+    { name = "count", offset = 4, type = "u16" },
+]
+
+[[structs]]
+name = "Player"
+fields = [
+    { name = "health", offset = 0x00, type = "i32" },
+    { name = "name", offset = 0x08, type = "string", length = 16 },
+    { name = "inventory", offset = 0x20, struct = "Item", count = 4 },
+    { name = "target", offset = 0x40, struct = "Player", pointer = true },
+    { name = "position", offset = 0x48, type = { array = { elem = "f32", len = 3 } } },
+]
+"#;
+
+#[test]
+fn test_structs_read_as_json_and_written_by_path() {
+    let registry = StructRegistry::from_toml(PLAYER_STRUCTS).unwrap();
+    let player = registry.get("Player").unwrap();
+    let mut snapshot = SnapshotProcess::new(7).with_architecture(ProcessArchitecture::X64);
+    snapshot
+        .add_region(SnapshotRegion::new(
+            Address::new(0x10000),
+            vec![0; 0x1000],
+            PAGE_READWRITE,
+        ))
+        .unwrap();
+    let writer = BasicMemoryWriter::new(&snapshot);
+    let (hero, foe) = (Address::new(0x10000), Address::new(0x10100));
+    writer.write(hero, 100i32).unwrap();
+    writer.write_string(hero.offset(0x08), "hero").unwrap();
+    for i in 0..4u16 {
+        writer
+            .write(hero.offset(0x20 + 8 * i as isize), i as u32 + 1)
+            .unwrap();
+        writer
+            .write(hero.offset(0x24 + 8 * i as isize), i * 10)
+            .unwrap();
+    }
+    writer
+        .write(hero.offset(0x40), foe.as_usize() as u64)
+        .unwrap();
+    writer.write(foe, 50i32).unwrap();
+    writer
+        .write(foe.offset(0x40), hero.as_usize() as u64)
+        .unwrap();
+
+    let accessor = StructAccessor::new(&snapshot, &registry).with_max_depth(1);
+    let json = accessor.read_struct(hero, player).unwrap();
+    assert_eq!(json["health"], 100);
+    assert_eq!(json["name"], "hero");
+    assert_eq!(json["inventory"][2]["id"], 3);
+    assert_eq!(json["inventory"][3]["count"], 30);
+    assert_eq!(json["position"], serde_json::json!([0.0, 0.0, 0.0]));
+    // One pointer deep, then the pointer itself
+    assert_eq!(json["target"]["health"], 50);
+    assert_eq!(json["target"]["target"], hero.to_string());
+    assert_eq!(
+        accessor.read_struct(foe.offset(0x200), player).unwrap()["target"],
+        serde_json::Value::Null
+    );
+
+    accessor
+        .write_field(
+            hero,
+            player,
+            "player.inventory[3].count",
+            &MemoryValue::U16(99),
+        )
+        .unwrap();
+    accessor
+        .write_field(hero, player, "target.health", &MemoryValue::I32(5))
+        .unwrap();
+    accessor
+        .write_field(hero, player, "position[1]", &MemoryValue::F32(2.5))
+        .unwrap();
+    let reader = SafeMemoryReader::new(&snapshot);
+    assert_eq!(reader.read::<u16>(hero.offset(0x3C)).unwrap(), 99);
+    assert_eq!(reader.read::<i32>(foe).unwrap(), 5);
+    assert_eq!(
+        accessor.read_field(hero, player, "position[1]").unwrap(),
+        MemoryValue::F32(2.5)
+    );
+
+    for (path, value) in [
+        ("inventory[3].count", MemoryValue::U32(1)),
+        ("inventory[4].count", MemoryValue::U16(1)),
+        ("inventory.count", MemoryValue::U16(1)),
+        ("target.target.target.mana", MemoryValue::I32(1)),
+        ("name", MemoryValue::String("sixteen letters!".to_string())),
+        ("target", MemoryValue::I32(1)),
+    ] {
+        assert!(
+            accessor.write_field(hero, player, path, &value).is_err(),
+            "{}",
+            path
+        );
+    }
+    // Under a policy with backups, field writes are journaled
+    let journal = WriteJournal::new();
+    let backed_up =
+        StructAccessor::new(&snapshot, &registry).with_policy(&MemoryPolicy::default(), &journal);
+    backed_up
+        .write_field(hero, player, "health", &MemoryValue::I32(1))
+        .unwrap();
+    assert_eq!(journal.patches().len(), 1);
+    assert_eq!(journal.revert_all(&snapshot).unwrap(), 1);
+    assert_eq!(reader.read::<i32>(hero).unwrap(), 100);
+}
+
+#[test]
+fn test_policy_limits_reads_and_protects_code() {
+    let snapshot = game_snapshot();
+    let policy = MemoryPolicy::default().with_max_read_size(0x100);
+
+    let reader = SafeMemoryReader::new(&snapshot).with_policy(policy.clone());
+    assert!(reader.read_raw(Address::new(HEAP_BASE), 0x100).is_ok());
+    assert!(matches!(
+        reader.read_raw(Address::new(HEAP_BASE), 0x101),
+        Err(MemoryError::ReadLimitExceeded { limit: 0x100, .. })
+    ));
+    let mut cached = MemoryReader::new(&snapshot).with_policy(policy.clone());
+    assert!(cached.read_bytes(Address::new(HEAP_BASE), 0x200).is_err());
+
+    let journal = WriteJournal::new();
+    let mut writer = SafeMemoryWriter::with_policy(&snapshot, &policy, &journal);
+    assert!(matches!(
+        writer.write_bytes(Address::new(CODE_BASE + 0x100), &[0x90; 7]),
+        Err(MemoryError::WriteProtected { .. })
+    ));
+    writer
+        .write(Address::new(HEAP_BASE + 0x40), 999i32)
+        .unwrap();
+    assert_eq!(journal.patches().len(), 1);
+
+    // Once allowed, the write gets past the policy to the read-only page itself
+    writer.set_policy(policy.with_protected_writes(true));
+    assert!(matches!(
+        writer.write_bytes(Address::new(CODE_BASE + 0x100), &[0x90; 7]),
+        Err(MemoryError::WriteFailed { .. })
+    ));
+    assert_eq!(journal.revert_all(&snapshot).unwrap(), 1);
+    assert_eq!(
+        reader.read::<i32>(Address::new(HEAP_BASE + 0x40)).unwrap(),
+        100
+    );
+
+    // Struct accessors protect code even without an explicit policy
+    let registry = StructRegistry::from_toml(PLAYER_STRUCTS).unwrap();
+    let accessor = StructAccessor::new(&snapshot, &registry);
+    assert!(matches!(
+        accessor.write_field(
+            Address::new(CODE_BASE + 0x100),
+            registry.get("Player").unwrap(),
+            "health",
+            &MemoryValue::I32(1)
+        ),
+        Err(MemoryError::WriteProtected { .. })
+    ));
+}
+
+#[test]
+fn test_writer_refuses_read_only_process() {
+    use memory_mcp::process::PolicyAccess;
+
+    let snapshot = game_snapshot().with_policy_access(PolicyAccess::ReadOnly {
+        rule: "game.exe".to_string(),
+    });
+    let address = Address::new(HEAP_BASE + 0x40);
+    let refused = |result: Result<(), MemoryError>| match result {
+        Err(MemoryError::PermissionDenied(message)) => assert!(message.contains("game.exe")),
+        other => panic!("Expected PermissionDenied, got {:?}", other),
+    };
+
+    refused(SafeMemoryWriter::new(&snapshot).write(address, 1i32));
+    refused(BasicMemoryWriter::new(&snapshot).write(address, 2i32));
+    let journal = WriteJournal::new();
+    let mut tx = journal.begin(&snapshot, &MemoryPolicy::default(), "set health");
+    refused(tx.write_bytes(address, &[3]));
+    drop(tx);
+
+    let reader = SafeMemoryReader::new(&snapshot);
+    assert_eq!(reader.read::<i32>(address).unwrap(), 100);
+    assert!(journal.patches().is_empty());
+}
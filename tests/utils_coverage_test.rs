@@ -1,5 +1,7 @@
 //! Additional tests to improve coverage for Windows utilities
 
+#![cfg(windows)]
+
 use memory_mcp::windows::utils::error_codes::{last_error_as_memory_error, ErrorCode, WinError};
 use memory_mcp::windows::utils::string_conv::{
     extract_filename, normalize_path, string_to_wide, wide_to_string,